@@ -0,0 +1,223 @@
+//! Analysis Cache - Per-file scan results keyed by path, mtime and size
+//!
+//! Re-analyzing a large repository re-reads every source file to count lines
+//! and collect imports. The cache stores those per-file results so only files
+//! whose modification time or size changed are read again.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// Bump when the cached fields or the way they are computed change
+const CACHE_VERSION: u32 = 3;
+
+/// Directory inside an analyzed project used when no app data dir is available
+pub const PROJECT_CACHE_DIR: &str = ".vibeforge/cache";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CachedFile {
+    pub mtime_ns: u64,
+    pub size: u64,
+    pub line_count: u32,
+    pub imports: Vec<String>,
+    pub module_imports: Vec<String>,
+    /// Binary or non-UTF-8 content; kept so the file is not re-read every run
+    #[serde(default)]
+    pub binary: bool,
+}
+
+impl CachedFile {
+    pub fn binary(fingerprint: (u64, u64)) -> Self {
+        let (mtime_ns, size) = fingerprint;
+        CachedFile { mtime_ns, size, line_count: 0, imports: Vec::new(), module_imports: Vec::new(), binary: true }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheStats {
+    pub enabled: bool,
+    pub hits: usize,
+    pub misses: usize,
+    pub cache_file: Option<String>,
+    /// Why the cache file could not be written; the analysis itself is unaffected
+    pub save_error: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheFile {
+    version: u32,
+    files: HashMap<String, CachedFile>,
+}
+
+pub struct AnalysisCache {
+    path: PathBuf,
+    previous: HashMap<String, CachedFile>,
+    current: HashMap<String, CachedFile>,
+    /// Entries added since `load`, as opposed to carried over on a hit
+    inserted: usize,
+    stats: CacheStats,
+}
+
+impl AnalysisCache {
+    /// Cache file for a project under an app-level cache directory. The name is a
+    /// 64-bit FNV-1a hash of the path, so it stays the same across builds and Rust versions.
+    pub fn app_cache_file(cache_dir: &Path, project_path: &Path) -> PathBuf {
+        let hash = project_path
+            .to_string_lossy()
+            .bytes()
+            .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3));
+        cache_dir.join(format!("{:016x}.json", hash))
+    }
+
+    /// Cache file stored inside the analyzed project itself
+    pub fn project_cache_file(project_path: &Path) -> PathBuf {
+        project_path.join(PROJECT_CACHE_DIR).join("analysis.json")
+    }
+
+    /// Load a cache file; a missing, unreadable or outdated file starts empty
+    pub fn load(path: PathBuf) -> Self {
+        let previous = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<CacheFile>(&content).ok())
+            .filter(|cache| cache.version == CACHE_VERSION)
+            .map(|cache| cache.files)
+            .unwrap_or_default();
+
+        let stats = CacheStats {
+            enabled: true,
+            cache_file: Some(path.to_string_lossy().to_string()),
+            ..Default::default()
+        };
+
+        AnalysisCache {
+            path,
+            previous,
+            current: HashMap::new(),
+            inserted: 0,
+            stats,
+        }
+    }
+
+    /// `(mtime_ns, size)` used to validate an entry
+    pub fn fingerprint(metadata: &fs::Metadata) -> (u64, u64) {
+        let mtime_ns = metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+        (mtime_ns, metadata.len())
+    }
+
+    /// Cached result for a file if its mtime and size are unchanged
+    pub fn get(&mut self, relative: &str, fingerprint: (u64, u64)) -> Option<CachedFile> {
+        let (mtime_ns, size) = fingerprint;
+        match self.previous.remove(relative) {
+            Some(entry) if entry.mtime_ns == mtime_ns && entry.size == size && mtime_ns != 0 => {
+                self.stats.hits += 1;
+                self.current.insert(relative.to_string(), entry.clone());
+                Some(entry)
+            }
+            _ => {
+                self.stats.misses += 1;
+                None
+            }
+        }
+    }
+
+    pub fn insert(&mut self, relative: &str, entry: CachedFile) {
+        self.current.insert(relative.to_string(), entry);
+        self.inserted += 1;
+    }
+
+    /// Write the entries seen during this analysis; files that disappeared are dropped.
+    /// A failed write is recorded in the returned stats rather than failing the analysis.
+    pub fn save(self) -> CacheStats {
+        let AnalysisCache { path, previous, current, inserted, mut stats } = self;
+        if inserted > 0 || !previous.is_empty() {
+            stats.save_error = Self::write(&path, current).err();
+        }
+        stats
+    }
+
+    fn write(path: &Path, files: HashMap<String, CachedFile>) -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create cache directory: {}", e))?;
+        }
+        let cache = CacheFile {
+            version: CACHE_VERSION,
+            files,
+        };
+        let content = serde_json::to_string(&cache).map_err(|e| format!("Failed to serialize cache: {}", e))?;
+        fs::write(path, content).map_err(|e| format!("Failed to write cache: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis_progress::AnalysisProgress;
+    use crate::code_analyzer::CodeAnalyzer;
+
+    #[test]
+    fn test_app_cache_file_name_is_stable() {
+        let cache_dir = Path::new("cache");
+        assert_eq!(AnalysisCache::app_cache_file(cache_dir, Path::new("")), cache_dir.join("cbf29ce484222325.json"));
+        assert_eq!(AnalysisCache::app_cache_file(cache_dir, Path::new("a")), cache_dir.join("af63dc4c8601ec8c.json"));
+        assert_ne!(
+            AnalysisCache::app_cache_file(cache_dir, Path::new("/work/app")),
+            AnalysisCache::app_cache_file(cache_dir, Path::new("/work/api"))
+        );
+    }
+
+    #[test]
+    fn test_second_analysis_hits_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let project = root.join("project");
+        fs::create_dir_all(project.join("src")).unwrap();
+        fs::write(project.join("src/main.py"), "import fastapi\nprint('hi')\n").unwrap();
+        fs::write(project.join("src/util.py"), "def f():\n    return 1\n").unwrap();
+
+        let mut analyzer = CodeAnalyzer::new();
+        analyzer.enable_cache(Some(root.join("cache")));
+        let project_path = project.to_string_lossy().to_string();
+        let silent = AnalysisProgress::silent();
+
+        let first = analyzer.analyze_project(&project_path, &silent);
+        assert_eq!((first.cache_stats.hits, first.cache_stats.misses), (0, 2));
+
+        let second = analyzer.analyze_project(&project_path, &silent);
+        assert_eq!((second.cache_stats.hits, second.cache_stats.misses), (2, 0));
+        let lines: u32 = second.profile.unwrap().languages.iter().map(|l| l.line_count).sum();
+        assert_eq!(lines, 4);
+
+        // A changed size invalidates only that file
+        fs::write(project.join("src/util.py"), "def f():\n    return 10\n").unwrap();
+        let third = analyzer.analyze_project(&project_path, &silent);
+        assert_eq!((third.cache_stats.hits, third.cache_stats.misses), (1, 1));
+
+        // Binary sources are remembered too, so an unchanged tree leaves the cache file alone
+        fs::write(project.join("src/blob.py"), [0u8, 159, 146, 150]).unwrap();
+        let fourth = analyzer.analyze_project(&project_path, &silent);
+        assert_eq!((fourth.cache_stats.hits, fourth.cache_stats.misses), (2, 1));
+        let cache_file = PathBuf::from(fourth.cache_stats.cache_file.unwrap());
+        let written = fs::metadata(&cache_file).unwrap().modified().unwrap();
+        let fifth = analyzer.analyze_project(&project_path, &silent);
+        assert_eq!((fifth.cache_stats.hits, fifth.cache_stats.misses), (3, 0));
+        assert_eq!(fs::metadata(&cache_file).unwrap().modified().unwrap(), written);
+        assert!(fifth.cache_stats.save_error.is_none());
+
+        // An unwritable cache location is reported instead of failing the analysis
+        let mut blocked = CodeAnalyzer::new();
+        fs::write(root.join("blocker"), "").unwrap();
+        blocked.enable_cache(Some(root.join("blocker")));
+        let unsaved = blocked.analyze_project(&project_path, &silent);
+        assert!(unsaved.success);
+        assert!(unsaved.cache_stats.save_error.is_some());
+    }
+}
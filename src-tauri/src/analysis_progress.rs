@@ -0,0 +1,219 @@
+//! Analysis Progress - Progress events and cancellation for long analyses
+//!
+//! Both `analyze_codebase` and `analyze_project_runtime` report progress as
+//! `analysis-progress` events and can be cancelled by id through
+//! `cancel_analysis`. Analyzers poll the shared cancel flag between files.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::Emitter;
+
+pub const ANALYSIS_PROGRESS_EVENT: &str = "analysis-progress";
+
+/// Error returned by analyses stopped through `cancel_analysis`
+pub const CANCELLED_ERROR: &str = "Analysis cancelled";
+
+/// Emit a `scan` event every this many files
+const SCAN_EVENT_INTERVAL: usize = 250;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalysisProgressEvent {
+    pub analysis_id: String,
+    pub phase: String, // "scan", "dependencies", "frameworks", "recommendations", "complete", "cancelled"
+    pub directories_visited: usize,
+    pub files_scanned: usize,
+    pub message: String,
+}
+
+type ProgressEmitter = Arc<dyn Fn(AnalysisProgressEvent) + Send + Sync>;
+
+/// Progress counters and cancel flag shared by one analysis run
+#[derive(Clone)]
+pub struct AnalysisProgress {
+    analysis_id: String,
+    cancelled: Arc<AtomicBool>,
+    directories: Arc<AtomicUsize>,
+    files: Arc<AtomicUsize>,
+    emitter: Option<ProgressEmitter>,
+}
+
+impl AnalysisProgress {
+    /// Progress that is never reported and never cancelled
+    pub fn silent() -> Self {
+        AnalysisProgress {
+            analysis_id: String::new(),
+            cancelled: Arc::new(AtomicBool::new(false)),
+            directories: Arc::new(AtomicUsize::new(0)),
+            files: Arc::new(AtomicUsize::new(0)),
+            emitter: None,
+        }
+    }
+
+    pub fn new(
+        analysis_id: &str,
+        cancelled: Arc<AtomicBool>,
+        emitter: impl Fn(AnalysisProgressEvent) + Send + Sync + 'static,
+    ) -> Self {
+        AnalysisProgress {
+            analysis_id: analysis_id.to_string(),
+            cancelled,
+            emitter: Some(Arc::new(emitter)),
+            ..Self::silent()
+        }
+    }
+
+    /// Progress that emits `analysis-progress` events to every window
+    pub fn for_app(app: &tauri::AppHandle, analysis_id: &str, cancelled: Arc<AtomicBool>) -> Self {
+        let app = app.clone();
+        Self::new(analysis_id, cancelled, move |event| {
+            let _ = app.emit(ANALYSIS_PROGRESS_EVENT, event);
+        })
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub fn directory_visited(&self) {
+        self.directories.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn file_scanned(&self) {
+        let files = self.files.fetch_add(1, Ordering::Relaxed) + 1;
+        if files.is_multiple_of(SCAN_EVENT_INTERVAL) {
+            self.phase("scan", &format!("Scanned {} files...", files));
+        }
+    }
+
+    /// Report entering a phase along with the current counters
+    pub fn phase(&self, phase: &str, message: &str) {
+        if let Some(emit) = &self.emitter {
+            emit(AnalysisProgressEvent {
+                analysis_id: self.analysis_id.clone(),
+                phase: phase.to_string(),
+                directories_visited: self.directories.load(Ordering::Relaxed),
+                files_scanned: self.files.load(Ordering::Relaxed),
+                message: message.to_string(),
+            });
+        }
+    }
+}
+
+// ============================================================================
+// CANCELLATION REGISTRY
+// ============================================================================
+
+/// Cancel flags of in-flight analyses, keyed by the id the frontend passed in
+#[derive(Default)]
+pub struct AnalysisRegistry {
+    running: Mutex<HashMap<String, Arc<AtomicBool>>>,
+}
+
+impl AnalysisRegistry {
+    /// Cancel flag for a new analysis; an id that is still running is rejected
+    /// so one run cannot take over another's flag
+    pub fn register(&self, analysis_id: &str) -> Result<Arc<AtomicBool>, String> {
+        let mut running = self.running.lock().unwrap();
+        if running.contains_key(analysis_id) {
+            return Err(format!("Analysis '{}' is already running", analysis_id));
+        }
+        let flag = Arc::new(AtomicBool::new(false));
+        running.insert(analysis_id.to_string(), flag.clone());
+        Ok(flag)
+    }
+
+    pub fn finish(&self, analysis_id: &str) {
+        self.running.lock().unwrap().remove(analysis_id);
+    }
+
+    /// Flag an analysis for cancellation; false if no analysis has that id
+    pub fn cancel(&self, analysis_id: &str) -> bool {
+        match self.running.lock().unwrap().get(analysis_id) {
+            Some(flag) => {
+                flag.store(true, Ordering::Relaxed);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Id used when the caller did not supply one; events still carry it
+pub fn generated_analysis_id() -> String {
+    // The counter keeps ids unique for analyses started in the same millisecond
+    static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+    format!(
+        "analysis-{}-{}",
+        chrono::Utc::now().timestamp_millis(),
+        NEXT_ID.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+#[tauri::command]
+pub fn cancel_analysis(registry: tauri::State<'_, AnalysisRegistry>, analysis_id: String) -> Result<bool, String> {
+    Ok(registry.cancel(&analysis_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::code_analyzer::CodeAnalyzer;
+    use crate::runtime_detector::{self, RuntimeAnalysisOptions};
+
+    #[test]
+    fn test_phases_reported_in_order() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = events.clone();
+        let progress = AnalysisProgress::new("a1", Arc::new(AtomicBool::new(false)), move |event| {
+            sink.lock().unwrap().push(event.phase);
+        });
+
+        let project = env!("CARGO_MANIFEST_DIR");
+        let result = CodeAnalyzer::new().analyze_project(project, &progress);
+        assert!(result.success);
+
+        let phases = events.lock().unwrap().clone();
+        let mut milestones: Vec<&str> = phases.iter().map(|p| p.as_str()).filter(|p| *p != "scan").collect();
+        milestones.dedup();
+        assert_eq!(milestones, vec!["dependencies", "frameworks", "recommendations", "complete"]);
+        assert_eq!(phases.first().map(|p| p.as_str()), Some("scan"));
+    }
+
+    #[test]
+    fn test_runtime_phases_reported_in_order() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = events.clone();
+        let progress = AnalysisProgress::new("a3", Arc::new(AtomicBool::new(false)), move |event| {
+            sink.lock().unwrap().push(event.phase);
+        });
+
+        let options: RuntimeAnalysisOptions =
+            serde_json::from_value(serde_json::json!({ "projectPath": env!("CARGO_MANIFEST_DIR") })).unwrap();
+        runtime_detector::analyze_project(options, progress, None).unwrap();
+
+        let mut phases = events.lock().unwrap().clone();
+        phases.dedup();
+        assert_eq!(phases, vec!["scan", "dependencies", "frameworks", "recommendations", "complete"]);
+    }
+
+    #[test]
+    fn test_cancelled_analysis_stops() {
+        let registry = AnalysisRegistry::default();
+        let flag = registry.register("a2").unwrap();
+        assert!(registry.register("a2").is_err());
+        assert_ne!(generated_analysis_id(), generated_analysis_id());
+        assert!(registry.cancel("a2"));
+        assert!(!registry.cancel("unknown"));
+
+        let progress = AnalysisProgress::new("a2", flag, |_| {});
+        let result = CodeAnalyzer::new().analyze_project(env!("CARGO_MANIFEST_DIR"), &progress);
+        assert!(!result.success);
+        assert_eq!(result.error.as_deref(), Some(CANCELLED_ERROR));
+
+        registry.finish("a2");
+        assert!(!registry.cancel("a2"));
+    }
+}
@@ -0,0 +1,611 @@
+//! Architecture - Entry points, HTTP routes and service boundaries
+//!
+//! Extracts concrete structure from an analyzed project: application entry
+//! points, route definitions for file-based and decorator-based routers, and
+//! the services declared in docker-compose files with the connections between
+//! them. The result is a component graph that can be rendered as Mermaid.
+
+use crate::detection_rules::read_small_file;
+use crate::infra_detector::{self, indentation, yaml_children, yaml_key};
+use crate::relative_path::{file_name, join_path, parent_dir};
+use rayon::prelude::*;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Upper bound on source files searched for route definitions
+const MAX_ROUTE_SOURCE_FILES: usize = 5000;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchitectureProfile {
+    pub entry_points: Vec<EntryPoint>,
+    pub routes: Vec<HttpRoute>,
+    pub components: Vec<ArchitectureComponent>,
+    pub connections: Vec<ComponentConnection>,
+    /// Mermaid flowchart of `components` and `connections`
+    pub diagram: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntryPoint {
+    pub name: String,
+    pub file: String,
+    pub language: String,
+    pub kind: String, // "binary", "server", "management"
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpRoute {
+    pub method: String, // "GET", "POST", ... or "ANY"
+    pub path: String,   // parameters normalized to ":name", catch-alls to "*name"
+    pub kind: String,   // "page", "endpoint"
+    pub framework: String,
+    pub file: String,
+    pub line: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchitectureComponent {
+    pub id: String,
+    pub kind: String,   // "app", "service", "database", "cache", "broker", "proxy"
+    pub source: String, // "compose", "entry-point"
+    pub path: Option<String>,
+    pub image: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComponentConnection {
+    pub from: String,
+    pub to: String,
+    pub kind: String, // "depends_on", "link", "network"
+    pub evidence: String,
+}
+
+impl ArchitectureProfile {
+    /// Components that run application code rather than backing services
+    pub fn application_components(&self) -> impl Iterator<Item = &ArchitectureComponent> {
+        self.components.iter().filter(|c| c.kind == "app" || c.kind == "service")
+    }
+
+    /// Component graph as a Mermaid flowchart
+    pub fn to_mermaid(&self) -> String {
+        let node_id = |id: &str| -> String {
+            id.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }).collect()
+        };
+
+        let mut lines = vec!["graph LR".to_string()];
+        for component in &self.components {
+            let label = format!("{} ({})", component.id, component.kind);
+            let node = match component.kind.as_str() {
+                "database" => format!("[(\"{}\")]", label),
+                "cache" | "broker" => format!("[[\"{}\"]]", label),
+                _ => format!("[\"{}\"]", label),
+            };
+            lines.push(format!("    {}{}", node_id(&component.id), node));
+        }
+        for connection in &self.connections {
+            let arrow = if connection.kind == "depends_on" { "-->" } else { "-.->" };
+            lines.push(format!(
+                "    {} {}|{}| {}",
+                node_id(&connection.from),
+                arrow,
+                connection.kind,
+                node_id(&connection.to)
+            ));
+        }
+        lines.join("\n")
+    }
+}
+
+/// Extract entry points, routes and the component graph from a scanned project
+pub fn extract_architecture(project_path: &Path, project_files: &[String]) -> ArchitectureProfile {
+    let entry_points: Vec<EntryPoint> = project_files.iter().filter_map(|f| entry_point(f)).collect();
+    let routes = detect_routes(project_path, project_files);
+
+    let mut components = Vec::new();
+    let mut connections = Vec::new();
+    for file in project_files.iter().filter(|f| infra_detector::is_compose_file(f)) {
+        if let Some(content) = read_small_file(&project_path.join(file)) {
+            parse_compose(file, &content, &mut components, &mut connections);
+        }
+    }
+
+    // Entry points not built by a compose service are components of their own
+    for entry in &entry_points {
+        let covered = components.iter().any(|c: &ArchitectureComponent| {
+            c.path
+                .as_deref()
+                .is_some_and(|p| p.is_empty() || entry.file.starts_with(&format!("{}/", p)))
+        });
+        if !covered && !components.iter().any(|c| c.id == entry.name) {
+            components.push(ArchitectureComponent {
+                id: entry.name.clone(),
+                kind: "app".to_string(),
+                source: "entry-point".to_string(),
+                path: Some(parent_dir(&entry.file).to_string()),
+                image: None,
+            });
+        }
+    }
+
+    let mut profile = ArchitectureProfile {
+        entry_points,
+        routes,
+        components,
+        connections,
+        diagram: String::new(),
+    };
+    profile.diagram = profile.to_mermaid();
+    profile
+}
+
+// ============================================================================
+// ENTRY POINTS
+// ============================================================================
+
+fn entry_point(file: &str) -> Option<EntryPoint> {
+    let segments: Vec<&str> = file.split('/').collect();
+    let name = file_name(file);
+    let dir = parent_dir(file);
+    // Name of the directory that owns `src/`, or the file's own directory
+    let owner = |dir: &str| -> String {
+        let dir = dir.strip_suffix("/src").or_else(|| (dir == "src").then_some("")).unwrap_or(dir);
+        match file_name(dir) {
+            "" => "app".to_string(),
+            owner => owner.to_string(),
+        }
+    };
+
+    let (entry_name, language, kind) = match name {
+        "main.rs" if dir == "src" || dir.ends_with("/src") => (owner(dir), "rust", "binary"),
+        _ if name.ends_with(".rs") && (dir == "src/bin" || dir.ends_with("/src/bin")) => {
+            (name.trim_end_matches(".rs").to_string(), "rust", "binary")
+        }
+        "main.go" => match segments.as_slice() {
+            [.., "cmd", command, "main.go"] => (command.to_string(), "go", "binary"),
+            _ if segments.len() <= 2 => (owner(dir), "go", "binary"),
+            _ => return None,
+        },
+        "manage.py" => (owner(dir), "python", "management"),
+        "main.py" | "app.py" | "asgi.py" | "wsgi.py" if segments.len() <= 3 && !file.contains("test") => {
+            // `app/main.py` belongs to the project, not to a component named "app"
+            let dir = if dir == "app" { "" } else { dir.strip_suffix("/app").unwrap_or(dir) };
+            (owner(dir), "python", "server")
+        }
+        "server.js" | "server.mjs" | "server.cjs" | "server.ts" if segments.len() <= 3 => {
+            let language = if name.ends_with(".ts") { "typescript" } else { "javascript" };
+            (owner(dir), language, "server")
+        }
+        // NestJS bootstraps from src/main.ts
+        "main.ts" if dir == "src" || dir.ends_with("/src") => (owner(dir), "typescript", "server"),
+        _ => return None,
+    };
+
+    Some(EntryPoint {
+        name: entry_name,
+        file: file.to_string(),
+        language: language.to_string(),
+        kind: kind.to_string(),
+    })
+}
+
+// ============================================================================
+// ROUTES
+// ============================================================================
+
+/// Convert file-system route segments into a URL path.
+///
+/// Drops `(group)` and `@slot` segments, maps `[id]` to `:id` and
+/// `[...rest]` / `[[...rest]]` to `*rest`; SvelteKit matchers (`[id=int]`)
+/// and optional parameters (`[[lang]]`) become plain parameters.
+fn route_path(segments: &[&str]) -> String {
+    let parts: Vec<String> = segments
+        .iter()
+        .filter(|s| {
+            let group = s.starts_with('(') && s.ends_with(')');
+            !group && !s.starts_with('@')
+        })
+        .map(|s| {
+            if s.starts_with('[') && s.ends_with(']') {
+                let inner = s.trim_start_matches('[').trim_end_matches(']');
+                let inner = inner.split('=').next().unwrap_or(inner);
+                match inner.strip_prefix("...") {
+                    Some(rest) => format!("*{}", rest),
+                    None => format!(":{}", inner),
+                }
+            } else {
+                s.to_string()
+            }
+        })
+        .collect();
+    format!("/{}", parts.join("/"))
+}
+
+/// Exported HTTP handlers (`export async function GET`, `export const POST`)
+fn exported_methods(content: &str) -> Vec<(String, usize)> {
+    static HANDLER: OnceLock<Regex> = OnceLock::new();
+    let handler = HANDLER.get_or_init(|| {
+        Regex::new(r"^\s*export\s+(?:async\s+)?(?:function|const|let)\s+(GET|POST|PUT|PATCH|DELETE|OPTIONS|HEAD)\b").unwrap()
+    });
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| handler.captures(line).map(|c| (c[1].to_string(), index + 1)))
+        .collect()
+}
+
+/// Routes defined by the file layout of SvelteKit and the Next.js app router
+fn file_route(project_path: &Path, file: &str) -> Vec<HttpRoute> {
+    let segments: Vec<&str> = file.split('/').collect();
+    let name = file_name(file);
+    let stem = name.split('.').next().unwrap_or(name);
+    let is_script = [".js", ".ts", ".jsx", ".tsx"].iter().any(|ext| name.ends_with(ext));
+
+    let (framework, base, page, endpoint) = if name.starts_with('+') {
+        let Some(base) = segments.iter().position(|s| *s == "routes") else {
+            return Vec::new();
+        };
+        ("sveltekit", base, name == "+page.svelte", stem == "+server" && is_script)
+    } else if (stem == "page" || stem == "route") && is_script {
+        let Some(base) = segments.iter().position(|s| *s == "app") else {
+            return Vec::new();
+        };
+        ("nextjs", base, stem == "page", stem == "route")
+    } else {
+        return Vec::new();
+    };
+
+    let path = route_path(&segments[base + 1..segments.len() - 1]);
+    let route = |method: &str, kind: &str, line: Option<usize>| HttpRoute {
+        method: method.to_string(),
+        path: path.clone(),
+        kind: kind.to_string(),
+        framework: framework.to_string(),
+        file: file.to_string(),
+        line,
+    };
+
+    if page {
+        vec![route("GET", "page", None)]
+    } else if endpoint {
+        let methods = read_small_file(&project_path.join(file))
+            .map(|content| exported_methods(&content))
+            .unwrap_or_default();
+        if methods.is_empty() {
+            vec![route("ANY", "endpoint", None)]
+        } else {
+            methods.iter().map(|(method, line)| route(method, "endpoint", Some(*line))).collect()
+        }
+    } else {
+        Vec::new()
+    }
+}
+
+/// FastAPI/Flask decorators: `@app.get("/items")`, `@bp.route("/", methods=["POST"])`
+fn python_routes(file: &str, content: &str) -> Vec<HttpRoute> {
+    static DECORATOR: OnceLock<Regex> = OnceLock::new();
+    static METHODS: OnceLock<Regex> = OnceLock::new();
+    let decorator = DECORATOR.get_or_init(|| {
+        Regex::new(r#"^\s*@\w+\.(get|post|put|patch|delete|options|head|api_route|route)\(\s*(?:path\s*=\s*)?["']([^"']*)["'](.*)"#).unwrap()
+    });
+    let methods = METHODS.get_or_init(|| Regex::new(r#"["'](GET|POST|PUT|PATCH|DELETE|OPTIONS|HEAD)["']"#).unwrap());
+
+    let framework = if content.contains("fastapi") {
+        "fastapi"
+    } else if content.contains("flask") {
+        "flask"
+    } else {
+        return Vec::new();
+    };
+
+    let mut routes = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let Some(c) = decorator.captures(line) else {
+            continue;
+        };
+        let declared: Vec<String> = if c[1] == *"route" || c[1] == *"api_route" {
+            let listed: Vec<String> = methods.captures_iter(&c[3]).map(|m| m[1].to_string()).collect();
+            match (listed.is_empty(), &c[1]) {
+                (false, _) => listed,
+                (true, "route") => vec!["GET".to_string()],
+                (true, _) => vec!["ANY".to_string()],
+            }
+        } else {
+            vec![c[1].to_uppercase()]
+        };
+        for method in declared {
+            routes.push(HttpRoute {
+                method,
+                path: c[2].to_string(),
+                kind: "endpoint".to_string(),
+                framework: framework.to_string(),
+                file: file.to_string(),
+                line: Some(index + 1),
+            });
+        }
+    }
+    routes
+}
+
+/// Express-style routers: `router.get("/users/:id", ...)`
+fn javascript_routes(file: &str, content: &str) -> Vec<HttpRoute> {
+    static HANDLER: OnceLock<Regex> = OnceLock::new();
+    let handler = HANDLER.get_or_init(|| {
+        Regex::new(r#"\b(app|router|server|api|routes|\w+Router)\.(get|post|put|patch|delete|all|options|head)\(\s*['"`](/[^'"`]*)['"`]"#).unwrap()
+    });
+
+    let Some(framework) = ["express", "fastify", "hono", "koa-router", "@koa/router"]
+        .into_iter()
+        .find(|f| content.contains(&format!("'{}'", f)) || content.contains(&format!("\"{}\"", f)))
+    else {
+        return Vec::new();
+    };
+
+    content
+        .lines()
+        .enumerate()
+        .flat_map(|(index, line)| {
+            handler.captures_iter(line).map(move |c| HttpRoute {
+                method: if &c[2] == "all" { "ANY".to_string() } else { c[2].to_uppercase() },
+                path: c[3].to_string(),
+                kind: "endpoint".to_string(),
+                framework: framework.trim_start_matches('@').trim_end_matches("/router").trim_end_matches("-router").to_string(),
+                file: file.to_string(),
+                line: Some(index + 1),
+            })
+        })
+        .collect()
+}
+
+fn detect_routes(project_path: &Path, project_files: &[String]) -> Vec<HttpRoute> {
+    let mut routes: Vec<HttpRoute> = project_files.iter().flat_map(|f| file_route(project_path, f)).collect();
+
+    let sources: Vec<&String> = project_files
+        .iter()
+        .filter(|f| {
+            let name = file_name(f);
+            !name.starts_with('+')
+                && [".py", ".js", ".mjs", ".cjs", ".ts"].iter().any(|ext| name.ends_with(ext))
+                && !name.ends_with(".d.ts")
+        })
+        .take(MAX_ROUTE_SOURCE_FILES)
+        .collect();
+
+    let mut decorated: Vec<HttpRoute> = sources
+        .into_par_iter()
+        .flat_map_iter(|file| {
+            let Some(content) = read_small_file(&project_path.join(file)) else {
+                return Vec::new();
+            };
+            if file.ends_with(".py") {
+                python_routes(file, &content)
+            } else {
+                javascript_routes(file, &content)
+            }
+        })
+        .collect();
+    decorated.sort_by(|a, b| a.file.cmp(&b.file).then_with(|| a.line.cmp(&b.line)));
+    routes.extend(decorated);
+    routes
+}
+
+// ============================================================================
+// COMPOSE SERVICES
+// ============================================================================
+
+/// Service body with its common indentation removed, so keys sit at column 0
+fn dedent(body: &str) -> String {
+    let indent = body
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(indentation)
+        .min()
+        .unwrap_or(0);
+    body.lines()
+        .map(|l| l.get(indent..).unwrap_or("").to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn unquote(value: &str) -> &str {
+    value.trim().trim_matches(|c| c == '"' || c == '\'')
+}
+
+/// Scalar value of a top-level key
+fn scalar<'a>(body: &'a str, key: &str) -> Option<&'a str> {
+    body.lines()
+        .filter(|l| indentation(l) == 0)
+        .find(|l| yaml_key(l) == Some(key))
+        .and_then(|l| l.split_once(':'))
+        .map(|(_, v)| unquote(v))
+        .filter(|v| !v.is_empty())
+}
+
+/// Child lines under a top-level key, with list markers stripped
+fn section_lines<'a>(body: &'a str, key: &str) -> Vec<&'a str> {
+    let mut in_key = false;
+    let mut child_indent = None;
+    let mut lines = Vec::new();
+    for line in body.lines().filter(|l| !l.trim().is_empty()) {
+        let indent = indentation(line);
+        if indent == 0 {
+            in_key = yaml_key(line) == Some(key);
+            child_indent = None;
+        } else if in_key && indent == *child_indent.get_or_insert(indent) {
+            let line = line.trim();
+            lines.push(line.strip_prefix("- ").unwrap_or(line));
+        }
+    }
+    lines
+}
+
+/// Entries of a list (`- db`), mapping (`db:`) or inline list (`[db, cache]`) under a key
+fn items(body: &str, key: &str) -> Vec<String> {
+    if let Some(inline) = scalar(body, key) {
+        return inline
+            .trim_matches(|c| c == '[' || c == ']')
+            .split(',')
+            .map(|v| unquote(v).to_string())
+            .filter(|v| !v.is_empty())
+            .collect();
+    }
+    section_lines(body, key)
+        .into_iter()
+        .map(|line| match yaml_key(line) {
+            Some(key) if line.trim_end().ends_with(':') => key.to_string(),
+            _ => unquote(line).to_string(),
+        })
+        .collect()
+}
+
+fn image_kind(image: &str) -> &'static str {
+    let name = image.rsplit('/').next().unwrap_or(image).split(':').next().unwrap_or(image);
+    const KINDS: &[(&str, &[&str])] = &[
+        ("database", &["postgres", "postgis", "mysql", "mariadb", "mongo", "cockroach", "clickhouse", "cassandra", "couchdb", "neo4j", "elasticsearch", "opensearch", "timescale", "surrealdb"]),
+        ("cache", &["redis", "valkey", "memcached", "keydb", "dragonfly"]),
+        ("broker", &["rabbitmq", "kafka", "nats", "redpanda", "activemq", "pulsar", "mosquitto", "zookeeper"]),
+        ("proxy", &["nginx", "traefik", "caddy", "haproxy", "envoy"]),
+    ];
+    KINDS
+        .iter()
+        .find(|(_, names)| names.iter().any(|n| name.starts_with(n)))
+        .map_or("service", |(kind, _)| kind)
+}
+
+fn parse_compose(
+    file: &str,
+    content: &str,
+    components: &mut Vec<ArchitectureComponent>,
+    connections: &mut Vec<ComponentConnection>,
+) {
+    static URL_HOST: OnceLock<Regex> = OnceLock::new();
+    let url_host = URL_HOST.get_or_init(|| Regex::new(r"[a-z][a-z0-9+.-]*://(?:[^@/\s]*@)?([A-Za-z0-9_.-]+)").unwrap());
+
+    let services: Vec<(String, String)> = yaml_children(content, "services")
+        .into_iter()
+        .map(|(name, body)| (name.to_string(), dedent(&body)))
+        .collect();
+    let names: Vec<&str> = services.iter().map(|(name, _)| name.as_str()).collect();
+
+    for (name, body) in &services {
+        let image = scalar(body, "image").map(|i| i.to_string());
+        // `build: ./api` or a `build:` mapping with `context: ./api`
+        let context = scalar(body, "build").map(|c| c.to_string()).or_else(|| {
+            section_lines(body, "build")
+                .into_iter()
+                .find(|l| yaml_key(l) == Some("context"))
+                .and_then(|l| l.split_once(':'))
+                .map(|(_, v)| unquote(v).to_string())
+        });
+        let kind = match (&image, &context) {
+            (_, Some(_)) => "service",
+            (Some(image), None) => image_kind(image),
+            (None, None) => "service",
+        };
+        if !components.iter().any(|c| c.id == *name) {
+            components.push(ArchitectureComponent {
+                id: name.clone(),
+                kind: kind.to_string(),
+                source: "compose".to_string(),
+                path: context.map(|c| join_path(parent_dir(file), &c)),
+                image,
+            });
+        }
+
+        let mut connect = |to: &str, kind: &str, evidence: String| {
+            let exists = connections.iter().any(|c| c.from == *name && c.to == to);
+            if to != name && names.contains(&to) && !exists {
+                connections.push(ComponentConnection {
+                    from: name.clone(),
+                    to: to.to_string(),
+                    kind: kind.to_string(),
+                    evidence,
+                });
+            }
+        };
+        for dependency in items(body, "depends_on") {
+            connect(&dependency, "depends_on", format!("{}: services.{}.depends_on", file, name));
+        }
+        for link in items(body, "links") {
+            let target = link.split(':').next().unwrap_or(&link).to_string();
+            connect(&target, "link", format!("{}: services.{}.links", file, name));
+        }
+        // Connection strings that address another service by hostname
+        for variable in section_lines(body, "environment") {
+            let value = variable.split_once(['=', ':']).map_or("", |(_, v)| v);
+            let host = url_host
+                .captures(value)
+                .map(|c| c[1].to_string())
+                .or_else(|| names.iter().find(|n| unquote(value) == **n).map(|n| n.to_string()));
+            if let Some(host) = host {
+                let key = variable.split(['=', ':']).next().unwrap_or(variable).trim().to_string();
+                connect(&host, "network", format!("{}: services.{}.environment.{}", file, name, key));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_based_route_paths() {
+        assert_eq!(route_path(&["blog", "[slug]"]), "/blog/:slug");
+        assert_eq!(route_path(&["(marketing)", "docs", "[...path]"]), "/docs/*path");
+        assert_eq!(route_path(&["[[lang]]", "items", "[id=integer]"]), "/:lang/items/:id");
+        assert_eq!(route_path(&[]), "/");
+    }
+
+    #[test]
+    fn test_decorator_and_router_routes() {
+        let python = "from fastapi import APIRouter\nrouter = APIRouter()\n\n@router.get(\"/items/{id}\")\nasync def item(id: int): ...\n@router.api_route(\"/hook\", methods=[\"POST\", \"PUT\"])\ndef hook(): ...\n";
+        let routes: Vec<(String, String)> = python_routes("api/items.py", python)
+            .into_iter()
+            .map(|r| (r.method, r.path))
+            .collect();
+        assert_eq!(
+            routes,
+            vec![
+                ("GET".to_string(), "/items/{id}".to_string()),
+                ("POST".to_string(), "/hook".to_string()),
+                ("PUT".to_string(), "/hook".to_string()),
+            ]
+        );
+
+        let javascript = "const express = require('express');\nconst router = express.Router();\nrouter.post('/users', create);\naxios.get('/elsewhere');\n";
+        let routes = javascript_routes("src/users.js", javascript);
+        assert_eq!(routes.len(), 1);
+        assert_eq!((routes[0].method.as_str(), routes[0].line), ("POST", Some(3)));
+    }
+
+    #[test]
+    fn test_compose_component_graph() {
+        let compose = "services:\n  web:\n    build: ./web\n    depends_on:\n      - api\n  api:\n    build:\n      context: ./api\n    environment:\n      DATABASE_URL: postgres://app:secret@db:5432/app\n      CACHE_HOST: cache\n    depends_on:\n      db:\n        condition: service_healthy\n  db:\n    image: postgres:16\n  cache:\n    image: redis:7\n";
+        let mut components = Vec::new();
+        let mut connections = Vec::new();
+        parse_compose("docker-compose.yml", compose, &mut components, &mut connections);
+
+        let kinds: Vec<(&str, &str)> = components.iter().map(|c| (c.id.as_str(), c.kind.as_str())).collect();
+        assert_eq!(kinds, vec![("web", "service"), ("api", "service"), ("db", "database"), ("cache", "cache")]);
+        assert_eq!(components[1].path.as_deref(), Some("api"));
+
+        let edges: Vec<(&str, &str, &str)> = connections
+            .iter()
+            .map(|c| (c.from.as_str(), c.to.as_str(), c.kind.as_str()))
+            .collect();
+        assert_eq!(
+            edges,
+            vec![("web", "api", "depends_on"), ("api", "db", "depends_on"), ("api", "cache", "network")]
+        );
+
+        let profile = ArchitectureProfile {
+            components,
+            connections,
+            ..Default::default()
+        };
+        assert!(profile.to_mermaid().contains("web -->|depends_on| api"));
+    }
+}
@@ -0,0 +1,3313 @@
+//! Auth Scaffold - Authentication code for generated projects
+//!
+//! Renders a runnable sign-in flow for the selected method: routes, a guard
+//! for protected endpoints, password hashing, refresh, the user model and
+//! tests. Node stacks share one framework-neutral core written against the
+//! Fetch API `Request`/`Response`, with a thin adapter per framework; the
+//! other ecosystems get idiomatic code on their own auth libraries.
+
+use crate::database_scaffold::{self, column, DatabaseSpec, Schema, Table};
+
+// ============================================================================
+// METHODS
+// ============================================================================
+
+pub struct AuthSpec {
+    pub id: &'static str, // "jwt", "session", "oauth", "webauthn"
+    pub name: &'static str,
+    /// Variables the generated code reads, with development defaults
+    pub env: &'static [(&'static str, &'static str)],
+}
+
+pub const AUTH_METHODS: &[AuthSpec] = &[
+    AuthSpec {
+        id: "jwt",
+        name: "JWT",
+        env: &[("JWT_SECRET", "change-me-to-a-long-random-string")],
+    },
+    AuthSpec {
+        id: "session",
+        name: "Server sessions",
+        env: &[],
+    },
+    AuthSpec {
+        id: "oauth",
+        name: "OAuth",
+        // Without OAUTH_ISSUER the generated code signs in through its mock provider
+        env: &[("OAUTH_ISSUER", ""), ("OAUTH_CLIENT_ID", "dev-client"), ("OAUTH_CLIENT_SECRET", "dev-secret")],
+    },
+    AuthSpec {
+        id: "webauthn",
+        name: "Passkeys (WebAuthn)",
+        env: &[("WEBAUTHN_RP_ID", "localhost")],
+    },
+];
+
+/// Look up an authentication selection; `None`, "none" and unknown ids have no spec
+pub fn auth_spec(authentication: Option<&str>) -> Option<&'static AuthSpec> {
+    let id = authentication?;
+    AUTH_METHODS.iter().find(|auth| auth.id == id)
+}
+
+impl AuthSpec {
+    /// Whether sign-in ends in a server session cookie rather than tokens
+    pub fn uses_sessions(&self) -> bool {
+        self.id != "jwt"
+    }
+
+    /// Whether users sign in with an email and password
+    pub fn uses_passwords(&self) -> bool {
+        matches!(self.id, "jwt" | "session")
+    }
+
+    /// `.env.example` block; redirect-based methods also need the public `app_url`.
+    /// Empty for methods without settings.
+    pub fn env_block(&self, app_url: &str) -> String {
+        if self.env.is_empty() {
+            return String::new();
+        }
+        let mut block = format!("\n# Authentication ({})\n", self.name);
+        if matches!(self.id, "oauth" | "webauthn") {
+            block.push_str(&format!("APP_URL={}\n", app_url));
+        }
+        if self.id == "oauth" {
+            block.push_str("# Leave OAUTH_ISSUER empty to sign in through the built-in mock provider\n");
+        }
+        for (key, value) in self.env {
+            block.push_str(&format!("{}={}\n", key, value));
+        }
+        block
+    }
+}
+
+/// A generated file, relative to the project (or module) root
+pub type AuthFile = (String, String);
+
+fn file(path: impl Into<String>, content: impl Into<String>) -> AuthFile {
+    (path.into(), content.into())
+}
+
+// ============================================================================
+// NODE CORE
+// ============================================================================
+
+/// Columns of a stack's users table the Node core reads and writes
+pub struct UserColumns {
+    pub name: &'static str,
+    /// Password hash; accounts without a password get an unusable "!"
+    pub password: &'static str,
+    /// Unique username the schema requires, filled with the email
+    pub username: Option<&'static str>,
+    pub created: &'static str,
+}
+
+/// Database the Node core keeps users, sessions, refresh tokens and passkeys in
+pub struct NodeAuthDatabase<'a> {
+    pub db: &'a DatabaseSpec,
+    /// Module exporting the stack's `db` connection, relative to the auth directory
+    pub connection: &'a str,
+    pub users: &'a UserColumns,
+}
+
+/// Table behind sessions, refresh tokens and passkeys: JSON values by
+/// prefixed key, so one migration covers every auth method
+const AUTH_RECORDS: Schema = Schema {
+    tables: &[Table {
+        name: "auth_records",
+        columns: &[column("id", "key", true, false), column("data", "text", true, false)],
+    }],
+    seeds: &[],
+};
+
+/// Framework-neutral auth core under `dir`, shared by the Node stacks.
+/// `app_url` is where the app is served in development. Without a
+/// `database` users and sessions live in memory.
+pub fn node_core_files(
+    auth: &AuthSpec,
+    dir: &str,
+    app_url: &str,
+    app_name: &str,
+    database: Option<&NodeAuthDatabase>,
+) -> Vec<AuthFile> {
+    let path = |name: &str| format!("{}/{}", dir, name);
+    let mut files = vec![
+        file(
+            path("config.js"),
+            format!(
+                "// Development defaults; every value can be overridden from the environment\nexport const APP_URL = process.env.APP_URL ?? '{}';\nexport const APP_NAME = process.env.APP_NAME ?? '{}';\n",
+                app_url, app_name
+            ),
+        ),
+        file(path("http.js"), NODE_HTTP),
+        file(path("memory.js"), NODE_MEMORY),
+        file(path("store.js"), node_store(database)),
+        file(path("users.js"), NODE_USERS),
+    ];
+    if let Some(database) = database {
+        files.push(file(
+            database.db.migration_path("0002_auth"),
+            database_scaffold::migration(database.db, &AUTH_RECORDS, "Auth records"),
+        ));
+    }
+    if auth.uses_passwords() {
+        files.push(file(path("password.js"), NODE_PASSWORD));
+    }
+    if auth.uses_sessions() {
+        files.push(file(path("sessions.js"), NODE_SESSIONS));
+    }
+
+    let (handlers, test) = match auth.id {
+        "session" => (NODE_SESSION_HANDLERS, NODE_SESSION_TEST),
+        "oauth" => {
+            files.push(file(path("oauth.js"), NODE_OAUTH));
+            files.push(file(path("mock-provider.js"), NODE_MOCK_PROVIDER));
+            (NODE_OAUTH_HANDLERS, NODE_OAUTH_TEST)
+        }
+        "webauthn" => {
+            files.push(file(path("webauthn.js"), NODE_WEBAUTHN));
+            (NODE_WEBAUTHN_HANDLERS, NODE_WEBAUTHN_TEST)
+        }
+        _ => {
+            files.push(file(path("tokens.js"), NODE_TOKENS));
+            (NODE_JWT_HANDLERS, NODE_JWT_TEST)
+        }
+    };
+    files.push(file(path("handlers.js"), handlers));
+    files.push(file(path("guard.js"), if auth.uses_sessions() { NODE_SESSION_GUARD } else { NODE_JWT_GUARD }));
+    files.push(file(path("auth.test.js"), test));
+    files
+}
+
+/// npm dependencies the Node core needs beyond the platform
+pub fn node_dependencies(auth: &AuthSpec) -> &'static [(&'static str, &'static str)] {
+    match auth.id {
+        "webauthn" => &[("@simplewebauthn/server", "^9.0.3")],
+        _ => &[],
+    }
+}
+
+const NODE_HTTP: &str = r#"// Helpers over the Fetch API Request/Response shared by every auth handler
+
+/** JSON response */
+export function json(body, init = {}) {
+  const headers = new Headers(init.headers);
+  headers.set('content-type', 'application/json');
+  return new Response(JSON.stringify(body), { ...init, headers });
+}
+
+export function error(status, message) {
+  return json({ error: message }, { status });
+}
+
+/** Request body as JSON; an empty or malformed body reads as {} */
+export async function readJson(request) {
+  try {
+    return await request.json();
+  } catch {
+    return {};
+  }
+}
+
+export function readCookie(request, name) {
+  for (const part of (request.headers.get('cookie') ?? '').split(';')) {
+    const [key, ...value] = part.trim().split('=');
+    if (key === name) return decodeURIComponent(value.join('='));
+  }
+  return null;
+}
+
+/** HttpOnly cookie; a max age of 0 clears it */
+export function cookie(name, value, maxAge) {
+  const secure = process.env.NODE_ENV === 'production' ? '; Secure' : '';
+  return `${name}=${encodeURIComponent(value)}; Path=/; HttpOnly; SameSite=Lax; Max-Age=${maxAge}${secure}`;
+}
+
+export function withCookies(response, cookies) {
+  for (const value of cookies) response.headers.append('set-cookie', value);
+  return response;
+}
+
+export function redirect(location, cookies = []) {
+  return withCookies(new Response(null, { status: 302, headers: { location } }), cookies);
+}
+"#;
+
+const NODE_MEMORY: &str = r#"// Maps on globalThis: they survive dev-server reloads but not restarts.
+// Pending challenges and codes always live here; users and sessions only
+// when no database is selected.
+
+/** @returns {Map<any, any>} */
+export function memoryStore(name) {
+  const key = Symbol.for(`auth.${name}`);
+  globalThis[key] ??= new Map();
+  return globalThis[key];
+}
+"#;
+
+/// `store.js`: users, plus sessions, refresh tokens and passkeys as records
+/// by key. Backed by the database when one is selected, otherwise by memory.
+fn node_store(database: Option<&NodeAuthDatabase>) -> String {
+    let Some(database) = database else {
+        return format!(
+            "// No database is selected, so users and sessions are lost on restart\n{}\nexport const {{ insertUser, findUser, getRecord, putRecord, deleteRecord }} = memory;\n",
+            NODE_MEMORY_BACKEND
+        );
+    };
+    let users = database.users;
+    let (username_column, username_value) = match users.username {
+        Some(column) => (format!("{}, ", column), "email, "),
+        None => (String::new(), ""),
+    };
+    let backend = if database.db.is_document_store() {
+        format!(
+            r#"const connection = () => import('{connection}');
+const collection = async (name) => (await connection()).db.collection(name);
+
+// Seeded users have numeric ids, the app's own get ObjectIds
+const userKey = (id) => (/^[0-9a-f]{{24}}$/i.test(id) ? new ObjectId(id) : Number(id));
+const toUser = (document) => ({{
+  id: String(document._id),
+  email: document.email,
+  name: document.{name} ?? '',
+  passwordHash: document.{password} === NO_PASSWORD ? null : document.{password},
+  createdAt: new Date(document.{created} ?? Date.now()).toISOString(),
+}});
+
+const database = {{
+  async insertUser({{ email, name, passwordHash }}) {{
+    const document = {{ email, {username_field}{name_field}, {password}: passwordHash ?? NO_PASSWORD, {created}: new Date() }};
+    const {{ insertedId }} = await (await collection('users')).insertOne(document);
+    return toUser({{ ...document, _id: insertedId }});
+  }},
+  async findUser(field, value) {{
+    const filter = field === 'id' ? {{ _id: userKey(String(value)) }} : {{ email: value }};
+    const document = await (await collection('users')).findOne(filter);
+    return document ? toUser(document) : null;
+  }},
+  async getRecord(key) {{
+    return (await (await collection('auth_records')).findOne({{ _id: key }}))?.data ?? null;
+  }},
+  async putRecord(key, value) {{
+    await (await collection('auth_records')).updateOne({{ _id: key }}, {{ $set: {{ data: value }} }}, {{ upsert: true }});
+  }},
+  async deleteRecord(key) {{
+    await (await collection('auth_records')).deleteOne({{ _id: key }});
+  }},
+}};
+"#,
+            connection = database.connection,
+            name = users.name,
+            password = users.password,
+            created = users.created,
+            username_field = users.username.map_or(String::new(), |column| format!("{}: email, ", column)),
+            name_field = if users.name == "name" { "name".to_string() } else { format!("{}: name", users.name) },
+        )
+    } else {
+        let (run, upsert) = match database.db.id {
+            "mysql" => (
+                "  const [rows] = await db.execute(sql, params);\n  return rows;",
+                "INSERT INTO auth_records (id, data) VALUES (?, ?) ON DUPLICATE KEY UPDATE data = VALUES(data)",
+            ),
+            "sqlite" => (
+                "  const statement = db.prepare(sql);\n  if (statement.reader) return statement.all(...params);\n  statement.run(...params);\n  return [];",
+                "INSERT INTO auth_records (id, data) VALUES (?, ?) ON CONFLICT (id) DO UPDATE SET data = excluded.data",
+            ),
+            _ => (
+                "  let index = 0;\n  const { rows } = await db.query(sql.replace(/\\?/g, () => `$${++index}`), params);\n  return rows;",
+                "INSERT INTO auth_records (id, data) VALUES (?, ?) ON CONFLICT (id) DO UPDATE SET data = excluded.data",
+            ),
+        };
+        format!(
+            r#"const connection = () => import('{connection}');
+
+/** Rows of a statement with `?` placeholders */
+async function query(sql, params) {{
+  const {{ db }} = await connection();
+{run}
+}}
+
+const toDate = (value) => (value instanceof Date ? value : new Date(`${{String(value).replace(' ', 'T')}}Z`));
+const toUser = (row) => ({{
+  id: String(row.id),
+  email: row.email,
+  name: row.{name} ?? '',
+  passwordHash: row.{password} === NO_PASSWORD ? null : row.{password},
+  createdAt: toDate(row.{created}).toISOString(),
+}});
+
+const database = {{
+  async insertUser({{ email, name, passwordHash }}) {{
+    await query('INSERT INTO users (email, {username_column}{name}, {password}) VALUES (?, {placeholders}?, ?)', [email, {username_value}name, passwordHash ?? NO_PASSWORD]);
+    return database.findUser('email', email);
+  }},
+  async findUser(field, value) {{
+    // Ids are numeric; anything else, like an id from the memory store, matches no row
+    if (field === 'id' && !/^\d+$/.test(String(value))) return null;
+    const [row] = await query(`SELECT * FROM users WHERE ${{field === 'id' ? 'id' : 'email'}} = ?`, [value]);
+    return row ? toUser(row) : null;
+  }},
+  async getRecord(key) {{
+    const [row] = await query('SELECT data FROM auth_records WHERE id = ?', [key]);
+    return row ? JSON.parse(row.data) : null;
+  }},
+  async putRecord(key, value) {{
+    await query('{upsert}', [key, JSON.stringify(value)]);
+  }},
+  async deleteRecord(key) {{
+    await query('DELETE FROM auth_records WHERE id = ?', [key]);
+  }},
+}};
+"#,
+            connection = database.connection,
+            run = run,
+            name = users.name,
+            password = users.password,
+            created = users.created,
+            username_column = username_column,
+            username_value = username_value,
+            placeholders = if users.username.is_some() { "?, " } else { "" },
+            upsert = upsert,
+        )
+    };
+    format!(
+        r#"// Users live in the `users` table, sessions, refresh tokens and passkeys in
+// `auth_records` (db/migrations/0002_auth.{ext}). The connection is loaded on
+// first use, so AUTH_STORE=memory runs without a database, as the tests do.
+{import}{memory}
+// Accounts without a password get an unusable hash, like the seeded ones
+const NO_PASSWORD = '!';
+
+{backend}
+const backend = () => (process.env.AUTH_STORE === 'memory' ? memory : database);
+
+export const insertUser = (user) => backend().insertUser(user);
+export const findUser = (field, value) => backend().findUser(field, value);
+export const getRecord = (key) => backend().getRecord(key);
+export const putRecord = (key, value) => backend().putRecord(key, value);
+export const deleteRecord = (key) => backend().deleteRecord(key);
+"#,
+        ext = if database.db.is_document_store() { "json" } else { "sql" },
+        import = if database.db.is_document_store() { "import { ObjectId } from 'mongodb';\n" } else { "" },
+        memory = NODE_MEMORY_BACKEND,
+        backend = backend,
+    )
+}
+
+const NODE_MEMORY_BACKEND: &str = r#"import { randomUUID } from 'node:crypto';
+import { memoryStore } from './memory.js';
+
+const users = memoryStore('users');
+const records = memoryStore('records');
+
+const memory = {
+  async insertUser({ email, name, passwordHash }) {
+    const user = { id: randomUUID(), email, name, passwordHash, createdAt: new Date().toISOString() };
+    users.set(user.id, user);
+    return user;
+  },
+  async findUser(field, value) {
+    for (const user of users.values()) {
+      if (user[field] === value) return user;
+    }
+    return null;
+  },
+  async getRecord(key) {
+    return records.get(key) ?? null;
+  },
+  async putRecord(key, value) {
+    records.set(key, value);
+  },
+  async deleteRecord(key) {
+    records.delete(key);
+  },
+};
+"#;
+
+const NODE_USERS: &str = r#"import { findUser, insertUser } from './store.js';
+
+/**
+ * @typedef {object} User
+ * @property {string} id
+ * @property {string} email
+ * @property {string} name
+ * @property {string | null} passwordHash  null for accounts without a password
+ * @property {string} createdAt
+ */
+
+/** @returns {Promise<User>} */
+export function createUser({ email, name = '', passwordHash = null }) {
+  return insertUser({ email: String(email).trim().toLowerCase(), name, passwordHash });
+}
+
+/** @returns {Promise<User | null>} */
+export async function findUserById(id) {
+  return id ? findUser('id', String(id)) : null;
+}
+
+/** @returns {Promise<User | null>} */
+export async function findUserByEmail(email) {
+  return findUser('email', String(email).trim().toLowerCase());
+}
+
+/** The user without secrets, safe to send to clients */
+export function publicUser({ id, email, name, createdAt }) {
+  return { id, email, name, createdAt };
+}
+"#;
+
+const NODE_PASSWORD: &str = r#"import { randomBytes, scrypt, timingSafeEqual } from 'node:crypto';
+import { promisify } from 'node:util';
+
+const scryptAsync = promisify(scrypt);
+const KEY_LENGTH = 64;
+
+/** Salted scrypt hash, stored as `scrypt$<salt>$<key>` */
+export async function hashPassword(password) {
+  const salt = randomBytes(16);
+  const key = await scryptAsync(password, salt, KEY_LENGTH);
+  return `scrypt$${salt.toString('base64url')}$${key.toString('base64url')}`;
+}
+
+export async function verifyPassword(password, stored) {
+  const [scheme, salt, key] = String(stored).split('$');
+  if (scheme !== 'scrypt' || !salt || !key) return false;
+  const expected = Buffer.from(key, 'base64url');
+  const actual = await scryptAsync(String(password), Buffer.from(salt, 'base64url'), expected.length);
+  return timingSafeEqual(expected, actual);
+}
+"#;
+
+const NODE_TOKENS: &str = r#"import { createHash, createHmac, randomBytes, timingSafeEqual } from 'node:crypto';
+import { deleteRecord, getRecord, putRecord } from './store.js';
+
+const SECRET = process.env.JWT_SECRET ?? 'dev-secret-change-me';
+if (process.env.NODE_ENV === 'production' && !process.env.JWT_SECRET) {
+  throw new Error('JWT_SECRET must be set in production');
+}
+
+export const ACCESS_TOKEN_TTL = 15 * 60;
+const REFRESH_TOKEN_TTL = 30 * 24 * 60 * 60;
+
+const encode = (value) => Buffer.from(JSON.stringify(value)).toString('base64url');
+const signature = (data) => createHmac('sha256', SECRET).update(data).digest('base64url');
+
+/** Short-lived HS256 access token */
+export function signAccessToken(user) {
+  const now = Math.floor(Date.now() / 1000);
+  const data = `${encode({ alg: 'HS256', typ: 'JWT' })}.${encode({ sub: user.id, email: user.email, iat: now, exp: now + ACCESS_TOKEN_TTL })}`;
+  return `${data}.${signature(data)}`;
+}
+
+/** Claims of a valid, unexpired access token, or null */
+export function verifyAccessToken(token) {
+  const [header, payload, sig] = String(token).split('.');
+  if (!header || !payload || !sig) return null;
+  const expected = Buffer.from(signature(`${header}.${payload}`));
+  const actual = Buffer.from(sig);
+  if (expected.length !== actual.length || !timingSafeEqual(expected, actual)) return null;
+  const claims = JSON.parse(Buffer.from(payload, 'base64url').toString());
+  return claims.exp > Date.now() / 1000 ? claims : null;
+}
+
+// Refresh tokens are opaque and stored hashed. Each one is spent on use and
+// replaced, so a stolen token stops working once the owner refreshes.
+const refreshKey = (token) => `refresh:${createHash('sha256').update(String(token)).digest('hex')}`;
+
+export async function issueRefreshToken(userId) {
+  const token = randomBytes(32).toString('base64url');
+  await putRecord(refreshKey(token), { userId, expiresAt: Date.now() + REFRESH_TOKEN_TTL * 1000 });
+  return token;
+}
+
+/** Spend a refresh token; returns its user and a replacement, or null */
+export async function rotateRefreshToken(token) {
+  const entry = await getRecord(refreshKey(token));
+  await deleteRecord(refreshKey(token));
+  if (!entry || entry.expiresAt < Date.now()) return null;
+  return { userId: entry.userId, refreshToken: await issueRefreshToken(entry.userId) };
+}
+
+export async function revokeRefreshToken(token) {
+  await deleteRecord(refreshKey(token));
+}
+"#;
+
+const NODE_SESSIONS: &str = r#"import { randomBytes } from 'node:crypto';
+import { cookie, readCookie } from './http.js';
+import { deleteRecord, getRecord, putRecord } from './store.js';
+
+export const SESSION_COOKIE = 'session';
+const SESSION_TTL = 7 * 24 * 60 * 60;
+
+const sessionKey = (id) => `session:${id}`;
+
+export async function createSession(userId) {
+  const id = randomBytes(32).toString('base64url');
+  await putRecord(sessionKey(id), { userId, expiresAt: Date.now() + SESSION_TTL * 1000 });
+  return id;
+}
+
+/** User id behind the request's session cookie, or null */
+export async function sessionUserId(request) {
+  const id = readCookie(request, SESSION_COOKIE);
+  const session = id ? await getRecord(sessionKey(id)) : null;
+  if (!session) return null;
+  if (session.expiresAt < Date.now()) {
+    await deleteRecord(sessionKey(id));
+    return null;
+  }
+  return session.userId;
+}
+
+/** Replace the session id and restart its lifetime; returns the new id or null */
+export async function refreshSession(request) {
+  const userId = await sessionUserId(request);
+  if (!userId) return null;
+  await deleteRecord(sessionKey(readCookie(request, SESSION_COOKIE)));
+  return createSession(userId);
+}
+
+export async function destroySession(request) {
+  const id = readCookie(request, SESSION_COOKIE);
+  if (id) await deleteRecord(sessionKey(id));
+}
+
+export const sessionCookie = (id) => cookie(SESSION_COOKIE, id, SESSION_TTL);
+export const clearSessionCookie = () => cookie(SESSION_COOKIE, '', 0);
+"#;
+
+const NODE_OAUTH: &str = r#"import { createHash, randomBytes } from 'node:crypto';
+import { APP_URL } from './config.js';
+
+/** Provider base URL; without OAUTH_ISSUER the mock provider below is used */
+const ISSUER = process.env.OAUTH_ISSUER || `${APP_URL}/api/auth/mock`;
+const CLIENT_ID = process.env.OAUTH_CLIENT_ID ?? 'dev-client';
+const CLIENT_SECRET = process.env.OAUTH_CLIENT_SECRET ?? 'dev-secret';
+const REDIRECT_URI = `${APP_URL}/api/auth/oauth/callback`;
+
+const s256 = (value) => createHash('sha256').update(String(value)).digest('base64url');
+
+/** Authorization URL plus the state and PKCE verifier to keep until the callback */
+export function authorizationRequest() {
+  const state = randomBytes(16).toString('base64url');
+  const verifier = randomBytes(32).toString('base64url');
+  const url = new URL(`${ISSUER}/authorize`);
+  url.search = new URLSearchParams({
+    response_type: 'code',
+    client_id: CLIENT_ID,
+    redirect_uri: REDIRECT_URI,
+    scope: 'openid email profile',
+    state,
+    code_challenge: s256(verifier),
+    code_challenge_method: 'S256',
+  }).toString();
+  return { url: url.toString(), state, verifier };
+}
+
+/** Exchange the authorization code, then fetch the provider's profile */
+export async function fetchProfile(code, verifier) {
+  const tokenResponse = await fetch(`${ISSUER}/token`, {
+    method: 'POST',
+    headers: { 'content-type': 'application/x-www-form-urlencoded' },
+    body: new URLSearchParams({
+      grant_type: 'authorization_code',
+      code,
+      redirect_uri: REDIRECT_URI,
+      client_id: CLIENT_ID,
+      client_secret: CLIENT_SECRET,
+      code_verifier: verifier,
+    }),
+  });
+  if (!tokenResponse.ok) throw new Error(`Token exchange failed with ${tokenResponse.status}`);
+  const { access_token: accessToken } = await tokenResponse.json();
+
+  const profileResponse = await fetch(`${ISSUER}/userinfo`, { headers: { authorization: `Bearer ${accessToken}` } });
+  if (!profileResponse.ok) throw new Error(`Userinfo request failed with ${profileResponse.status}`);
+  return profileResponse.json();
+}
+"#;
+
+const NODE_MOCK_PROVIDER: &str = r#"// OAuth provider for development. It approves every request as the same demo
+// user, so the whole redirect flow runs without registering a real app.
+import { createHash, randomBytes } from 'node:crypto';
+import { APP_URL } from './config.js';
+import { error, json, redirect } from './http.js';
+import { memoryStore } from './memory.js';
+
+const codes = memoryStore('oauth.codes');
+const accessTokens = memoryStore('oauth.accessTokens');
+
+const s256 = (value) => createHash('sha256').update(String(value)).digest('base64url');
+
+/** Never in production, and only until a real provider is configured */
+export const mockEnabled = () => process.env.NODE_ENV !== 'production' && !process.env.OAUTH_ISSUER;
+
+/**
+ * Serve the provider's authorize, token and userinfo endpoints
+ * @param {string} path  relative to the provider's base URL
+ * @param {Request} request
+ */
+export async function handleMockProvider(path, request) {
+  if (!mockEnabled()) return error(404, 'Not found');
+  switch (`${request.method} ${path}`) {
+    case 'GET authorize': {
+      const target = authorize(new URL(request.url));
+      return target ? redirect(target) : error(400, 'Invalid redirect_uri');
+    }
+    case 'POST token': {
+      const token = issueToken(new URLSearchParams(await request.text()));
+      return token ? json(token) : json({ error: 'invalid_grant' }, { status: 400 });
+    }
+    case 'GET userinfo': {
+      const profile = userinfo(request);
+      return profile ? json(profile) : error(401, 'Invalid access token');
+    }
+    default:
+      return error(404, 'Not found');
+  }
+}
+
+/** Redirect target carrying a fresh code, or null for a foreign redirect URI */
+function authorize(url) {
+  const params = url.searchParams;
+  const redirectUri = params.get('redirect_uri') ?? '';
+  if (!URL.canParse(redirectUri) || new URL(redirectUri).origin !== new URL(APP_URL).origin) return null;
+
+  const code = randomBytes(16).toString('base64url');
+  codes.set(code, { redirectUri, challenge: params.get('code_challenge') });
+  const target = new URL(redirectUri);
+  target.searchParams.set('code', code);
+  target.searchParams.set('state', params.get('state') ?? '');
+  return target.toString();
+}
+
+/** Token response for a valid code and PKCE verifier, or null */
+function issueToken(form) {
+  const code = form.get('code') ?? '';
+  const entry = codes.get(code);
+  codes.delete(code);
+  if (!entry || entry.redirectUri !== form.get('redirect_uri')) return null;
+  if (entry.challenge && entry.challenge !== s256(form.get('code_verifier') ?? '')) return null;
+
+  const token = randomBytes(24).toString('base64url');
+  accessTokens.set(token, true);
+  return { access_token: token, token_type: 'Bearer', expires_in: 3600 };
+}
+
+function userinfo(request) {
+  const token = (request.headers.get('authorization') ?? '').replace(/^Bearer /, '');
+  if (!accessTokens.has(token)) return null;
+  return { sub: 'mock-user', email: 'dev@example.com', name: 'Dev User' };
+}
+"#;
+
+const NODE_WEBAUTHN: &str = r#"import {
+  generateAuthenticationOptions,
+  generateRegistrationOptions,
+  verifyAuthenticationResponse,
+  verifyRegistrationResponse,
+} from '@simplewebauthn/server';
+import { APP_NAME, APP_URL } from './config.js';
+import { memoryStore } from './memory.js';
+import { getRecord, putRecord } from './store.js';
+
+const RP_ID = process.env.WEBAUTHN_RP_ID ?? new URL(APP_URL).hostname;
+
+/**
+ * Passkeys are records keyed by their base64url credential id, holding
+ * `{ userId, publicKey, counter, transports }` with a base64url public key
+ */
+const passkeyKey = (id) => `passkey:${id}`;
+/** Outstanding challenges, each accepted once; registration ones map to their user */
+const challenges = memoryStore('webauthn.challenges');
+
+function clientChallenge(response) {
+  const clientData = JSON.parse(Buffer.from(response?.response?.clientDataJSON ?? '', 'base64url').toString() || '{}');
+  return clientData.challenge ?? '';
+}
+
+function takeChallenge(response) {
+  const challenge = clientChallenge(response);
+  if (!challenges.has(challenge)) return null;
+  const userId = challenges.get(challenge);
+  challenges.delete(challenge);
+  return { challenge, userId };
+}
+
+export async function registrationOptions(user) {
+  const options = await generateRegistrationOptions({
+    rpName: APP_NAME,
+    rpID: RP_ID,
+    userID: user.id,
+    userName: user.email,
+    attestationType: 'none',
+    authenticatorSelection: { residentKey: 'required', userVerification: 'preferred' },
+  });
+  challenges.set(options.challenge, user.id);
+  return options;
+}
+
+/** Store the new passkey; returns the user it belongs to, or null */
+export async function verifyRegistration(response) {
+  const pending = takeChallenge(response);
+  if (!pending?.userId) return null;
+  try {
+    const { verified, registrationInfo } = await verifyRegistrationResponse({
+      response,
+      expectedChallenge: pending.challenge,
+      expectedOrigin: APP_URL,
+      expectedRPID: RP_ID,
+    });
+    if (!verified || !registrationInfo) return null;
+    await putRecord(passkeyKey(Buffer.from(registrationInfo.credentialID).toString('base64url')), {
+      userId: pending.userId,
+      publicKey: Buffer.from(registrationInfo.credentialPublicKey).toString('base64url'),
+      counter: registrationInfo.counter,
+      transports: response.response.transports,
+    });
+    return pending.userId;
+  } catch {
+    return null;
+  }
+}
+
+export async function authenticationOptions() {
+  const options = await generateAuthenticationOptions({ rpID: RP_ID, userVerification: 'preferred' });
+  challenges.set(options.challenge, null);
+  return options;
+}
+
+/** Check an assertion against the stored passkey; returns its user, or null */
+export async function verifyAuthentication(response) {
+  const pending = takeChallenge(response);
+  const credential = response?.id ? await getRecord(passkeyKey(response.id)) : null;
+  if (!pending || !credential) return null;
+  try {
+    const { verified, authenticationInfo } = await verifyAuthenticationResponse({
+      response,
+      expectedChallenge: pending.challenge,
+      expectedOrigin: APP_URL,
+      expectedRPID: RP_ID,
+      authenticator: {
+        credentialID: Buffer.from(response.id, 'base64url'),
+        credentialPublicKey: Buffer.from(credential.publicKey, 'base64url'),
+        counter: credential.counter,
+        transports: credential.transports,
+      },
+    });
+    if (!verified) return null;
+    await putRecord(passkeyKey(response.id), { ...credential, counter: authenticationInfo.newCounter });
+    return credential.userId;
+  } catch {
+    return null;
+  }
+}
+"#;
+
+const NODE_JWT_HANDLERS: &str = r#"import { error, json, readJson } from './http.js';
+import { hashPassword, verifyPassword } from './password.js';
+import { ACCESS_TOKEN_TTL, issueRefreshToken, revokeRefreshToken, rotateRefreshToken, signAccessToken } from './tokens.js';
+import { createUser, findUserByEmail, findUserById, publicUser } from './users.js';
+
+// OAuth 2 token response fields, shared by every generated backend
+async function tokens(user, refreshToken) {
+  return {
+    user: publicUser(user),
+    access_token: signAccessToken(user),
+    refresh_token: refreshToken ?? (await issueRefreshToken(user.id)),
+    token_type: 'Bearer',
+    expires_in: ACCESS_TOKEN_TTL,
+  };
+}
+
+/**
+ * Handle /api/auth/<path>: register, login, refresh and logout.
+ * @param {string} path
+ * @param {Request} request
+ * @returns {Promise<Response>}
+ */
+export async function handleAuth(path, request) {
+  switch (`${request.method} ${path}`) {
+    case 'POST register': {
+      const { email, password, name } = await readJson(request);
+      if (!email || typeof password !== 'string' || password.length < 8) {
+        return error(400, 'An email and a password of at least 8 characters are required');
+      }
+      if (await findUserByEmail(email)) return error(409, 'Email already registered');
+      const user = await createUser({ email, name, passwordHash: await hashPassword(password) });
+      return json(await tokens(user), { status: 201 });
+    }
+    case 'POST login': {
+      const { email, password } = await readJson(request);
+      const user = email ? await findUserByEmail(email) : null;
+      if (!user?.passwordHash || !(await verifyPassword(password ?? '', user.passwordHash))) {
+        return error(401, 'Invalid email or password');
+      }
+      return json(await tokens(user));
+    }
+    case 'POST refresh': {
+      const { refresh_token: refreshToken } = await readJson(request);
+      const rotated = refreshToken ? await rotateRefreshToken(refreshToken) : null;
+      const user = rotated ? await findUserById(rotated.userId) : null;
+      if (!user) return error(401, 'Invalid refresh token');
+      return json(await tokens(user, rotated.refreshToken));
+    }
+    case 'POST logout': {
+      const { refresh_token: refreshToken } = await readJson(request);
+      if (refreshToken) await revokeRefreshToken(refreshToken);
+      return new Response(null, { status: 204 });
+    }
+    default:
+      return error(404, 'Not found');
+  }
+}
+"#;
+
+const NODE_SESSION_HANDLERS: &str = r#"import { error, json, readJson, withCookies } from './http.js';
+import { hashPassword, verifyPassword } from './password.js';
+import { clearSessionCookie, createSession, destroySession, refreshSession, sessionCookie, sessionUserId } from './sessions.js';
+import { createUser, findUserByEmail, findUserById, publicUser } from './users.js';
+
+async function signedIn(user, status = 200) {
+  return withCookies(json({ user: publicUser(user) }, { status }), [sessionCookie(await createSession(user.id))]);
+}
+
+/**
+ * Handle /api/auth/<path>: register, login, refresh and logout.
+ * @param {string} path
+ * @param {Request} request
+ * @returns {Promise<Response>}
+ */
+export async function handleAuth(path, request) {
+  switch (`${request.method} ${path}`) {
+    case 'POST register': {
+      const { email, password, name } = await readJson(request);
+      if (!email || typeof password !== 'string' || password.length < 8) {
+        return error(400, 'An email and a password of at least 8 characters are required');
+      }
+      if (await findUserByEmail(email)) return error(409, 'Email already registered');
+      return signedIn(await createUser({ email, name, passwordHash: await hashPassword(password) }), 201);
+    }
+    case 'POST login': {
+      const { email, password } = await readJson(request);
+      const user = email ? await findUserByEmail(email) : null;
+      if (!user?.passwordHash || !(await verifyPassword(password ?? '', user.passwordHash))) {
+        return error(401, 'Invalid email or password');
+      }
+      // A fresh id on every login prevents session fixation
+      await destroySession(request);
+      return signedIn(user);
+    }
+    case 'POST refresh': {
+      const user = await findUserById(await sessionUserId(request));
+      const id = user ? await refreshSession(request) : null;
+      if (!id) return error(401, 'No active session');
+      return withCookies(json({ user: publicUser(user) }), [sessionCookie(id)]);
+    }
+    case 'POST logout':
+      await destroySession(request);
+      return withCookies(new Response(null, { status: 204 }), [clearSessionCookie()]);
+    default:
+      return error(404, 'Not found');
+  }
+}
+"#;
+
+const NODE_OAUTH_HANDLERS: &str = r#"import { cookie, error, json, readCookie, redirect, withCookies } from './http.js';
+import { handleMockProvider } from './mock-provider.js';
+import { authorizationRequest, fetchProfile } from './oauth.js';
+import { clearSessionCookie, createSession, destroySession, refreshSession, sessionCookie, sessionUserId } from './sessions.js';
+import { createUser, findUserByEmail, findUserById, publicUser } from './users.js';
+
+const STATE_COOKIE = 'oauth_state';
+
+/**
+ * Handle /api/auth/<path>: the OAuth redirect flow, refresh and logout, plus
+ * the development mock provider under mock/.
+ * @param {string} path
+ * @param {Request} request
+ * @returns {Promise<Response>}
+ */
+export async function handleAuth(path, request) {
+  const url = new URL(request.url);
+  switch (`${request.method} ${path}`) {
+    case 'GET oauth/login': {
+      const { url: authorizeUrl, state, verifier } = authorizationRequest();
+      return redirect(authorizeUrl, [cookie(STATE_COOKIE, `${state}.${verifier}`, 600)]);
+    }
+    case 'GET oauth/callback': {
+      const [state, verifier] = (readCookie(request, STATE_COOKIE) ?? '').split('.');
+      if (!state || url.searchParams.get('state') !== state) return error(400, 'Invalid OAuth state');
+      const profile = await fetchProfile(url.searchParams.get('code') ?? '', verifier);
+      if (!profile.email) return error(400, 'The provider did not share an email address');
+      const user =
+        (await findUserByEmail(profile.email)) ?? (await createUser({ email: profile.email, name: profile.name ?? '' }));
+      const session = await createSession(user.id);
+      return redirect(process.env.AUTH_REDIRECT_URL ?? '/', [sessionCookie(session), cookie(STATE_COOKIE, '', 0)]);
+    }
+    case 'POST refresh': {
+      const user = await findUserById(await sessionUserId(request));
+      const id = user ? await refreshSession(request) : null;
+      if (!id) return error(401, 'No active session');
+      return withCookies(json({ user: publicUser(user) }), [sessionCookie(id)]);
+    }
+    case 'POST logout':
+      await destroySession(request);
+      return withCookies(new Response(null, { status: 204 }), [clearSessionCookie()]);
+  }
+
+  if (path.startsWith('mock/')) return handleMockProvider(path.slice('mock/'.length), request);
+  return error(404, 'Not found');
+}
+"#;
+
+const NODE_WEBAUTHN_HANDLERS: &str = r#"import { error, json, readJson, withCookies } from './http.js';
+import { clearSessionCookie, createSession, destroySession, refreshSession, sessionCookie, sessionUserId } from './sessions.js';
+import { createUser, findUserByEmail, findUserById, publicUser } from './users.js';
+import { authenticationOptions, registrationOptions, verifyAuthentication, verifyRegistration } from './webauthn.js';
+
+async function signedIn(user, status = 200) {
+  return withCookies(json({ user: publicUser(user) }, { status }), [sessionCookie(await createSession(user.id))]);
+}
+
+/**
+ * Handle /api/auth/<path>: passkey registration and sign-in, refresh and
+ * logout. Pass the options to `navigator.credentials` (for example with
+ * @simplewebauthn/browser) and post the result back to the verify route.
+ * @param {string} path
+ * @param {Request} request
+ * @returns {Promise<Response>}
+ */
+export async function handleAuth(path, request) {
+  switch (`${request.method} ${path}`) {
+    case 'POST passkey/register/options': {
+      // Signed-in users add a passkey to their account; anyone else creates one
+      let user = await findUserById(await sessionUserId(request));
+      if (!user) {
+        const { email, name } = await readJson(request);
+        if (!email) return error(400, 'An email is required');
+        if (await findUserByEmail(email)) return error(409, 'Sign in to add a passkey to an existing account');
+        user = await createUser({ email, name });
+      }
+      return json(await registrationOptions(user));
+    }
+    case 'POST passkey/register/verify': {
+      const user = await findUserById(await verifyRegistration(await readJson(request)));
+      return user ? signedIn(user, 201) : error(400, 'Passkey registration failed');
+    }
+    case 'POST passkey/login/options':
+      return json(await authenticationOptions());
+    case 'POST passkey/login/verify': {
+      const user = await findUserById(await verifyAuthentication(await readJson(request)));
+      return user ? signedIn(user) : error(401, 'Passkey sign-in failed');
+    }
+    case 'POST refresh': {
+      const user = await findUserById(await sessionUserId(request));
+      const id = user ? await refreshSession(request) : null;
+      if (!id) return error(401, 'No active session');
+      return withCookies(json({ user: publicUser(user) }), [sessionCookie(id)]);
+    }
+    case 'POST logout':
+      await destroySession(request);
+      return withCookies(new Response(null, { status: 204 }), [clearSessionCookie()]);
+    default:
+      return error(404, 'Not found');
+  }
+}
+"#;
+
+const NODE_JWT_GUARD: &str = r#"import { error } from './http.js';
+import { verifyAccessToken } from './tokens.js';
+import { findUserById } from './users.js';
+
+/** The user behind the request's bearer token, or null */
+export async function authenticate(request) {
+  const header = request.headers.get('authorization') ?? '';
+  const claims = header.startsWith('Bearer ') ? verifyAccessToken(header.slice('Bearer '.length)) : null;
+  return claims ? findUserById(claims.sub) : null;
+}
+
+/** Run `handler(request, user, ...rest)` for signed-in users only */
+export function withAuth(handler) {
+  return async (request, ...rest) => {
+    const user = await authenticate(request);
+    return user ? handler(request, user, ...rest) : error(401, 'Authentication required');
+  };
+}
+"#;
+
+const NODE_SESSION_GUARD: &str = r#"import { error } from './http.js';
+import { sessionUserId } from './sessions.js';
+import { findUserById } from './users.js';
+
+/** The user behind the request's session cookie, or null */
+export async function authenticate(request) {
+  const userId = await sessionUserId(request);
+  return userId ? findUserById(userId) : null;
+}
+
+/** Run `handler(request, user, ...rest)` for signed-in users only */
+export function withAuth(handler) {
+  return async (request, ...rest) => {
+    const user = await authenticate(request);
+    return user ? handler(request, user, ...rest) : error(401, 'Authentication required');
+  };
+}
+"#;
+
+const NODE_JWT_TEST: &str = r#"import assert from 'node:assert/strict';
+import { test } from 'node:test';
+import { authenticate } from './guard.js';
+import { handleAuth } from './handlers.js';
+
+// Test accounts stay in memory, whether or not the app has a database
+process.env.AUTH_STORE = 'memory';
+
+const post = (path, body) =>
+  handleAuth(path, new Request(`http://localhost/api/auth/${path}`, { method: 'POST', body: JSON.stringify(body) }));
+const bearer = (token) => new Request('http://localhost/api/me', { headers: { authorization: `Bearer ${token}` } });
+
+test('register, log in, refresh and log out with tokens', async () => {
+  const registered = await post('register', { email: 'jwt@example.com', password: 'correct horse' });
+  assert.equal(registered.status, 201);
+  const { access_token: accessToken, refresh_token: refreshToken } = await registered.json();
+  assert.equal((await authenticate(bearer(accessToken)))?.email, 'jwt@example.com');
+  assert.equal(await authenticate(bearer(`${accessToken}x`)), null);
+
+  assert.equal((await post('register', { email: 'jwt@example.com', password: 'correct horse' })).status, 409);
+  assert.equal((await post('login', { email: 'jwt@example.com', password: 'wrong password' })).status, 401);
+  assert.equal((await post('login', { email: 'jwt@example.com', password: 'correct horse' })).status, 200);
+
+  const refreshed = await post('refresh', { refresh_token: refreshToken });
+  assert.equal(refreshed.status, 200);
+  // Refresh tokens rotate, so the old one is spent
+  assert.equal((await post('refresh', { refresh_token: refreshToken })).status, 401);
+
+  const { refresh_token: latest } = await refreshed.json();
+  assert.equal((await post('logout', { refresh_token: latest })).status, 204);
+  assert.equal((await post('refresh', { refresh_token: latest })).status, 401);
+});
+"#;
+
+const NODE_SESSION_TEST: &str = r#"import assert from 'node:assert/strict';
+import { test } from 'node:test';
+import { authenticate } from './guard.js';
+import { handleAuth } from './handlers.js';
+
+// Test accounts stay in memory, whether or not the app has a database
+process.env.AUTH_STORE = 'memory';
+
+const post = (path, body, session) =>
+  handleAuth(
+    path,
+    new Request(`http://localhost/api/auth/${path}`, {
+      method: 'POST',
+      headers: session ? { cookie: `session=${session}` } : {},
+      body: JSON.stringify(body ?? {}),
+    }),
+  );
+const sessionOf = (response) => /session=([^;]*)/.exec(response.headers.get('set-cookie') ?? '')?.[1];
+const withSession = (session) => new Request('http://localhost/api/me', { headers: { cookie: `session=${session}` } });
+
+test('register, refresh and log out with a session cookie', async () => {
+  const registered = await post('register', { email: 'session@example.com', password: 'correct horse' });
+  assert.equal(registered.status, 201);
+  const session = sessionOf(registered);
+  assert.equal((await authenticate(withSession(session)))?.email, 'session@example.com');
+
+  assert.equal((await post('login', { email: 'session@example.com', password: 'wrong password' })).status, 401);
+
+  const refreshed = await post('refresh', {}, session);
+  assert.equal(refreshed.status, 200);
+  const renewed = sessionOf(refreshed);
+  // Refreshing rotates the session id
+  assert.equal(await authenticate(withSession(session)), null);
+  assert.equal((await authenticate(withSession(renewed)))?.email, 'session@example.com');
+
+  assert.equal((await post('logout', {}, renewed)).status, 204);
+  assert.equal(await authenticate(withSession(renewed)), null);
+});
+"#;
+
+const NODE_OAUTH_TEST: &str = r#"import assert from 'node:assert/strict';
+import { test } from 'node:test';
+import { authenticate } from './guard.js';
+import { handleAuth } from './handlers.js';
+
+// Test accounts stay in memory, whether or not the app has a database
+process.env.AUTH_STORE = 'memory';
+
+// Serve the mock provider's endpoints in-process instead of over the network
+globalThis.fetch = async (input, init) => {
+  const url = new URL(input);
+  return handleAuth(url.pathname.replace('/api/auth/', ''), new Request(url, init));
+};
+
+const get = (url, cookie) => handleAuth(new URL(url).pathname.replace('/api/auth/', ''), new Request(url, { headers: cookie ? { cookie } : {} }));
+const cookieValue = (response, name) => new RegExp(`${name}=([^;]*)`).exec(response.headers.get('set-cookie') ?? '')?.[1];
+
+test('sign in through the mock OAuth provider', async () => {
+  const login = await get('http://localhost:3000/api/auth/oauth/login');
+  assert.equal(login.status, 302);
+  const state = cookieValue(login, 'oauth_state');
+
+  const authorized = await get(login.headers.get('location'));
+  assert.equal(authorized.status, 302);
+  const callback = authorized.headers.get('location');
+  assert.equal((await get(callback)).status, 400, 'the callback needs the state cookie');
+
+  const signedIn = await get(callback, `oauth_state=${state}`);
+  assert.equal(signedIn.status, 302);
+  const session = cookieValue(signedIn, 'session');
+  const me = await authenticate(new Request('http://localhost:3000/api/me', { headers: { cookie: `session=${session}` } }));
+  assert.equal(me?.email, 'dev@example.com');
+});
+"#;
+
+const NODE_WEBAUTHN_TEST: &str = r#"import assert from 'node:assert/strict';
+import { test } from 'node:test';
+import { handleAuth } from './handlers.js';
+
+// Test accounts stay in memory, whether or not the app has a database
+process.env.AUTH_STORE = 'memory';
+
+const post = (path, body) =>
+  handleAuth(path, new Request(`http://localhost/api/auth/${path}`, { method: 'POST', body: JSON.stringify(body ?? {}) }));
+
+// Completing a ceremony needs an authenticator; these cover the server side of it
+test('issue passkey challenges and reject unverified responses', async () => {
+  const options = await post('passkey/register/options', { email: 'passkey@example.com' });
+  assert.equal(options.status, 200);
+  assert.ok((await options.json()).challenge);
+  assert.equal((await post('passkey/register/options', { email: 'passkey@example.com' })).status, 409);
+
+  const login = await post('passkey/login/options');
+  assert.ok((await login.json()).challenge);
+  assert.equal((await post('passkey/login/verify', { id: 'unknown', response: {} })).status, 401);
+  assert.equal((await post('passkey/register/verify', { id: 'unknown', response: {} })).status, 400);
+});
+"#;
+
+// ============================================================================
+// NODE FRAMEWORK ADAPTERS
+// ============================================================================
+
+/// Catch-all `/api/auth/*` route handler plus a protected `/api/me` example
+pub fn nextjs_routes() -> Vec<AuthFile> {
+    vec![
+        file("src/app/api/auth/[...path]/route.ts", NEXTJS_AUTH_ROUTE),
+        file("src/app/api/me/route.ts", NEXTJS_ME_ROUTE),
+    ]
+}
+
+/// Auth endpoint, a hook resolving `locals.user` on every request and a protected `/api/me`
+pub fn sveltekit_routes() -> Vec<AuthFile> {
+    vec![
+        file("src/routes/api/auth/[...path]/+server.ts", SVELTEKIT_AUTH_ROUTE),
+        file("src/hooks.server.ts", SVELTEKIT_HOOKS),
+        file("src/app.d.ts", SVELTEKIT_APP_TYPES),
+        file("src/routes/api/me/+server.ts", SVELTEKIT_ME_ROUTE),
+    ]
+}
+
+pub fn solidstart_routes() -> Vec<AuthFile> {
+    vec![
+        file("src/routes/api/auth/[...path].ts", SOLIDSTART_AUTH_ROUTE),
+        file("src/routes/api/me.ts", SOLIDSTART_ME_ROUTE),
+    ]
+}
+
+/// Express router and middleware; `server/index.js` mounts them
+pub fn express_adapter() -> Vec<AuthFile> {
+    vec![file("server/auth/express.js", EXPRESS_ADAPTER)]
+}
+
+const NEXTJS_AUTH_ROUTE: &str = r#"import { handleAuth } from '../../../../lib/auth/handlers.js';
+
+// The auth core uses node:crypto
+export const runtime = 'nodejs';
+
+type Context = { params: { path: string[] } };
+
+const handler = (request: Request, { params }: Context) => handleAuth(params.path.join('/'), request);
+
+export { handler as GET, handler as POST };
+"#;
+
+const NEXTJS_ME_ROUTE: &str = r#"import { withAuth } from '../../../lib/auth/guard.js';
+import { json } from '../../../lib/auth/http.js';
+import { publicUser } from '../../../lib/auth/users.js';
+
+export const runtime = 'nodejs';
+
+export const GET = withAuth(async (_request: Request, user) => json({ user: publicUser(user) }));
+"#;
+
+const SVELTEKIT_AUTH_ROUTE: &str = r#"import { handleAuth } from '$lib/server/auth/handlers.js';
+import type { RequestHandler } from './$types';
+
+const handler: RequestHandler = ({ params, request }) => handleAuth(params.path, request);
+
+export const GET = handler;
+export const POST = handler;
+"#;
+
+const SVELTEKIT_HOOKS: &str = r#"import type { Handle } from '@sveltejs/kit';
+import { authenticate } from '$lib/server/auth/guard.js';
+import { publicUser } from '$lib/server/auth/users.js';
+
+export const handle: Handle = async ({ event, resolve }) => {
+	const user = await authenticate(event.request);
+	event.locals.user = user ? publicUser(user) : null;
+	return resolve(event);
+};
+"#;
+
+const SVELTEKIT_APP_TYPES: &str = r#"// See https://kit.svelte.dev/docs/types#app
+declare global {
+	namespace App {
+		interface Locals {
+			/** Set by src/hooks.server.ts for signed-in requests */
+			user: { id: string; email: string; name: string; createdAt: string } | null;
+		}
+	}
+}
+
+export {};
+"#;
+
+const SVELTEKIT_ME_ROUTE: &str = r#"import { error, json } from '@sveltejs/kit';
+import type { RequestHandler } from './$types';
+
+export const GET: RequestHandler = ({ locals }) => {
+	if (!locals.user) error(401, 'Authentication required');
+	return json({ user: locals.user });
+};
+"#;
+
+const SOLIDSTART_AUTH_ROUTE: &str = r#"import type { APIEvent } from "@solidjs/start/server";
+import { handleAuth } from "../../../lib/auth/handlers.js";
+
+const handler = ({ params, request }: APIEvent) => handleAuth(params.path, request);
+
+export const GET = handler;
+export const POST = handler;
+"#;
+
+const SOLIDSTART_ME_ROUTE: &str = r#"import type { APIEvent } from "@solidjs/start/server";
+import { authenticate } from "../../lib/auth/guard.js";
+import { error, json } from "../../lib/auth/http.js";
+import { publicUser } from "../../lib/auth/users.js";
+
+export async function GET({ request }: APIEvent) {
+  const user = await authenticate(request);
+  return user ? json({ user: publicUser(user) }) : error(401, "Authentication required");
+}
+"#;
+
+const EXPRESS_ADAPTER: &str = r#"import express from 'express';
+import { authenticate } from './guard.js';
+import { handleAuth } from './handlers.js';
+
+/** Fetch API Request for an Express request; the body is only kept when read raw */
+function toRequest(req) {
+  const url = `${req.protocol}://${req.get('host')}${req.originalUrl}`;
+  const body = Buffer.isBuffer(req.body) && req.body.length > 0 ? req.body : undefined;
+  return new Request(url, { method: req.method, headers: req.headers, body });
+}
+
+async function send(res, response) {
+  res.status(response.status);
+  response.headers.forEach((value, key) => {
+    if (key !== 'set-cookie') res.setHeader(key, value);
+  });
+  const cookies = response.headers.getSetCookie();
+  if (cookies.length > 0) res.setHeader('set-cookie', cookies);
+  res.send(Buffer.from(await response.arrayBuffer()));
+}
+
+/** Mount at /api/auth before express.json(); the handlers read the raw body */
+export const authRouter = express.Router();
+authRouter.use(express.raw({ type: () => true }));
+authRouter.use(async (req, res, next) => {
+  try {
+    await send(res, await handleAuth(req.path.slice(1), toRequest(req)));
+  } catch (error) {
+    next(error);
+  }
+});
+
+/** Reject anonymous requests; the signed-in user is available as req.user */
+export async function requireAuth(req, res, next) {
+  try {
+    const user = await authenticate(toRequest(req));
+    if (!user) return res.status(401).json({ error: 'Authentication required' });
+    req.user = user;
+    next();
+  } catch (error) {
+    next(error);
+  }
+}
+"#;
+
+// ============================================================================
+// T3 STACK
+// ============================================================================
+
+/// Mock OAuth provider served by the app itself, registered with NextAuth in
+/// development so sign-in works before Discord or GitHub credentials exist
+pub fn t3_mock_provider(app_url: &str, app_name: &str) -> Vec<AuthFile> {
+    let dir = "src/server/auth";
+    vec![
+        file(
+            format!("{}/config.js", dir),
+            format!(
+                "// Development defaults; every value can be overridden from the environment\nexport const APP_URL = process.env.APP_URL ?? process.env.NEXTAUTH_URL ?? '{}';\nexport const APP_NAME = process.env.APP_NAME ?? '{}';\n",
+                app_url, app_name
+            ),
+        ),
+        file(format!("{}/http.js", dir), NODE_HTTP),
+        file(format!("{}/memory.js", dir), NODE_MEMORY),
+        file(format!("{}/mock-provider.js", dir), NODE_MOCK_PROVIDER),
+        file(format!("{}/mock-provider.test.js", dir), T3_MOCK_PROVIDER_TEST),
+        file("src/app/api/mock-oauth/[...path]/route.ts", T3_MOCK_PROVIDER_ROUTE),
+    ]
+}
+
+/// NextAuth provider entry for `t3_mock_provider`, spliced into `authOptions.providers`
+pub const T3_MOCK_PROVIDER_OPTION: &str = r#"    // Development sign-in through the mock provider in src/server/auth
+    ...(mockEnabled()
+      ? [
+          {
+            id: "mock",
+            name: "Mock provider (development)",
+            type: "oauth",
+            clientId: "dev-client",
+            clientSecret: "dev-secret",
+            checks: ["pkce", "state"],
+            authorization: {
+              url: `${process.env.NEXTAUTH_URL}/api/mock-oauth/authorize`,
+              params: { scope: "openid email profile" },
+            },
+            token: `${process.env.NEXTAUTH_URL}/api/mock-oauth/token`,
+            userinfo: `${process.env.NEXTAUTH_URL}/api/mock-oauth/userinfo`,
+            profile: (profile: { sub: string; name: string; email: string }) => ({
+              id: profile.sub,
+              name: profile.name,
+              email: profile.email,
+              image: null,
+            }),
+          } satisfies OAuthConfig<{ sub: string; name: string; email: string }>,
+        ]
+      : []),
+"#;
+
+const T3_MOCK_PROVIDER_ROUTE: &str = r#"import { handleMockProvider } from "~/server/auth/mock-provider.js";
+
+type Context = { params: { path: string[] } };
+
+const handler = (request: Request, { params }: Context) => handleMockProvider(params.path.join("/"), request);
+
+export { handler as GET, handler as POST };
+"#;
+
+const T3_MOCK_PROVIDER_TEST: &str = r#"import assert from 'node:assert/strict';
+import { createHash } from 'node:crypto';
+import { test } from 'node:test';
+import { APP_URL } from './config.js';
+import { handleMockProvider } from './mock-provider.js';
+
+const verifier = 'a'.repeat(43);
+const challenge = createHash('sha256').update(verifier).digest('base64url');
+const redirectUri = `${APP_URL}/api/auth/callback/mock`;
+
+test('authorize, exchange the code and read the profile', async () => {
+  const authorize = new URL('http://localhost/api/mock-oauth/authorize');
+  authorize.search = new URLSearchParams({ redirect_uri: redirectUri, state: 'xyz', code_challenge: challenge }).toString();
+  const redirect = await handleMockProvider('authorize', new Request(authorize));
+  assert.equal(redirect.status, 302);
+  const code = new URL(redirect.headers.get('location')).searchParams.get('code');
+
+  const form = (verifierValue) =>
+    new Request('http://localhost/api/mock-oauth/token', {
+      method: 'POST',
+      body: new URLSearchParams({ code, redirect_uri: redirectUri, code_verifier: verifierValue }),
+    });
+  const token = await handleMockProvider('token', form(verifier));
+  assert.equal(token.status, 200);
+  // Codes are single-use
+  assert.equal((await handleMockProvider('token', form(verifier))).status, 400);
+
+  const { access_token: accessToken } = await token.json();
+  const profile = await handleMockProvider(
+    'userinfo',
+    new Request('http://localhost/api/mock-oauth/userinfo', { headers: { authorization: `Bearer ${accessToken}` } }),
+  );
+  assert.equal((await profile.json()).email, 'dev@example.com');
+});
+
+test('reject redirect URIs outside the app', async () => {
+  const authorize = new URL('http://localhost/api/mock-oauth/authorize');
+  authorize.search = new URLSearchParams({ redirect_uri: 'https://evil.example/callback' }).toString();
+  assert.equal((await handleMockProvider('authorize', new Request(authorize))).status, 400);
+});
+"#;
+
+// ============================================================================
+// FASTAPI
+// ============================================================================
+
+/// Security helpers, the `get_current_user` dependency, the auth router and its tests
+pub fn fastapi_files(auth: &AuthSpec) -> Vec<AuthFile> {
+    let (security, deps, routes, test) = match auth.id {
+        "jwt" => (
+            format!("{}{}{}", FASTAPI_SECURITY_JWT_HEAD, FASTAPI_GRANTS, FASTAPI_SECURITY_JWT_TAIL),
+            FASTAPI_DEPS_JWT,
+            FASTAPI_ROUTES_JWT.to_string(),
+            FASTAPI_TEST_JWT,
+        ),
+        "oauth" => (
+            format!("{}{}{}", FASTAPI_SECURITY_SESSION_HEAD, FASTAPI_GRANTS, FASTAPI_SECURITY_SESSION_TAIL),
+            FASTAPI_DEPS_SESSION,
+            format!("{}{}{}", FASTAPI_ROUTES_OAUTH_IMPORTS, FASTAPI_ROUTES_SESSION, FASTAPI_ROUTES_OAUTH),
+            FASTAPI_TEST_OAUTH,
+        ),
+        _ => (
+            format!("{}{}{}", FASTAPI_SECURITY_SESSION_HEAD, FASTAPI_GRANTS, FASTAPI_SECURITY_SESSION_TAIL),
+            FASTAPI_DEPS_SESSION,
+            format!("{}{}{}", FASTAPI_ROUTES_SESSION_IMPORTS, FASTAPI_ROUTES_SESSION, FASTAPI_ROUTES_SESSION_PASSWORD),
+            FASTAPI_TEST_SESSION,
+        ),
+    };
+    vec![
+        file("app/core/security.py", security),
+        file("app/api/deps.py", deps),
+        file("app/schemas/auth.py", FASTAPI_SCHEMAS),
+        file("app/api/routes/auth.py", routes),
+        file("tests/test_auth.py", format!("{}{}", FASTAPI_TEST_SETUP, test)),
+    ]
+}
+
+/// Fields appended to `Settings` in app/core/config.py
+pub fn fastapi_settings(auth: &AuthSpec) -> &'static str {
+    match auth.id {
+        "jwt" => "\n    # Authentication\n    ENVIRONMENT: str = \"development\"\n    JWT_SECRET: str = \"dev-secret-change-me\"\n",
+        "oauth" => r#"
+    # Authentication
+    ENVIRONMENT: str = "development"
+    APP_URL: str = "http://localhost:8000"
+    COOKIE_SECURE: bool = False
+    AUTH_REDIRECT_URL: str = "/"
+    # Empty means the mock provider under /auth/mock (never in production)
+    OAUTH_ISSUER: str = ""
+    OAUTH_CLIENT_ID: str = "dev-client"
+    OAUTH_CLIENT_SECRET: str = "dev-secret"
+"#,
+        _ => "\n    # Authentication\n    COOKIE_SECURE: bool = False\n",
+    }
+}
+
+/// Extra requirements; httpx also backs FastAPI's TestClient
+pub const FASTAPI_REQUIREMENTS: &str = "bcrypt==4.0.1\nemail-validator==2.1.0\nhttpx==0.26.0\n";
+
+const FASTAPI_SECURITY_JWT_HEAD: &str = r#""""Password hashing, access tokens and rotating refresh tokens"""
+import hashlib
+import secrets
+from datetime import datetime, timedelta, timezone
+
+from jose import JWTError, jwt
+from passlib.context import CryptContext
+
+from app.core.config import settings
+
+# The default secret is public; tokens signed with it can be forged
+if settings.ENVIRONMENT == "production" and settings.JWT_SECRET == "dev-secret-change-me":
+    raise RuntimeError("JWT_SECRET must be set in production")
+
+pwd_context = CryptContext(schemes=["bcrypt"], deprecated="auto")
+"#;
+
+const FASTAPI_SECURITY_JWT_TAIL: &str = r#"
+
+# Each refresh token is spent on use and replaced
+refresh_tokens = Grants(timedelta(days=30))
+
+
+def create_access_token(user_id: int) -> str:
+    expires = datetime.now(timezone.utc) + timedelta(minutes=settings.ACCESS_TOKEN_EXPIRE_MINUTES)
+    return jwt.encode({"sub": str(user_id), "exp": expires}, settings.JWT_SECRET, algorithm=settings.ALGORITHM)
+
+
+def decode_access_token(token: str) -> int | None:
+    """User id of a valid, unexpired access token"""
+    try:
+        return int(jwt.decode(token, settings.JWT_SECRET, algorithms=[settings.ALGORITHM])["sub"])
+    except (JWTError, KeyError, ValueError):
+        return None
+"#;
+
+const FASTAPI_SECURITY_SESSION_HEAD: &str = r#""""Password hashing and server-side sessions"""
+import hashlib
+import secrets
+from datetime import datetime, timedelta, timezone
+
+from passlib.context import CryptContext
+
+SESSION_COOKIE = "session"
+SESSION_TTL = timedelta(days=7)
+
+pwd_context = CryptContext(schemes=["bcrypt"], deprecated="auto")
+"#;
+
+const FASTAPI_SECURITY_SESSION_TAIL: &str = r#"
+
+sessions = Grants(SESSION_TTL)
+"#;
+
+const FASTAPI_GRANTS: &str = r#"
+
+def hash_password(password: str) -> str:
+    return pwd_context.hash(password)
+
+
+def verify_password(password: str, hashed_password: str) -> bool:
+    try:
+        return pwd_context.verify(password, hashed_password)
+    except ValueError:
+        # Unusable hashes, such as "!" for seeded or OAuth accounts, never match
+        return False
+
+
+class Grants:
+    """Opaque credentials mapped to users, stored hashed.
+
+    Kept in memory, which suits a single process; move them to the database
+    or Redis before running several workers.
+    """
+
+    def __init__(self, ttl: timedelta):
+        self.ttl = ttl
+        self._entries: dict[str, tuple[int, datetime]] = {}
+
+    @staticmethod
+    def _digest(token: str) -> str:
+        return hashlib.sha256(token.encode()).hexdigest()
+
+    def issue(self, user_id: int) -> str:
+        token = secrets.token_urlsafe(32)
+        self._entries[self._digest(token)] = (user_id, datetime.now(timezone.utc) + self.ttl)
+        return token
+
+    def lookup(self, token: str) -> int | None:
+        entry = self._entries.get(self._digest(token))
+        if entry is None or entry[1] < datetime.now(timezone.utc):
+            return None
+        return entry[0]
+
+    def revoke(self, token: str) -> None:
+        self._entries.pop(self._digest(token), None)
+
+    def rotate(self, token: str) -> tuple[int, str] | None:
+        """Spend a credential and issue its replacement for the same user"""
+        user_id = self.lookup(token)
+        self.revoke(token)
+        if user_id is None:
+            return None
+        return user_id, self.issue(user_id)
+"#;
+
+const FASTAPI_DEPS_JWT: &str = r#"from fastapi import Depends, HTTPException, status
+from fastapi.security import HTTPAuthorizationCredentials, HTTPBearer
+from sqlalchemy.orm import Session
+
+from app.core.database import get_db
+from app.core.security import decode_access_token
+from app.models.user import User
+
+bearer = HTTPBearer(auto_error=False)
+
+
+def get_current_user(
+    credentials: HTTPAuthorizationCredentials | None = Depends(bearer),
+    db: Session = Depends(get_db),
+) -> User:
+    """The signed-in user; depend on it to protect a route"""
+    user_id = decode_access_token(credentials.credentials) if credentials else None
+    user = db.get(User, user_id) if user_id is not None else None
+    if user is None or not user.is_active:
+        raise HTTPException(
+            status_code=status.HTTP_401_UNAUTHORIZED,
+            detail="Authentication required",
+            headers={"WWW-Authenticate": "Bearer"},
+        )
+    return user
+"#;
+
+const FASTAPI_DEPS_SESSION: &str = r#"from fastapi import Cookie, Depends, HTTPException, status
+from sqlalchemy.orm import Session
+
+from app.core.database import get_db
+from app.core.security import sessions
+from app.models.user import User
+
+
+def get_current_user(
+    session: str | None = Cookie(default=None),
+    db: Session = Depends(get_db),
+) -> User:
+    """The signed-in user; depend on it to protect a route"""
+    user_id = sessions.lookup(session) if session else None
+    user = db.get(User, user_id) if user_id is not None else None
+    if user is None or not user.is_active:
+        raise HTTPException(status_code=status.HTTP_401_UNAUTHORIZED, detail="Authentication required")
+    return user
+"#;
+
+const FASTAPI_SCHEMAS: &str = r#"from typing import Optional
+
+from pydantic import BaseModel, Field
+
+from app.schemas.user import User
+
+
+class RegisterRequest(BaseModel):
+    email: str
+    username: str
+    password: str = Field(min_length=8)
+    full_name: Optional[str] = None
+
+
+class LoginRequest(BaseModel):
+    email: str
+    password: str
+
+
+class RefreshRequest(BaseModel):
+    refresh_token: str
+
+
+class TokenResponse(BaseModel):
+    """OAuth 2 token response fields, shared by every generated backend"""
+
+    user: User
+    access_token: str
+    refresh_token: str
+    token_type: str = "Bearer"
+    expires_in: int
+"#;
+
+const FASTAPI_ROUTES_JWT: &str = r#"from fastapi import APIRouter, Depends, HTTPException, status
+from sqlalchemy.orm import Session
+
+from app.api.deps import get_current_user
+from app.core.config import settings
+from app.core.database import get_db
+from app.core.security import create_access_token, hash_password, refresh_tokens, verify_password
+from app.models.user import User
+from app.schemas.auth import LoginRequest, RefreshRequest, RegisterRequest, TokenResponse
+from app.schemas.user import User as UserSchema
+
+router = APIRouter()
+
+
+def _tokens(user: User, refresh_token: str | None = None) -> TokenResponse:
+    return TokenResponse(
+        user=UserSchema.model_validate(user),
+        access_token=create_access_token(user.id),
+        refresh_token=refresh_token or refresh_tokens.issue(user.id),
+        expires_in=settings.ACCESS_TOKEN_EXPIRE_MINUTES * 60,
+    )
+
+
+@router.post("/register", response_model=TokenResponse, status_code=status.HTTP_201_CREATED)
+def register(body: RegisterRequest, db: Session = Depends(get_db)):
+    taken = db.query(User).filter((User.email == body.email) | (User.username == body.username)).first()
+    if taken:
+        raise HTTPException(status_code=status.HTTP_409_CONFLICT, detail="Email or username already registered")
+    user = User(
+        email=body.email,
+        username=body.username,
+        full_name=body.full_name,
+        hashed_password=hash_password(body.password),
+    )
+    db.add(user)
+    db.commit()
+    db.refresh(user)
+    return _tokens(user)
+
+
+@router.post("/login", response_model=TokenResponse)
+def login(body: LoginRequest, db: Session = Depends(get_db)):
+    user = db.query(User).filter(User.email == body.email).first()
+    if user is None or not user.is_active or not verify_password(body.password, user.hashed_password):
+        raise HTTPException(status_code=status.HTTP_401_UNAUTHORIZED, detail="Invalid email or password")
+    return _tokens(user)
+
+
+@router.post("/refresh", response_model=TokenResponse)
+def refresh(body: RefreshRequest, db: Session = Depends(get_db)):
+    rotated = refresh_tokens.rotate(body.refresh_token)
+    user = db.get(User, rotated[0]) if rotated else None
+    if user is None or not user.is_active:
+        raise HTTPException(status_code=status.HTTP_401_UNAUTHORIZED, detail="Invalid refresh token")
+    return _tokens(user, rotated[1])
+
+
+@router.post("/logout", status_code=status.HTTP_204_NO_CONTENT)
+def logout(body: RefreshRequest):
+    refresh_tokens.revoke(body.refresh_token)
+
+
+@router.get("/me", response_model=UserSchema)
+def me(user: User = Depends(get_current_user)):
+    return user
+"#;
+
+const FASTAPI_ROUTES_SESSION_IMPORTS: &str = r#"from fastapi import APIRouter, Cookie, Depends, HTTPException, Response, status
+from sqlalchemy.orm import Session
+
+from app.api.deps import get_current_user
+from app.core.config import settings
+from app.core.database import get_db
+from app.core.security import SESSION_COOKIE, SESSION_TTL, hash_password, sessions, verify_password
+from app.models.user import User
+from app.schemas.auth import LoginRequest, RegisterRequest
+from app.schemas.user import User as UserSchema
+
+router = APIRouter()
+"#;
+
+const FASTAPI_ROUTES_OAUTH_IMPORTS: &str = r#"import base64
+import hashlib
+import secrets
+from urllib.parse import urlencode, urlsplit
+
+import httpx
+from fastapi import APIRouter, Cookie, Depends, Form, Header, HTTPException, Response, status
+from fastapi.responses import JSONResponse, RedirectResponse
+from sqlalchemy.orm import Session
+
+from app.api.deps import get_current_user
+from app.core.config import settings
+from app.core.database import get_db
+from app.core.security import SESSION_COOKIE, SESSION_TTL, sessions
+from app.models.user import User
+from app.schemas.user import User as UserSchema
+
+router = APIRouter()
+
+STATE_COOKIE = "oauth_state"
+
+# Replaced by the test client in tests/test_auth.py
+http = httpx.Client(timeout=10)
+"#;
+
+const FASTAPI_ROUTES_SESSION: &str = r#"
+
+def _set_session_cookie(response: Response, session_id: str) -> None:
+    response.set_cookie(
+        SESSION_COOKIE,
+        session_id,
+        max_age=int(SESSION_TTL.total_seconds()),
+        httponly=True,
+        samesite="lax",
+        secure=settings.COOKIE_SECURE,
+    )
+
+
+@router.post("/refresh", response_model=UserSchema)
+def refresh(response: Response, session: str | None = Cookie(default=None), db: Session = Depends(get_db)):
+    """Replace the session id and restart its lifetime"""
+    rotated = sessions.rotate(session) if session else None
+    user = db.get(User, rotated[0]) if rotated else None
+    if user is None or not user.is_active:
+        raise HTTPException(status_code=status.HTTP_401_UNAUTHORIZED, detail="No active session")
+    _set_session_cookie(response, rotated[1])
+    return user
+
+
+@router.post("/logout", status_code=status.HTTP_204_NO_CONTENT)
+def logout(response: Response, session: str | None = Cookie(default=None)):
+    if session:
+        sessions.revoke(session)
+    response.delete_cookie(SESSION_COOKIE)
+
+
+@router.get("/me", response_model=UserSchema)
+def me(user: User = Depends(get_current_user)):
+    return user
+"#;
+
+const FASTAPI_ROUTES_SESSION_PASSWORD: &str = r#"
+
+@router.post("/register", response_model=UserSchema, status_code=status.HTTP_201_CREATED)
+def register(body: RegisterRequest, response: Response, db: Session = Depends(get_db)):
+    taken = db.query(User).filter((User.email == body.email) | (User.username == body.username)).first()
+    if taken:
+        raise HTTPException(status_code=status.HTTP_409_CONFLICT, detail="Email or username already registered")
+    user = User(
+        email=body.email,
+        username=body.username,
+        full_name=body.full_name,
+        hashed_password=hash_password(body.password),
+    )
+    db.add(user)
+    db.commit()
+    db.refresh(user)
+    _set_session_cookie(response, sessions.issue(user.id))
+    return user
+
+
+@router.post("/login", response_model=UserSchema)
+def login(
+    body: LoginRequest,
+    response: Response,
+    session: str | None = Cookie(default=None),
+    db: Session = Depends(get_db),
+):
+    user = db.query(User).filter(User.email == body.email).first()
+    if user is None or not user.is_active or not verify_password(body.password, user.hashed_password):
+        raise HTTPException(status_code=status.HTTP_401_UNAUTHORIZED, detail="Invalid email or password")
+    # A fresh id on every sign-in prevents session fixation
+    if session:
+        sessions.revoke(session)
+    _set_session_cookie(response, sessions.issue(user.id))
+    return user
+"#;
+
+const FASTAPI_ROUTES_OAUTH: &str = r#"
+
+# ---------------------------------------------------------------------------
+# OAuth sign-in (authorization code flow with PKCE)
+# ---------------------------------------------------------------------------
+
+
+def _issuer() -> str:
+    return settings.OAUTH_ISSUER or f"{settings.APP_URL}{settings.API_V1_STR}/auth/mock"
+
+
+def _redirect_uri() -> str:
+    return f"{settings.APP_URL}{settings.API_V1_STR}/auth/oauth/callback"
+
+
+def _s256(value: str) -> str:
+    return base64.urlsafe_b64encode(hashlib.sha256(value.encode()).digest()).rstrip(b"=").decode()
+
+
+@router.get("/oauth/login")
+def oauth_login():
+    state, verifier = secrets.token_urlsafe(16), secrets.token_urlsafe(32)
+    query = urlencode({
+        "response_type": "code",
+        "client_id": settings.OAUTH_CLIENT_ID,
+        "redirect_uri": _redirect_uri(),
+        "scope": "openid email profile",
+        "state": state,
+        "code_challenge": _s256(verifier),
+        "code_challenge_method": "S256",
+    })
+    response = RedirectResponse(f"{_issuer()}/authorize?{query}", status_code=status.HTTP_302_FOUND)
+    response.set_cookie(STATE_COOKIE, f"{state}.{verifier}", max_age=600, httponly=True, samesite="lax", secure=settings.COOKIE_SECURE)
+    return response
+
+
+@router.get("/oauth/callback")
+def oauth_callback(code: str, state: str, oauth_state: str | None = Cookie(default=None), db: Session = Depends(get_db)):
+    expected, _, verifier = (oauth_state or "").partition(".")
+    if not expected or not secrets.compare_digest(state.encode(), expected.encode()):
+        raise HTTPException(status_code=status.HTTP_400_BAD_REQUEST, detail="Invalid OAuth state")
+
+    token = http.post(f"{_issuer()}/token", data={
+        "grant_type": "authorization_code",
+        "code": code,
+        "redirect_uri": _redirect_uri(),
+        "client_id": settings.OAUTH_CLIENT_ID,
+        "client_secret": settings.OAUTH_CLIENT_SECRET,
+        "code_verifier": verifier,
+    })
+    if token.status_code != 200:
+        raise HTTPException(status_code=status.HTTP_502_BAD_GATEWAY, detail="Token exchange failed")
+    profile = http.get(f"{_issuer()}/userinfo", headers={"Authorization": f"Bearer {token.json()['access_token']}"})
+    if profile.status_code != 200 or not profile.json().get("email"):
+        raise HTTPException(status_code=status.HTTP_502_BAD_GATEWAY, detail="The provider did not share an email address")
+
+    email = profile.json()["email"]
+    user = db.query(User).filter(User.email == email).first()
+    if user is None:
+        # Accounts created through OAuth have no usable password
+        user = User(email=email, username=email, full_name=profile.json().get("name"), hashed_password="!")
+        db.add(user)
+        db.commit()
+        db.refresh(user)
+
+    response = RedirectResponse(settings.AUTH_REDIRECT_URL, status_code=status.HTTP_302_FOUND)
+    _set_session_cookie(response, sessions.issue(user.id))
+    response.delete_cookie(STATE_COOKIE)
+    return response
+
+
+# ---------------------------------------------------------------------------
+# Mock provider for development. It approves every request as the same demo
+# user, so the redirect flow works before a real provider is registered.
+# ---------------------------------------------------------------------------
+
+_mock_codes: dict[str, tuple[str, str]] = {}
+_mock_tokens: set[str] = set()
+
+
+def _mock_enabled() -> None:
+    if settings.ENVIRONMENT == "production" or settings.OAUTH_ISSUER:
+        raise HTTPException(status_code=status.HTTP_404_NOT_FOUND)
+
+
+@router.get("/mock/authorize", dependencies=[Depends(_mock_enabled)])
+def mock_authorize(redirect_uri: str, state: str = "", code_challenge: str = ""):
+    if urlsplit(redirect_uri)[:2] != urlsplit(settings.APP_URL)[:2]:
+        raise HTTPException(status_code=status.HTTP_400_BAD_REQUEST, detail="Invalid redirect_uri")
+    code = secrets.token_urlsafe(16)
+    _mock_codes[code] = (redirect_uri, code_challenge)
+    return RedirectResponse(f"{redirect_uri}?{urlencode({'code': code, 'state': state})}", status_code=status.HTTP_302_FOUND)
+
+
+@router.post("/mock/token", dependencies=[Depends(_mock_enabled)])
+def mock_token(code: str = Form(...), redirect_uri: str = Form(...), code_verifier: str = Form("")):
+    entry = _mock_codes.pop(code, None)
+    if entry is None or entry[0] != redirect_uri or (entry[1] and entry[1] != _s256(code_verifier)):
+        return JSONResponse({"error": "invalid_grant"}, status_code=status.HTTP_400_BAD_REQUEST)
+    access_token = secrets.token_urlsafe(24)
+    _mock_tokens.add(access_token)
+    return {"access_token": access_token, "token_type": "Bearer", "expires_in": 3600}
+
+
+@router.get("/mock/userinfo", dependencies=[Depends(_mock_enabled)])
+def mock_userinfo(authorization: str = Header(default="")):
+    if authorization.removeprefix("Bearer ") not in _mock_tokens:
+        raise HTTPException(status_code=status.HTTP_401_UNAUTHORIZED, detail="Invalid access token")
+    return {"sub": "mock-user", "email": "dev@example.com", "name": "Dev User"}
+"#;
+
+const FASTAPI_TEST_SETUP: &str = r#"from fastapi.testclient import TestClient
+from sqlalchemy import create_engine
+from sqlalchemy.orm import sessionmaker
+from sqlalchemy.pool import StaticPool
+
+from app.core.database import Base, get_db
+from app.main import app
+
+# Each test run gets a fresh in-memory database
+engine = create_engine("sqlite://", connect_args={"check_same_thread": False}, poolclass=StaticPool)
+TestingSession = sessionmaker(autocommit=False, autoflush=False, bind=engine)
+Base.metadata.create_all(bind=engine)
+
+
+def override_get_db():
+    db = TestingSession()
+    try:
+        yield db
+    finally:
+        db.close()
+
+
+app.dependency_overrides[get_db] = override_get_db
+client = TestClient(app)
+"#;
+
+const FASTAPI_TEST_JWT: &str = r#"
+
+def test_register_refresh_and_logout_with_tokens():
+    response = client.post(
+        "/api/v1/auth/register",
+        json={"email": "jwt@example.com", "username": "jwt", "password": "correct horse"},
+    )
+    assert response.status_code == 201
+    tokens = response.json()
+
+    me = client.get("/api/v1/auth/me", headers={"Authorization": f"Bearer {tokens['access_token']}"})
+    assert me.json()["email"] == "jwt@example.com"
+    assert client.get("/api/v1/auth/me").status_code == 401
+
+    wrong = client.post("/api/v1/auth/login", json={"email": "jwt@example.com", "password": "wrong password"})
+    assert wrong.status_code == 401
+
+    refreshed = client.post("/api/v1/auth/refresh", json={"refresh_token": tokens["refresh_token"]})
+    assert refreshed.status_code == 200
+    # Refresh tokens rotate, so the old one is spent
+    replayed = client.post("/api/v1/auth/refresh", json={"refresh_token": tokens["refresh_token"]})
+    assert replayed.status_code == 401
+
+    latest = refreshed.json()["refresh_token"]
+    assert client.post("/api/v1/auth/logout", json={"refresh_token": latest}).status_code == 204
+    assert client.post("/api/v1/auth/refresh", json={"refresh_token": latest}).status_code == 401
+"#;
+
+const FASTAPI_TEST_SESSION: &str = r#"
+
+def test_register_refresh_and_logout_with_session():
+    response = client.post(
+        "/api/v1/auth/register",
+        json={"email": "session@example.com", "username": "session", "password": "correct horse"},
+    )
+    assert response.status_code == 201
+    assert client.get("/api/v1/auth/me").json()["email"] == "session@example.com"
+
+    wrong = client.post("/api/v1/auth/login", json={"email": "session@example.com", "password": "wrong password"})
+    assert wrong.status_code == 401
+
+    old_session = client.cookies["session"]
+    assert client.post("/api/v1/auth/refresh").status_code == 200
+    # Refreshing rotates the session id
+    assert client.cookies["session"] != old_session
+    assert TestClient(app, cookies={"session": old_session}).get("/api/v1/auth/me").status_code == 401
+
+    assert client.post("/api/v1/auth/logout").status_code == 204
+    assert client.get("/api/v1/auth/me").status_code == 401
+"#;
+
+const FASTAPI_TEST_OAUTH: &str = r#"from urllib.parse import urlsplit
+
+from app.api.routes import auth as auth_routes
+
+
+def _local(url: str) -> str:
+    parts = urlsplit(url)
+    return f"{parts.path}?{parts.query}"
+
+
+def test_sign_in_through_mock_provider(monkeypatch):
+    # Serve the provider's token and userinfo calls in-process
+    monkeypatch.setattr(auth_routes, "http", client)
+
+    login = client.get("/api/v1/auth/oauth/login", follow_redirects=False)
+    assert login.status_code == 302
+    authorized = client.get(_local(login.headers["location"]), follow_redirects=False)
+    assert authorized.status_code == 302
+
+    forged = client.get(_local(authorized.headers["location"]).replace("state=", "state=x"), follow_redirects=False)
+    assert forged.status_code == 400
+
+    signed_in = client.get(_local(authorized.headers["location"]), follow_redirects=False)
+    assert signed_in.status_code == 302
+    assert client.get("/api/v1/auth/me").json()["email"] == "dev@example.com"
+"#;
+
+// ============================================================================
+// DJANGO
+// ============================================================================
+
+/// DRF views, URLs and tests in `apps/users`, mounted at `api/auth/`
+pub fn django_files(auth: &AuthSpec) -> Vec<AuthFile> {
+    let (views, urls, tests) = if auth.uses_sessions() {
+        (DJANGO_VIEWS_SESSION, DJANGO_URLS_SESSION, DJANGO_TESTS_SESSION)
+    } else {
+        (DJANGO_VIEWS_JWT, DJANGO_URLS_JWT, DJANGO_TESTS_JWT)
+    };
+    vec![
+        file("apps/users/serializers.py", DJANGO_SERIALIZERS),
+        file("apps/users/views.py", views),
+        file("apps/users/urls.py", urls),
+        file("apps/users/tests.py", tests),
+    ]
+}
+
+/// Block appended to settings.py
+pub fn django_settings(auth: &AuthSpec) -> &'static str {
+    if auth.uses_sessions() {
+        r#"
+# Authentication: server sessions (see apps/users/views.py)
+SESSION_COOKIE_AGE = 60 * 60 * 24 * 7
+SESSION_COOKIE_HTTPONLY = True
+SESSION_COOKIE_SAMESITE = 'Lax'
+SESSION_COOKIE_SECURE = not DEBUG
+CSRF_COOKIE_SECURE = not DEBUG
+"#
+    } else {
+        r#"
+# Authentication: JWT access tokens with rotating refresh tokens (see apps/users/views.py)
+from datetime import timedelta  # noqa: E402
+
+INSTALLED_APPS += ['rest_framework_simplejwt.token_blacklist']
+REST_FRAMEWORK['DEFAULT_AUTHENTICATION_CLASSES'] = [
+    'rest_framework_simplejwt.authentication.JWTAuthentication',
+]
+SIMPLE_JWT = {
+    'ACCESS_TOKEN_LIFETIME': timedelta(minutes=15),
+    'REFRESH_TOKEN_LIFETIME': timedelta(days=30),
+    'SIGNING_KEY': config('JWT_SECRET', default=SECRET_KEY),
+}
+"#
+    }
+}
+
+/// Extra requirement line, if any
+pub fn django_requirements(auth: &AuthSpec) -> &'static str {
+    if auth.uses_sessions() {
+        ""
+    } else {
+        "djangorestframework-simplejwt==5.3.1\n"
+    }
+}
+
+const DJANGO_SERIALIZERS: &str = r#"from django.contrib.auth import get_user_model
+from django.contrib.auth.password_validation import validate_password
+from rest_framework import serializers
+
+User = get_user_model()
+
+
+class UserSerializer(serializers.ModelSerializer):
+    class Meta:
+        model = User
+        fields = ['id', 'username', 'email', 'date_joined']
+
+
+class RegisterSerializer(serializers.ModelSerializer):
+    password = serializers.CharField(write_only=True, validators=[validate_password])
+
+    class Meta:
+        model = User
+        fields = ['id', 'username', 'email', 'password']
+
+    def create(self, validated_data):
+        # create_user hashes the password
+        return User.objects.create_user(**validated_data)
+
+
+class LoginSerializer(serializers.Serializer):
+    username = serializers.CharField()
+    password = serializers.CharField(write_only=True)
+"#;
+
+const DJANGO_VIEWS_SESSION: &str = r#"from django.contrib.auth import authenticate, login, logout
+from django.middleware.csrf import get_token
+from rest_framework import permissions, status
+from rest_framework.decorators import api_view, permission_classes
+from rest_framework.response import Response
+
+from .serializers import LoginSerializer, RegisterSerializer, UserSerializer
+
+
+@api_view(['GET'])
+@permission_classes([permissions.AllowAny])
+def csrf(request):
+    """Sets the CSRF cookie; send its value back in X-CSRFToken on writes"""
+    return Response({'csrfToken': get_token(request)})
+
+
+@api_view(['POST'])
+@permission_classes([permissions.AllowAny])
+def register(request):
+    serializer = RegisterSerializer(data=request.data)
+    serializer.is_valid(raise_exception=True)
+    user = serializer.save()
+    login(request, user)
+    return Response(UserSerializer(user).data, status=status.HTTP_201_CREATED)
+
+
+@api_view(['POST'])
+@permission_classes([permissions.AllowAny])
+def login_view(request):
+    serializer = LoginSerializer(data=request.data)
+    serializer.is_valid(raise_exception=True)
+    user = authenticate(request, **serializer.validated_data)
+    if user is None:
+        return Response({'detail': 'Invalid username or password'}, status=status.HTTP_401_UNAUTHORIZED)
+    # login() issues a new session key, which prevents session fixation
+    login(request, user)
+    return Response(UserSerializer(user).data)
+
+
+@api_view(['POST'])
+@permission_classes([permissions.IsAuthenticated])
+def refresh(request):
+    """Replace the session key and restart its lifetime"""
+    request.session.cycle_key()
+    return Response(UserSerializer(request.user).data)
+
+
+@api_view(['POST'])
+@permission_classes([permissions.AllowAny])
+def logout_view(request):
+    logout(request)
+    return Response(status=status.HTTP_204_NO_CONTENT)
+
+
+@api_view(['GET'])
+@permission_classes([permissions.IsAuthenticated])
+def me(request):
+    return Response(UserSerializer(request.user).data)
+"#;
+
+const DJANGO_VIEWS_JWT: &str = r#"from django.conf import settings
+from django.contrib.auth import authenticate, get_user_model
+from rest_framework import permissions, status
+from rest_framework.decorators import api_view, permission_classes
+from rest_framework.response import Response
+from rest_framework_simplejwt.exceptions import TokenError
+from rest_framework_simplejwt.tokens import RefreshToken
+
+from .serializers import LoginSerializer, RegisterSerializer, UserSerializer
+
+User = get_user_model()
+
+
+def _tokens(user):
+    """OAuth 2 token response fields, shared by every generated backend"""
+    refresh = RefreshToken.for_user(user)
+    return {
+        'user': UserSerializer(user).data,
+        'access_token': str(refresh.access_token),
+        'refresh_token': str(refresh),
+        'token_type': 'Bearer',
+        'expires_in': int(settings.SIMPLE_JWT['ACCESS_TOKEN_LIFETIME'].total_seconds()),
+    }
+
+
+@api_view(['POST'])
+@permission_classes([permissions.AllowAny])
+def register(request):
+    serializer = RegisterSerializer(data=request.data)
+    serializer.is_valid(raise_exception=True)
+    return Response(_tokens(serializer.save()), status=status.HTTP_201_CREATED)
+
+
+@api_view(['POST'])
+@permission_classes([permissions.AllowAny])
+def login_view(request):
+    serializer = LoginSerializer(data=request.data)
+    serializer.is_valid(raise_exception=True)
+    user = authenticate(request, **serializer.validated_data)
+    if user is None:
+        return Response({'detail': 'Invalid username or password'}, status=status.HTTP_401_UNAUTHORIZED)
+    return Response(_tokens(user))
+
+
+@api_view(['POST'])
+@permission_classes([permissions.AllowAny])
+def refresh(request):
+    """Spend a refresh token for a new pair; the old one is blacklisted"""
+    try:
+        old = RefreshToken(request.data.get('refresh_token', ''))
+        user = User.objects.get(pk=old['user_id'], is_active=True)
+        old.blacklist()
+    except (TokenError, User.DoesNotExist):
+        return Response({'detail': 'Invalid refresh token'}, status=status.HTTP_401_UNAUTHORIZED)
+    return Response(_tokens(user))
+
+
+@api_view(['POST'])
+@permission_classes([permissions.AllowAny])
+def logout_view(request):
+    try:
+        RefreshToken(request.data.get('refresh_token', '')).blacklist()
+    except TokenError:
+        pass
+    return Response(status=status.HTTP_204_NO_CONTENT)
+
+
+@api_view(['GET'])
+@permission_classes([permissions.IsAuthenticated])
+def me(request):
+    return Response(UserSerializer(request.user).data)
+"#;
+
+const DJANGO_URLS_SESSION: &str = r#"from django.urls import path
+
+from . import views
+
+urlpatterns = [
+    path('csrf/', views.csrf, name='auth-csrf'),
+    path('register/', views.register, name='auth-register'),
+    path('login/', views.login_view, name='auth-login'),
+    path('refresh/', views.refresh, name='auth-refresh'),
+    path('logout/', views.logout_view, name='auth-logout'),
+    path('me/', views.me, name='auth-me'),
+]
+"#;
+
+const DJANGO_URLS_JWT: &str = r#"from django.urls import path
+
+from . import views
+
+urlpatterns = [
+    path('register/', views.register, name='auth-register'),
+    path('login/', views.login_view, name='auth-login'),
+    path('refresh/', views.refresh, name='auth-refresh'),
+    path('logout/', views.logout_view, name='auth-logout'),
+    path('me/', views.me, name='auth-me'),
+]
+"#;
+
+const DJANGO_TESTS_SESSION: &str = r#"from django.contrib.auth import get_user_model
+from rest_framework.test import APITestCase
+
+CREDENTIALS = {'username': 'ada', 'email': 'ada@example.com', 'password': 'correct-horse-battery'}
+
+
+class SessionAuthTests(APITestCase):
+    def test_register_refresh_and_logout(self):
+        response = self.client.post('/api/auth/register/', CREDENTIALS, format='json')
+        self.assertEqual(response.status_code, 201)
+        self.assertEqual(self.client.get('/api/auth/me/').data['username'], 'ada')
+
+        old_session = self.client.cookies['sessionid'].value
+        self.assertEqual(self.client.post('/api/auth/refresh/').status_code, 200)
+        self.assertNotEqual(self.client.cookies['sessionid'].value, old_session)
+
+        self.assertEqual(self.client.post('/api/auth/logout/').status_code, 204)
+        self.assertEqual(self.client.get('/api/auth/me/').status_code, 403)
+
+    def test_login_rejects_a_wrong_password(self):
+        get_user_model().objects.create_user(**CREDENTIALS)
+        response = self.client.post('/api/auth/login/', {'username': 'ada', 'password': 'wrong'}, format='json')
+        self.assertEqual(response.status_code, 401)
+"#;
+
+const DJANGO_TESTS_JWT: &str = r#"from django.contrib.auth import get_user_model
+from rest_framework.test import APITestCase
+
+CREDENTIALS = {'username': 'ada', 'email': 'ada@example.com', 'password': 'correct-horse-battery'}
+
+
+class JwtAuthTests(APITestCase):
+    def test_register_refresh_and_logout(self):
+        tokens = self.client.post('/api/auth/register/', CREDENTIALS, format='json').data
+        self.client.credentials(HTTP_AUTHORIZATION=f"Bearer {tokens['access_token']}")
+        self.assertEqual(self.client.get('/api/auth/me/').data['username'], 'ada')
+        self.client.credentials()
+        self.assertEqual(self.client.get('/api/auth/me/').status_code, 401)
+
+        refreshed = self.client.post('/api/auth/refresh/', {'refresh_token': tokens['refresh_token']}, format='json')
+        self.assertEqual(refreshed.status_code, 200)
+        # Refresh tokens rotate, so the old one is spent
+        replayed = self.client.post('/api/auth/refresh/', {'refresh_token': tokens['refresh_token']}, format='json')
+        self.assertEqual(replayed.status_code, 401)
+
+        latest = refreshed.data['refresh_token']
+        self.assertEqual(self.client.post('/api/auth/logout/', {'refresh_token': latest}, format='json').status_code, 204)
+        self.assertEqual(self.client.post('/api/auth/refresh/', {'refresh_token': latest}, format='json').status_code, 401)
+
+    def test_login_rejects_a_wrong_password(self):
+        get_user_model().objects.create_user(**CREDENTIALS)
+        response = self.client.post('/api/auth/login/', {'username': 'ada', 'password': 'wrong'}, format='json')
+        self.assertEqual(response.status_code, 401)
+"#;
+
+// ============================================================================
+// GO
+// ============================================================================
+
+/// `internal/auth`: user store, bcrypt hashing, handlers, middleware and tests
+pub fn go_files(auth: &AuthSpec) -> Vec<AuthFile> {
+    let (credentials, test, extra_import) = if auth.uses_sessions() {
+        (GO_SESSION, GO_SESSION_TEST, "")
+    } else {
+        (GO_JWT, GO_JWT_TEST, "\t\"encoding/json\"\n")
+    };
+    let test = format!(
+        "package auth\n\nimport (\n{}\t\"net/http\"\n\t\"net/http/httptest\"\n\t\"strings\"\n\t\"testing\"\n\n\t\"github.com/gin-gonic/gin\"\n)\n{}{}",
+        extra_import, GO_TEST_SETUP, test
+    );
+    vec![
+        file("internal/auth/store.go", GO_STORE),
+        file("internal/auth/handlers.go", GO_HANDLERS),
+        file(format!("internal/auth/{}.go", auth.id), credentials),
+        file("internal/auth/auth_test.go", test),
+    ]
+}
+
+/// Module required by the auth package beyond the stack's go.mod
+pub const GO_REQUIREMENT: (&str, &str) = ("golang.org/x/crypto", "v0.17.0");
+
+const GO_STORE: &str = r#"// Package auth implements sign-up, sign-in and route protection. Users and
+// the credentials issued to them live in memory so the flow runs without
+// setup; back Store with the database before deploying.
+package auth
+
+import (
+	"crypto/rand"
+	"crypto/sha256"
+	"encoding/base64"
+	"encoding/hex"
+	"errors"
+	"strings"
+	"sync"
+	"time"
+
+	"golang.org/x/crypto/bcrypt"
+)
+
+var ErrEmailTaken = errors.New("email already registered")
+
+type User struct {
+	ID           string    `json:"id"`
+	Email        string    `json:"email"`
+	PasswordHash string    `json:"-"`
+	CreatedAt    time.Time `json:"created_at"`
+}
+
+// grant is an opaque credential (refresh token or session id), stored by digest
+type grant struct {
+	userID    string
+	expiresAt time.Time
+}
+
+type Store struct {
+	mu     sync.Mutex
+	users  map[string]*User
+	grants map[string]grant
+}
+
+func NewStore() *Store {
+	return &Store{users: map[string]*User{}, grants: map[string]grant{}}
+}
+
+func (s *Store) CreateUser(email, password string) (*User, error) {
+	hash, err := bcrypt.GenerateFromPassword([]byte(password), bcrypt.DefaultCost)
+	if err != nil {
+		return nil, err
+	}
+	email = strings.ToLower(strings.TrimSpace(email))
+
+	s.mu.Lock()
+	defer s.mu.Unlock()
+	for _, user := range s.users {
+		if user.Email == email {
+			return nil, ErrEmailTaken
+		}
+	}
+	user := &User{ID: randomToken(16), Email: email, PasswordHash: string(hash), CreatedAt: time.Now()}
+	s.users[user.ID] = user
+	return user, nil
+}
+
+// Authenticate returns the user with this email and password
+func (s *Store) Authenticate(email, password string) (*User, bool) {
+	email = strings.ToLower(strings.TrimSpace(email))
+	s.mu.Lock()
+	var found *User
+	for _, user := range s.users {
+		if user.Email == email {
+			found = user
+		}
+	}
+	s.mu.Unlock()
+	if found == nil || bcrypt.CompareHashAndPassword([]byte(found.PasswordHash), []byte(password)) != nil {
+		return nil, false
+	}
+	return found, true
+}
+
+func (s *Store) User(id string) (*User, bool) {
+	s.mu.Lock()
+	defer s.mu.Unlock()
+	user, ok := s.users[id]
+	return user, ok
+}
+
+func (s *Store) issue(userID string, ttl time.Duration) string {
+	token := randomToken(32)
+	s.mu.Lock()
+	defer s.mu.Unlock()
+	s.grants[digest(token)] = grant{userID: userID, expiresAt: time.Now().Add(ttl)}
+	return token
+}
+
+func (s *Store) lookup(token string) (string, bool) {
+	s.mu.Lock()
+	defer s.mu.Unlock()
+	g, ok := s.grants[digest(token)]
+	if !ok || time.Now().After(g.expiresAt) {
+		return "", false
+	}
+	return g.userID, true
+}
+
+// take spends a credential, returning the user it was issued to
+func (s *Store) take(token string) (string, bool) {
+	userID, ok := s.lookup(token)
+	s.revoke(token)
+	return userID, ok
+}
+
+func (s *Store) revoke(token string) {
+	s.mu.Lock()
+	defer s.mu.Unlock()
+	delete(s.grants, digest(token))
+}
+
+func randomToken(size int) string {
+	b := make([]byte, size)
+	if _, err := rand.Read(b); err != nil {
+		panic(err)
+	}
+	return base64.RawURLEncoding.EncodeToString(b)
+}
+
+func digest(token string) string {
+	sum := sha256.Sum256([]byte(token))
+	return hex.EncodeToString(sum[:])
+}
+"#;
+
+const GO_HANDLERS: &str = r#"package auth
+
+import (
+	"errors"
+	"net/http"
+
+	"github.com/gin-gonic/gin"
+)
+
+type credentials struct {
+	Email    string `json:"email" binding:"required,email"`
+	Password string `json:"password" binding:"required,min=8"`
+}
+
+// RegisterRoutes mounts register, login, refresh and logout on r
+func RegisterRoutes(r *gin.RouterGroup, store *Store) {
+	configure()
+	r.POST("/register", func(c *gin.Context) {
+		var body credentials
+		if err := c.ShouldBindJSON(&body); err != nil {
+			c.JSON(http.StatusBadRequest, gin.H{"error": err.Error()})
+			return
+		}
+		user, err := store.CreateUser(body.Email, body.Password)
+		if errors.Is(err, ErrEmailTaken) {
+			c.JSON(http.StatusConflict, gin.H{"error": err.Error()})
+			return
+		}
+		if err != nil {
+			c.JSON(http.StatusInternalServerError, gin.H{"error": err.Error()})
+			return
+		}
+		signIn(c, store, user, http.StatusCreated)
+	})
+
+	r.POST("/login", func(c *gin.Context) {
+		var body credentials
+		if err := c.ShouldBindJSON(&body); err != nil {
+			c.JSON(http.StatusBadRequest, gin.H{"error": err.Error()})
+			return
+		}
+		user, ok := store.Authenticate(body.Email, body.Password)
+		if !ok {
+			c.JSON(http.StatusUnauthorized, gin.H{"error": "invalid email or password"})
+			return
+		}
+		signIn(c, store, user, http.StatusOK)
+	})
+
+	r.POST("/refresh", func(c *gin.Context) { refresh(c, store) })
+	r.POST("/logout", func(c *gin.Context) { logout(c, store) })
+}
+
+// RequireAuth rejects anonymous requests and stores the signed-in *User under "user"
+func RequireAuth(store *Store) gin.HandlerFunc {
+	return func(c *gin.Context) {
+		user, ok := currentUser(c, store)
+		if !ok {
+			c.AbortWithStatusJSON(http.StatusUnauthorized, gin.H{"error": "authentication required"})
+			return
+		}
+		c.Set("user", user)
+		c.Next()
+	}
+}
+"#;
+
+const GO_JWT: &str = r#"package auth
+
+import (
+	"net/http"
+	"os"
+	"strings"
+	"time"
+
+	"github.com/gin-gonic/gin"
+	"github.com/golang-jwt/jwt/v5"
+)
+
+const (
+	accessTokenTTL  = 15 * time.Minute
+	refreshTokenTTL = 30 * 24 * time.Hour
+)
+
+// configure runs when routes are registered, after main has loaded .env.
+// The development secret is public, so release builds refuse to start
+// without their own.
+func configure() {
+	if os.Getenv("JWT_SECRET") == "" && os.Getenv("GIN_MODE") == "release" {
+		panic("JWT_SECRET must be set in production")
+	}
+}
+
+func secret() []byte {
+	if value := os.Getenv("JWT_SECRET"); value != "" {
+		return []byte(value)
+	}
+	return []byte("dev-secret-change-me")
+}
+
+// signIn responds with OAuth 2 token response fields, shared by every generated backend
+func signIn(c *gin.Context, store *Store, user *User, status int) {
+	now := time.Now()
+	claims := jwt.RegisteredClaims{
+		Subject:   user.ID,
+		IssuedAt:  jwt.NewNumericDate(now),
+		ExpiresAt: jwt.NewNumericDate(now.Add(accessTokenTTL)),
+	}
+	accessToken, err := jwt.NewWithClaims(jwt.SigningMethodHS256, claims).SignedString(secret())
+	if err != nil {
+		c.JSON(http.StatusInternalServerError, gin.H{"error": err.Error()})
+		return
+	}
+	c.JSON(status, gin.H{
+		"user":          user,
+		"access_token":  accessToken,
+		"refresh_token": store.issue(user.ID, refreshTokenTTL),
+		"token_type":    "Bearer",
+		"expires_in":    int(accessTokenTTL.Seconds()),
+	})
+}
+
+type refreshRequest struct {
+	RefreshToken string `json:"refresh_token" binding:"required"`
+}
+
+// refresh spends the refresh token and issues a new pair
+func refresh(c *gin.Context, store *Store) {
+	var body refreshRequest
+	if err := c.ShouldBindJSON(&body); err != nil {
+		c.JSON(http.StatusBadRequest, gin.H{"error": err.Error()})
+		return
+	}
+	userID, ok := store.take(body.RefreshToken)
+	user, found := store.User(userID)
+	if !ok || !found {
+		c.JSON(http.StatusUnauthorized, gin.H{"error": "invalid refresh token"})
+		return
+	}
+	signIn(c, store, user, http.StatusOK)
+}
+
+func logout(c *gin.Context, store *Store) {
+	var body refreshRequest
+	if err := c.ShouldBindJSON(&body); err == nil {
+		store.revoke(body.RefreshToken)
+	}
+	c.Status(http.StatusNoContent)
+}
+
+func currentUser(c *gin.Context, store *Store) (*User, bool) {
+	raw, found := strings.CutPrefix(c.GetHeader("Authorization"), "Bearer ")
+	if !found {
+		return nil, false
+	}
+	claims := &jwt.RegisteredClaims{}
+	_, err := jwt.ParseWithClaims(raw, claims, func(*jwt.Token) (any, error) { return secret(), nil },
+		jwt.WithValidMethods([]string{jwt.SigningMethodHS256.Alg()}))
+	if err != nil {
+		return nil, false
+	}
+	return store.User(claims.Subject)
+}
+"#;
+
+const GO_SESSION: &str = r#"package auth
+
+import (
+	"net/http"
+	"time"
+
+	"github.com/gin-gonic/gin"
+)
+
+const (
+	sessionCookie = "session"
+	sessionTTL    = 7 * 24 * time.Hour
+)
+
+// configure has nothing to check: session ids are random and held by the store
+func configure() {}
+
+func setSessionCookie(c *gin.Context, value string, maxAge int) {
+	c.SetSameSite(http.SameSiteLaxMode)
+	c.SetCookie(sessionCookie, value, maxAge, "/", "", gin.Mode() == gin.ReleaseMode, true)
+}
+
+func signIn(c *gin.Context, store *Store, user *User, status int) {
+	// A fresh id on every sign-in prevents session fixation
+	if old, err := c.Cookie(sessionCookie); err == nil {
+		store.revoke(old)
+	}
+	setSessionCookie(c, store.issue(user.ID, sessionTTL), int(sessionTTL.Seconds()))
+	c.JSON(status, gin.H{"user": user})
+}
+
+// refresh replaces the session id and restarts its lifetime
+func refresh(c *gin.Context, store *Store) {
+	old, _ := c.Cookie(sessionCookie)
+	userID, ok := store.take(old)
+	user, found := store.User(userID)
+	if !ok || !found {
+		c.JSON(http.StatusUnauthorized, gin.H{"error": "no active session"})
+		return
+	}
+	setSessionCookie(c, store.issue(user.ID, sessionTTL), int(sessionTTL.Seconds()))
+	c.JSON(http.StatusOK, gin.H{"user": user})
+}
+
+func logout(c *gin.Context, store *Store) {
+	if id, err := c.Cookie(sessionCookie); err == nil {
+		store.revoke(id)
+	}
+	setSessionCookie(c, "", -1)
+	c.Status(http.StatusNoContent)
+}
+
+func currentUser(c *gin.Context, store *Store) (*User, bool) {
+	id, err := c.Cookie(sessionCookie)
+	if err != nil {
+		return nil, false
+	}
+	userID, ok := store.lookup(id)
+	if !ok {
+		return nil, false
+	}
+	return store.User(userID)
+}
+"#;
+
+const GO_TEST_SETUP: &str = r#"
+func newRouter() *gin.Engine {
+	gin.SetMode(gin.TestMode)
+	store := NewStore()
+	r := gin.New()
+	RegisterRoutes(r.Group("/auth"), store)
+	r.GET("/me", RequireAuth(store), func(c *gin.Context) {
+		c.JSON(http.StatusOK, c.MustGet("user"))
+	})
+	return r
+}
+
+func send(r http.Handler, method, path, body string, header http.Header) *httptest.ResponseRecorder {
+	req := httptest.NewRequest(method, path, strings.NewReader(body))
+	req.Header.Set("Content-Type", "application/json")
+	for key, values := range header {
+		req.Header[key] = values
+	}
+	rec := httptest.NewRecorder()
+	r.ServeHTTP(rec, req)
+	return rec
+}
+
+const registration = `{"email":"gopher@example.com","password":"correct horse"}`
+"#;
+
+const GO_JWT_TEST: &str = r#"
+func TestTokenLifecycle(t *testing.T) {
+	r := newRouter()
+
+	rec := send(r, http.MethodPost, "/auth/register", registration, nil)
+	if rec.Code != http.StatusCreated {
+		t.Fatalf("register: got %d", rec.Code)
+	}
+	var tokens struct {
+		AccessToken  string `json:"access_token"`
+		RefreshToken string `json:"refresh_token"`
+	}
+	if err := json.Unmarshal(rec.Body.Bytes(), &tokens); err != nil {
+		t.Fatal(err)
+	}
+
+	bearer := http.Header{"Authorization": {"Bearer " + tokens.AccessToken}}
+	if rec := send(r, http.MethodGet, "/me", "", bearer); rec.Code != http.StatusOK {
+		t.Fatalf("me with token: got %d", rec.Code)
+	}
+	if rec := send(r, http.MethodGet, "/me", "", nil); rec.Code != http.StatusUnauthorized {
+		t.Fatalf("me without token: got %d", rec.Code)
+	}
+	if rec := send(r, http.MethodPost, "/auth/login", `{"email":"gopher@example.com","password":"wrong password"}`, nil); rec.Code != http.StatusUnauthorized {
+		t.Fatalf("login with wrong password: got %d", rec.Code)
+	}
+
+	refresh := `{"refresh_token":"` + tokens.RefreshToken + `"}`
+	if rec := send(r, http.MethodPost, "/auth/refresh", refresh, nil); rec.Code != http.StatusOK {
+		t.Fatalf("refresh: got %d", rec.Code)
+	}
+	// Refresh tokens rotate, so the old one is spent
+	if rec := send(r, http.MethodPost, "/auth/refresh", refresh, nil); rec.Code != http.StatusUnauthorized {
+		t.Fatalf("replayed refresh: got %d", rec.Code)
+	}
+}
+"#;
+
+const GO_SESSION_TEST: &str = r#"
+func sessionHeader(rec *httptest.ResponseRecorder) http.Header {
+	for _, cookie := range rec.Result().Cookies() {
+		if cookie.Name == sessionCookie {
+			return http.Header{"Cookie": {cookie.Name + "=" + cookie.Value}}
+		}
+	}
+	return nil
+}
+
+func TestSessionLifecycle(t *testing.T) {
+	r := newRouter()
+
+	rec := send(r, http.MethodPost, "/auth/register", registration, nil)
+	if rec.Code != http.StatusCreated {
+		t.Fatalf("register: got %d", rec.Code)
+	}
+	session := sessionHeader(rec)
+	if rec := send(r, http.MethodGet, "/me", "", session); rec.Code != http.StatusOK {
+		t.Fatalf("me with session: got %d", rec.Code)
+	}
+	if rec := send(r, http.MethodPost, "/auth/login", `{"email":"gopher@example.com","password":"wrong password"}`, nil); rec.Code != http.StatusUnauthorized {
+		t.Fatalf("login with wrong password: got %d", rec.Code)
+	}
+
+	rec = send(r, http.MethodPost, "/auth/refresh", "", session)
+	if rec.Code != http.StatusOK {
+		t.Fatalf("refresh: got %d", rec.Code)
+	}
+	renewed := sessionHeader(rec)
+	// Refreshing rotates the session id
+	if rec := send(r, http.MethodGet, "/me", "", session); rec.Code != http.StatusUnauthorized {
+		t.Fatalf("me with the replaced session: got %d", rec.Code)
+	}
+
+	if rec := send(r, http.MethodPost, "/auth/logout", "", renewed); rec.Code != http.StatusNoContent {
+		t.Fatalf("logout: got %d", rec.Code)
+	}
+	if rec := send(r, http.MethodGet, "/me", "", renewed); rec.Code != http.StatusUnauthorized {
+		t.Fatalf("me after logout: got %d", rec.Code)
+	}
+}
+"#;
+
+// ============================================================================
+// LARAVEL
+// ============================================================================
+
+/// Session auth on Laravel's `web` guard: user model and migration,
+/// controller, `routes/auth.php` (required from web.php) and a feature test
+pub fn laravel_files() -> Vec<AuthFile> {
+    vec![
+        file("app/Models/User.php", LARAVEL_USER),
+        file("database/migrations/2014_10_12_000000_create_users_table.php", LARAVEL_USERS_MIGRATION),
+        file("app/Http/Controllers/AuthController.php", LARAVEL_CONTROLLER),
+        file("routes/auth.php", LARAVEL_ROUTES),
+        file("tests/TestCase.php", LARAVEL_TEST_CASE),
+        file("tests/CreatesApplication.php", LARAVEL_CREATES_APPLICATION),
+        file("tests/Feature/AuthTest.php", LARAVEL_TEST),
+    ]
+}
+
+const LARAVEL_USER: &str = r#"<?php
+
+namespace App\Models;
+
+use Illuminate\Database\Eloquent\Factories\HasFactory;
+use Illuminate\Foundation\Auth\User as Authenticatable;
+use Illuminate\Notifications\Notifiable;
+
+class User extends Authenticatable
+{
+    use HasFactory, Notifiable;
+
+    protected $fillable = [
+        'name',
+        'email',
+        'password',
+    ];
+
+    protected $hidden = [
+        'password',
+        'remember_token',
+    ];
+
+    protected $casts = [
+        'email_verified_at' => 'datetime',
+        // Hashed with bcrypt on assignment
+        'password' => 'hashed',
+    ];
+}
+"#;
+
+const LARAVEL_USERS_MIGRATION: &str = r#"<?php
+
+use Illuminate\Database\Migrations\Migration;
+use Illuminate\Database\Schema\Blueprint;
+use Illuminate\Support\Facades\Schema;
+
+return new class extends Migration
+{
+    public function up(): void
+    {
+        Schema::create('users', function (Blueprint $table) {
+            $table->id();
+            $table->string('name');
+            $table->string('email')->unique();
+            $table->timestamp('email_verified_at')->nullable();
+            $table->string('password');
+            $table->rememberToken();
+            $table->timestamps();
+        });
+    }
+
+    public function down(): void
+    {
+        Schema::dropIfExists('users');
+    }
+};
+"#;
+
+const LARAVEL_CONTROLLER: &str = r#"<?php
+
+namespace App\Http\Controllers;
+
+use App\Models\User;
+use Illuminate\Http\JsonResponse;
+use Illuminate\Http\Request;
+use Illuminate\Http\Response;
+use Illuminate\Support\Facades\Auth;
+
+class AuthController extends Controller
+{
+    public function register(Request $request): JsonResponse
+    {
+        $validated = $request->validate([
+            'name' => 'required|string|max:255',
+            'email' => 'required|email|max:255|unique:users',
+            'password' => 'required|string|min:8',
+        ]);
+
+        $user = User::create($validated);
+        Auth::login($user);
+        $request->session()->regenerate();
+
+        return response()->json(['user' => $user], 201);
+    }
+
+    public function login(Request $request): JsonResponse
+    {
+        $credentials = $request->validate([
+            'email' => 'required|email',
+            'password' => 'required|string',
+        ]);
+
+        if (! Auth::attempt($credentials)) {
+            return response()->json(['message' => 'Invalid email or password'], 401);
+        }
+        // A fresh session id on every sign-in prevents session fixation
+        $request->session()->regenerate();
+
+        return response()->json(['user' => $request->user()]);
+    }
+
+    /**
+     * Replace the session id and restart its lifetime.
+     */
+    public function refresh(Request $request): JsonResponse
+    {
+        $request->session()->regenerate();
+
+        return response()->json(['user' => $request->user()]);
+    }
+
+    public function logout(Request $request): Response
+    {
+        Auth::guard('web')->logout();
+        $request->session()->invalidate();
+        $request->session()->regenerateToken();
+
+        return response()->noContent();
+    }
+
+    public function me(Request $request): JsonResponse
+    {
+        return response()->json(['user' => $request->user()]);
+    }
+}
+"#;
+
+const LARAVEL_ROUTES: &str = r#"<?php
+
+use App\Http\Controllers\AuthController;
+use Illuminate\Support\Facades\Route;
+
+// Session routes live in the web group for its session and CSRF middleware;
+// SPAs read the XSRF-TOKEN cookie and send it back as X-XSRF-TOKEN
+Route::prefix('api/auth')->group(function () {
+    Route::middleware('guest')->group(function () {
+        Route::post('/register', [AuthController::class, 'register']);
+        Route::post('/login', [AuthController::class, 'login']);
+    });
+
+    Route::middleware('auth')->group(function () {
+        Route::post('/refresh', [AuthController::class, 'refresh']);
+        Route::post('/logout', [AuthController::class, 'logout']);
+        Route::get('/me', [AuthController::class, 'me']);
+    });
+});
+"#;
+
+const LARAVEL_TEST_CASE: &str = r#"<?php
+
+namespace Tests;
+
+use Illuminate\Foundation\Testing\TestCase as BaseTestCase;
+
+abstract class TestCase extends BaseTestCase
+{
+    use CreatesApplication;
+}
+"#;
+
+const LARAVEL_CREATES_APPLICATION: &str = r#"<?php
+
+namespace Tests;
+
+use Illuminate\Contracts\Console\Kernel;
+use Illuminate\Foundation\Application;
+
+trait CreatesApplication
+{
+    public function createApplication(): Application
+    {
+        $app = require __DIR__.'/../bootstrap/app.php';
+
+        $app->make(Kernel::class)->bootstrap();
+
+        return $app;
+    }
+}
+"#;
+
+const LARAVEL_TEST: &str = r#"<?php
+
+namespace Tests\Feature;
+
+use App\Models\User;
+use Illuminate\Foundation\Testing\RefreshDatabase;
+use Tests\TestCase;
+
+class AuthTest extends TestCase
+{
+    use RefreshDatabase;
+
+    public function test_register_refresh_and_logout(): void
+    {
+        $this->postJson('/api/auth/register', [
+            'name' => 'Ada',
+            'email' => 'ada@example.com',
+            'password' => 'correct horse',
+        ])->assertCreated();
+        $this->assertAuthenticated();
+
+        $this->getJson('/api/auth/me')->assertOk()->assertJsonPath('user.email', 'ada@example.com');
+        $this->postJson('/api/auth/refresh')->assertOk();
+
+        $this->postJson('/api/auth/logout')->assertNoContent();
+        $this->assertGuest();
+    }
+
+    public function test_login_rejects_a_wrong_password(): void
+    {
+        User::create(['name' => 'Ada', 'email' => 'ada@example.com', 'password' => 'correct horse']);
+
+        $this->postJson('/api/auth/login', ['email' => 'ada@example.com', 'password' => 'wrong password'])
+            ->assertUnauthorized();
+        $this->assertGuest();
+    }
+
+    public function test_me_requires_a_session(): void
+    {
+        $this->getJson('/api/auth/me')->assertUnauthorized();
+    }
+}
+"#;
+
+// ============================================================================
+// EXPO
+// ============================================================================
+
+/// Token client for a JWT backend: secure storage, authorized fetch with
+/// one refresh-and-retry on 401, and sign-out
+pub fn expo_client() -> Vec<AuthFile> {
+    vec![file("lib/auth.ts", EXPO_AUTH_CLIENT)]
+}
+
+/// Package added to the Expo app for `expo_client`
+pub const EXPO_DEPENDENCY: (&str, &str) = ("expo-secure-store", "~12.8.1");
+
+const EXPO_AUTH_CLIENT: &str = r#"import * as SecureStore from 'expo-secure-store';
+
+// Auth endpoints of the backend; every generated backend shares the same
+// OAuth 2 style token fields
+const AUTH_URL = process.env.EXPO_PUBLIC_AUTH_URL ?? 'http://localhost:8000/api/v1/auth';
+
+const ACCESS_TOKEN = 'auth.accessToken';
+const REFRESH_TOKEN = 'auth.refreshToken';
+
+type TokenResponse = { access_token: string; refresh_token: string };
+
+async function store(tokens: TokenResponse) {
+  await SecureStore.setItemAsync(ACCESS_TOKEN, tokens.access_token);
+  await SecureStore.setItemAsync(REFRESH_TOKEN, tokens.refresh_token);
+}
+
+async function post(path: string, body: unknown): Promise<Response> {
+  return fetch(`${AUTH_URL}/${path}`, {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify(body),
+  });
+}
+
+async function signInWith(path: string, body: Record<string, string>) {
+  const response = await post(path, body);
+  if (!response.ok) throw new Error(`Sign-in failed with ${response.status}`);
+  await store(await response.json());
+}
+
+/** Create an account; pass the fields your backend's register endpoint expects */
+export const register = (fields: Record<string, string>) => signInWith('register', fields);
+
+/** `identifier` is sent as both email and username, whichever the backend uses */
+export const login = (identifier: string, password: string) =>
+  signInWith('login', { email: identifier, username: identifier, password });
+
+/** Swap the stored refresh token for a new pair; false once the session is over */
+async function refresh(): Promise<boolean> {
+  const refreshToken = await SecureStore.getItemAsync(REFRESH_TOKEN);
+  if (!refreshToken) return false;
+  const response = await post('refresh', { refresh_token: refreshToken });
+  if (!response.ok) {
+    await clear();
+    return false;
+  }
+  await store(await response.json());
+  return true;
+}
+
+/** fetch with the access token, refreshing it once if the backend rejects it */
+export async function authFetch(input: string, init: RequestInit = {}): Promise<Response> {
+  const send = async () => {
+    const token = await SecureStore.getItemAsync(ACCESS_TOKEN);
+    const headers = new Headers(init.headers);
+    if (token) headers.set('Authorization', `Bearer ${token}`);
+    return fetch(input, { ...init, headers });
+  };
+  const response = await send();
+  return response.status === 401 && (await refresh()) ? send() : response;
+}
+
+export async function logout() {
+  const refreshToken = await SecureStore.getItemAsync(REFRESH_TOKEN);
+  if (refreshToken) await post('logout', { refresh_token: refreshToken }).catch(() => undefined);
+  await clear();
+}
+
+async function clear() {
+  await SecureStore.deleteItemAsync(ACCESS_TOKEN);
+  await SecureStore.deleteItemAsync(REFRESH_TOKEN);
+}
+"#;
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_node_core_files_per_method() {
+        let jwt = auth_spec(Some("jwt")).unwrap();
+        let files = node_core_files(jwt, "src/lib/auth", "http://localhost:3000", "demo", None);
+        let names: Vec<&str> = files.iter().map(|(path, _)| path.as_str()).collect();
+        assert!(names.contains(&"src/lib/auth/tokens.js"));
+        assert!(names.contains(&"src/lib/auth/password.js"));
+        assert!(!names.contains(&"src/lib/auth/sessions.js"));
+        let config = &files.iter().find(|(path, _)| path.ends_with("config.js")).unwrap().1;
+        assert!(config.contains("'http://localhost:3000'"));
+
+        let oauth = auth_spec(Some("oauth")).unwrap();
+        let files = node_core_files(oauth, "server/auth", "http://localhost:5000", "demo", None);
+        let names: Vec<&str> = files.iter().map(|(path, _)| path.as_str()).collect();
+        assert!(names.contains(&"server/auth/mock-provider.js"));
+        assert!(names.contains(&"server/auth/sessions.js"));
+        assert!(!names.contains(&"server/auth/password.js"));
+        let env = oauth.env_block("http://localhost:5000");
+        assert!(env.contains("APP_URL=http://localhost:5000\n"));
+        assert!(env.contains("OAUTH_CLIENT_ID=dev-client\n"));
+
+        assert!(auth_spec(Some("none")).is_none());
+        assert!(auth_spec(None).is_none());
+    }
+
+    #[test]
+    fn test_node_core_persists_to_the_selected_database() {
+        let jwt = auth_spec(Some("jwt")).unwrap();
+        let content = |files: &[AuthFile], name: &str| {
+            files.iter().find(|(path, _)| path.ends_with(name)).map(|(_, c)| c.clone())
+        };
+
+        let memory = node_core_files(jwt, "src/lib/auth", "http://localhost:3000", "demo", None);
+        let store = content(&memory, "/store.js").unwrap();
+        assert!(store.contains("} = memory;"));
+        assert!(!store.contains("import('"));
+        assert!(content(&memory, "0002_auth.sql").is_none());
+
+        let users = UserColumns {
+            name: "full_name",
+            password: "hashed_password",
+            username: Some("username"),
+            created: "created_at",
+        };
+        let postgres = database_scaffold::database_spec(Some("postgresql")).unwrap();
+        let database = NodeAuthDatabase { db: postgres, connection: "../db", users: &users };
+        let files = node_core_files(jwt, "src/lib/auth", "http://localhost:3000", "demo", Some(&database));
+        let store = content(&files, "/store.js").unwrap();
+        assert!(store.contains("import('../db')"));
+        assert!(store.contains("INSERT INTO users (email, username, full_name, hashed_password) VALUES (?, ?, ?, ?)"));
+        assert!(store.contains("ON CONFLICT (id) DO UPDATE SET data = excluded.data"));
+        assert!(store.contains("`$${++index}`"));
+        let migration = content(&files, "db/migrations/0002_auth.sql").unwrap();
+        assert!(migration.starts_with("-- Auth records for PostgreSQL."));
+        assert!(migration.contains("CREATE TABLE IF NOT EXISTS \"auth_records\""));
+        assert!(content(&files, "/tokens.js").unwrap().contains("await putRecord(refreshKey(token)"));
+
+        let mysql = NodeAuthDatabase { db: database_scaffold::database_spec(Some("mysql")).unwrap(), ..database };
+        let files = node_core_files(jwt, "src/lib/auth", "http://localhost:3000", "demo", Some(&mysql));
+        let store = content(&files, "/store.js").unwrap();
+        assert!(store.contains("ON DUPLICATE KEY UPDATE data = VALUES(data)"));
+
+        let mern = UserColumns { name: "name", password: "password", username: None, created: "createdAt" };
+        let mongodb = database_scaffold::database_spec(Some("mongodb")).unwrap();
+        let database = NodeAuthDatabase { db: mongodb, connection: "../db.js", users: &mern };
+        let files = node_core_files(jwt, "server/auth", "http://localhost:5000", "demo", Some(&database));
+        let store = content(&files, "/store.js").unwrap();
+        assert!(store.starts_with("// Users live in the `users` table"));
+        assert!(store.contains("import { ObjectId } from 'mongodb';"));
+        assert!(store.contains("import('../db.js')"));
+        assert!(store.contains("const document = { email, name, password: passwordHash ?? NO_PASSWORD, createdAt: new Date() };"));
+        assert!(content(&files, "db/migrations/0002_auth.json").unwrap().contains("\"auth_records\""));
+    }
+
+    #[test]
+    fn test_go_release_builds_require_a_jwt_secret() {
+        let jwt = go_files(auth_spec(Some("jwt")).unwrap());
+        let content = |name: &str| jwt.iter().find(|(path, _)| path.ends_with(name)).unwrap().1.clone();
+        assert!(content("jwt.go").contains("if os.Getenv(\"JWT_SECRET\") == \"\" && os.Getenv(\"GIN_MODE\") == \"release\" {\n\t\tpanic("));
+        assert!(content("handlers.go").contains("func RegisterRoutes(r *gin.RouterGroup, store *Store) {\n\tconfigure()\n"));
+
+        // Every credentials file provides the hook the shared handlers call
+        let session = go_files(auth_spec(Some("session")).unwrap());
+        assert!(session.iter().any(|(path, content)| path.ends_with("session.go") && content.contains("func configure() {}")));
+    }
+}
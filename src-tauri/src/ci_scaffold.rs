@@ -0,0 +1,439 @@
+//! CI Scaffold - Continuous integration workflows for generated projects
+//!
+//! Every app in a project is described once as a [`CiApp`]: the toolchain it
+//! needs and the install, lint, typecheck, test and build commands of its
+//! stack. This module turns each app into one checking job, plus a job that
+//! builds its Docker image, in the syntax of the selected provider: GitHub
+//! Actions, GitLab CI, or Gitea and Forgejo Actions. Workflows are checked
+//! against the provider's schema before they are returned, so a malformed job
+//! fails generation instead of the first push.
+
+use crate::docker_scaffold::{GO_VERSION, NODE_VERSION, PYTHON_VERSION};
+use crate::yaml_writer::{list, map, str, Yaml};
+
+// ============================================================================
+// PROVIDERS
+// ============================================================================
+
+pub struct CiProvider {
+    pub id: &'static str, // "github-actions", "gitlab-ci", "gitea-actions", "forgejo-actions"
+    pub name: &'static str,
+    /// Workflow file, relative to the repository root
+    pub path: &'static str,
+    /// Prefix for `uses:` references; Forgejo resolves bare names against
+    /// its own action mirror
+    action_host: &'static str,
+}
+
+pub const CI_PROVIDERS: &[CiProvider] = &[
+    CiProvider { id: "github-actions", name: "GitHub Actions", path: ".github/workflows/ci.yml", action_host: "" },
+    CiProvider { id: "gitlab-ci", name: "GitLab CI", path: ".gitlab-ci.yml", action_host: "" },
+    CiProvider { id: "gitea-actions", name: "Gitea Actions", path: ".gitea/workflows/ci.yml", action_host: "" },
+    CiProvider {
+        id: "forgejo-actions",
+        name: "Forgejo Actions",
+        path: ".forgejo/workflows/ci.yml",
+        action_host: "https://github.com/",
+    },
+];
+
+/// Look up a provider by the id `infra_detector` reports for its workflows
+pub fn ci_provider(id: &str) -> Option<&'static CiProvider> {
+    CI_PROVIDERS.iter().find(|provider| provider.id == id)
+}
+
+impl CiProvider {
+    pub fn is_gitlab(&self) -> bool {
+        self.id == "gitlab-ci"
+    }
+
+    fn action(&self, name: &str) -> Yaml {
+        str(format!("{}{}", self.action_host, name))
+    }
+}
+
+// ============================================================================
+// TOOLCHAINS
+// ============================================================================
+
+pub struct CiToolchain {
+    /// Component and runtime languages the toolchain builds
+    pub languages: &'static [&'static str],
+    /// Image GitLab runs the job in and its tag; runtime releases are shared
+    /// with the Dockerfiles
+    pub image: (&'static str, &'static str),
+    /// Setup action and its inputs; None when runners already have the toolchain
+    pub setup: Option<(&'static str, &'static [(&'static str, &'static str)])>,
+    /// Dependency caches: path on an Actions runner, then the variable that
+    /// points the tool at a project-local directory GitLab can cache
+    pub caches: &'static [(&'static str, &'static str, &'static str)],
+    /// Manifest whose contents key the cache
+    pub manifest: &'static str,
+    /// Commands for apps without stack-specific checks
+    pub install: &'static str,
+    pub test: &'static str,
+}
+
+pub const CI_TOOLCHAINS: &[CiToolchain] = &[
+    CiToolchain {
+        languages: &["node", "typescript", "javascript"],
+        image: ("node", NODE_VERSION),
+        setup: Some(("actions/setup-node@v4", &[("node-version", NODE_VERSION)])),
+        caches: &[("~/.npm", "npm_config_cache", ".npm")],
+        manifest: "package.json",
+        install: "npm install",
+        test: "npm test --if-present",
+    },
+    CiToolchain {
+        languages: &["python"],
+        image: ("python", PYTHON_VERSION),
+        setup: Some(("actions/setup-python@v5", &[("python-version", PYTHON_VERSION)])),
+        caches: &[("~/.cache/pip", "PIP_CACHE_DIR", ".cache/pip")],
+        manifest: "requirements.txt",
+        install: "pip install -r requirements.txt",
+        test: "python -m pytest",
+    },
+    CiToolchain {
+        languages: &["go"],
+        image: ("golang", GO_VERSION),
+        // The action's own cache needs a go.sum the project may not have yet
+        setup: Some(("actions/setup-go@v5", &[("go-version", GO_VERSION), ("cache", "false")])),
+        caches: &[("~/go/pkg/mod", "GOMODCACHE", ".cache/go-mod"), ("~/.cache/go-build", "GOCACHE", ".cache/go-build")],
+        manifest: "go.mod",
+        install: "go mod download",
+        test: "go test ./...",
+    },
+    CiToolchain {
+        languages: &["php"],
+        image: ("composer", "2"),
+        setup: Some(("shivammathur/setup-php@v2", &[("php-version", "8.3"), ("tools", "composer")])),
+        caches: &[("~/.cache/composer", "COMPOSER_CACHE_DIR", ".cache/composer")],
+        manifest: "composer.json",
+        install: "composer install --no-interaction",
+        test: "vendor/bin/phpunit",
+    },
+    CiToolchain {
+        languages: &["rust"],
+        image: ("rust", "1"),
+        setup: None,
+        caches: &[("~/.cargo/registry", "CARGO_HOME", ".cargo")],
+        manifest: "Cargo.toml",
+        install: "cargo fetch",
+        test: "cargo test",
+    },
+];
+
+pub fn ci_toolchain(language: &str) -> Option<&'static CiToolchain> {
+    CI_TOOLCHAINS.iter().find(|toolchain| toolchain.languages.contains(&language))
+}
+
+/// What a stack checks in CI before its production build
+pub struct CiChecks {
+    /// Install including the dev tooling the checks use
+    pub install: &'static str,
+    pub lint: Option<&'static str>,
+    pub typecheck: Option<&'static str>,
+    pub test: Option<&'static str>,
+}
+
+// ============================================================================
+// JOBS
+// ============================================================================
+
+/// One app as CI sees it
+pub struct CiApp<'a> {
+    /// Job id; the image job is `<name>-docker`
+    pub name: String,
+    /// Working directory relative to the repository root; None for the root
+    pub dir: Option<String>,
+    pub toolchain: &'a CiToolchain,
+    pub install: String,
+    pub lint: Option<String>,
+    pub typecheck: Option<String>,
+    pub tests: Vec<String>,
+    pub build: Option<String>,
+    /// Build the Dockerfile in `dir` once the checks pass
+    pub docker: bool,
+}
+
+impl<'a> CiApp<'a> {
+    /// An app checked with the toolchain's default install and test commands
+    pub fn new(name: &str, dir: Option<&str>, toolchain: &'a CiToolchain) -> Self {
+        CiApp {
+            name: name.to_string(),
+            dir: dir.map(str::to_string),
+            toolchain,
+            install: toolchain.install.to_string(),
+            lint: None,
+            typecheck: None,
+            tests: vec![toolchain.test.to_string()],
+            build: None,
+            docker: false,
+        }
+    }
+
+    /// Named commands in the order the job runs them
+    fn steps(&self) -> Vec<(&'static str, &str)> {
+        let mut steps = vec![("Install", self.install.as_str())];
+        steps.extend(self.lint.as_deref().map(|command| ("Lint", command)));
+        steps.extend(self.typecheck.as_deref().map(|command| ("Typecheck", command)));
+        steps.extend(self.tests.iter().map(|command| ("Test", command.as_str())));
+        steps.extend(self.build.as_deref().map(|command| ("Build", command)));
+        steps
+    }
+
+    fn in_dir(&self, file: &str) -> String {
+        match &self.dir {
+            Some(dir) => format!("{}/{}", dir, file),
+            None => file.to_string(),
+        }
+    }
+
+    fn docker_build(&self) -> String {
+        format!("docker build -t {}:ci {}", self.name, self.dir.as_deref().unwrap_or("."))
+    }
+}
+
+/// The checking job for `app` and, when it has a Dockerfile, its image job
+pub fn app_jobs(provider: &CiProvider, app: &CiApp) -> Vec<(String, Yaml)> {
+    if provider.is_gitlab() {
+        gitlab_jobs(app)
+    } else {
+        actions_jobs(provider, app)
+    }
+}
+
+fn actions_jobs(provider: &CiProvider, app: &CiApp) -> Vec<(String, Yaml)> {
+    let checkout = map([("uses", provider.action("actions/checkout@v4"))]);
+    let mut job = map([("runs-on", str("ubuntu-latest"))]);
+    if let Some(dir) = &app.dir {
+        job.insert("defaults", map([("run", map([("working-directory", str(dir))]))]));
+    }
+
+    let mut steps = vec![checkout.clone()];
+    if let Some((action, inputs)) = app.toolchain.setup {
+        steps.push(map([
+            ("uses", provider.action(action)),
+            ("with", map(inputs.iter().map(|(key, value)| (*key, str(*value))))),
+        ]));
+    }
+    if !app.toolchain.caches.is_empty() {
+        let paths: Vec<&str> = app.toolchain.caches.iter().map(|(path, _, _)| *path).collect();
+        let key = format!(
+            "${{{{ runner.os }}}}-{}-${{{{ hashFiles('{}') }}}}",
+            app.name,
+            app.in_dir(&format!("**/{}", app.toolchain.manifest))
+        );
+        steps.push(map([
+            ("name", str("Cache dependencies")),
+            ("uses", provider.action("actions/cache@v4")),
+            ("with", map([("path", str(paths.join("\n"))), ("key", str(key))])),
+        ]));
+    }
+    for (name, command) in app.steps() {
+        steps.push(map([("name", str(name)), ("run", str(command))]));
+    }
+    job.insert("steps", list(steps));
+
+    let mut jobs = vec![(app.name.clone(), job)];
+    if app.docker {
+        jobs.push((
+            format!("{}-docker", app.name),
+            map([
+                ("runs-on", str("ubuntu-latest")),
+                ("needs", list([str(&app.name)])),
+                (
+                    "steps",
+                    list([checkout, map([("name", str("Build image")), ("run", str(app.docker_build()))])]),
+                ),
+            ]),
+        ));
+    }
+    jobs
+}
+
+fn gitlab_jobs(app: &CiApp) -> Vec<(String, Yaml)> {
+    let caches = app.toolchain.caches;
+    let mut job = map([("stage", str("test")), ("image", str(format!("{}:{}", app.toolchain.image.0, app.toolchain.image.1)))]);
+    if !caches.is_empty() {
+        // GitLab only caches paths inside the project directory
+        job.insert(
+            "variables",
+            map(caches.iter().map(|(_, variable, dir)| (*variable, str(format!("$CI_PROJECT_DIR/{}", dir))))),
+        );
+        job.insert(
+            "cache",
+            map([
+                ("key", map([("files", list([str(app.in_dir(app.toolchain.manifest))])), ("prefix", str(&app.name))])),
+                ("paths", list(caches.iter().map(|(_, _, dir)| str(*dir)))),
+            ]),
+        );
+    }
+    let mut script: Vec<Yaml> = app.dir.iter().map(|dir| str(format!("cd {}", dir))).collect();
+    script.extend(app.steps().into_iter().map(|(_, command)| str(command)));
+    job.insert("script", list(script));
+
+    let mut jobs = vec![(app.name.clone(), job)];
+    if app.docker {
+        jobs.push((
+            format!("{}-docker", app.name),
+            map([
+                ("stage", str("docker")),
+                ("image", str("docker:27")),
+                ("services", list([str("docker:27-dind")])),
+                ("variables", map([("DOCKER_TLS_CERTDIR", str("/certs"))])),
+                ("needs", list([str(&app.name)])),
+                ("script", list([str(app.docker_build())])),
+            ]),
+        ));
+    }
+    jobs
+}
+
+// ============================================================================
+// WORKFLOWS
+// ============================================================================
+
+/// The provider's workflow file for `apps`, checked before it is returned
+pub fn ci_file(provider: &CiProvider, apps: &[CiApp]) -> Result<(String, String), String> {
+    let jobs: Vec<(String, Yaml)> = apps.iter().flat_map(|app| app_jobs(provider, app)).collect();
+    let document = if provider.is_gitlab() {
+        let mut stages = vec![str("test")];
+        if apps.iter().any(|app| app.docker) {
+            stages.push(str("docker"));
+        }
+        let mut pipeline = map([("stages", list(stages))]);
+        for (id, job) in jobs {
+            pipeline.insert(&id, job);
+        }
+        pipeline
+    } else {
+        map([
+            ("name", str("CI")),
+            (
+                "on",
+                map([("push", map([("branches", list([str("main")]))])), ("pull_request", map::<&str>([]))]),
+            ),
+            ("jobs", map(jobs)),
+        ])
+    };
+    check(provider, &document)?;
+    Ok((provider.path.to_string(), document.to_yaml()))
+}
+
+/// Top-level GitLab keys that configure the pipeline rather than name a job
+const GITLAB_KEYWORDS: &[&str] = &[
+    "stages", "variables", "default", "workflow", "include", "image", "services", "cache", "before_script", "after_script",
+];
+
+/// Check a workflow against the provider's schema: every job has what the
+/// runner needs to start it, and `needs` only names jobs that exist
+pub fn check(provider: &CiProvider, document: &Yaml) -> Result<(), String> {
+    let path = provider.path;
+    let jobs: Vec<(&str, &Yaml)> = if provider.is_gitlab() {
+        document.require(path, &["stages.0"])?;
+        match document {
+            Yaml::Map(entries) => entries
+                .iter()
+                .filter(|(key, _)| !GITLAB_KEYWORDS.contains(&key.as_str()) && !key.starts_with('.'))
+                .map(|(key, job)| (key.as_str(), job))
+                .collect(),
+            _ => Vec::new(),
+        }
+    } else {
+        document.require(path, &["name", "on", "jobs"])?;
+        match document.get("jobs") {
+            Some(Yaml::Map(entries)) => entries.iter().map(|(key, job)| (key.as_str(), job)).collect(),
+            _ => Vec::new(),
+        }
+    };
+    if jobs.is_empty() {
+        return Err(format!("{}: no jobs defined", path));
+    }
+
+    let stages: Vec<&str> = match document.get("stages") {
+        Some(Yaml::List(items)) => items.iter().filter_map(Yaml::as_str).collect(),
+        _ => Vec::new(),
+    };
+    for (id, job) in &jobs {
+        let label = format!("{} job '{}'", path, id);
+        if provider.is_gitlab() {
+            job.require(&label, &["stage", "image", "script.0"])?;
+            let stage = job.get("stage").and_then(Yaml::as_str).unwrap_or_default();
+            if !stages.contains(&stage) {
+                return Err(format!("{}: stage '{}' is not listed in 'stages'", label, stage));
+            }
+        } else {
+            job.require(&label, &["runs-on", "steps.0"])?;
+            if let Some(Yaml::List(steps)) = job.get("steps") {
+                for (i, step) in steps.iter().enumerate() {
+                    match (step.get("uses").and_then(Yaml::as_str), step.get("run")) {
+                        (Some(action), None) if action.contains('@') => {}
+                        (Some(action), None) => return Err(format!("{} step {}: '{}' is not pinned to a version", label, i, action)),
+                        (None, Some(_)) => {}
+                        _ => return Err(format!("{} step {}: needs exactly one of 'uses' or 'run'", label, i)),
+                    }
+                }
+            }
+        }
+        if let Some(Yaml::List(needs)) = job.get("needs") {
+            for need in needs.iter().filter_map(Yaml::as_str) {
+                if !jobs.iter().any(|(other, _)| *other == need) {
+                    return Err(format!("{}: needs unknown job '{}'", label, need));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apps() -> Vec<CiApp<'static>> {
+        let node = ci_toolchain("typescript").unwrap();
+        let web = CiApp {
+            lint: Some("npm run lint".to_string()),
+            typecheck: Some("npx tsc --noEmit".to_string()),
+            build: Some("npm run build".to_string()),
+            docker: true,
+            ..CiApp::new("web", Some("apps/web"), node)
+        };
+        vec![web, CiApp::new("api", Some("apps/api"), ci_toolchain("go").unwrap())]
+    }
+
+    #[test]
+    fn test_ci_file_per_provider() {
+        let (path, github) = ci_file(ci_provider("github-actions").unwrap(), &apps()).unwrap();
+        assert_eq!(path, ".github/workflows/ci.yml");
+        assert!(github.starts_with("name: CI\n\"on\":\n  push:\n    branches:\n      - main\n  pull_request: {}\njobs:\n  web:\n    runs-on: ubuntu-latest\n"));
+        assert!(github.contains("        with:\n          path: ~/.npm\n          key: ${{ runner.os }}-web-${{ hashFiles('apps/web/**/package.json') }}\n"));
+        assert!(github.contains("      - name: Typecheck\n        run: npx tsc --noEmit\n      - name: Test\n        run: npm test --if-present\n      - name: Build\n        run: npm run build\n"));
+        assert!(github.contains("  web-docker:\n    runs-on: ubuntu-latest\n    needs:\n      - web\n"));
+        assert!(github.contains("        run: docker build -t web:ci apps/web\n"));
+        assert!(!github.contains("api-docker"));
+
+        let (_, forgejo) = ci_file(ci_provider("forgejo-actions").unwrap(), &apps()).unwrap();
+        assert!(forgejo.contains("      - uses: https://github.com/actions/checkout@v4\n"));
+
+        let (path, gitlab) = ci_file(ci_provider("gitlab-ci").unwrap(), &apps()).unwrap();
+        assert_eq!(path, ".gitlab-ci.yml");
+        assert!(gitlab.starts_with("stages:\n  - test\n  - docker\nweb:\n  stage: test\n  image: node:22\n  variables:\n    npm_config_cache: $CI_PROJECT_DIR/.npm\n"));
+        assert!(gitlab.contains("  script:\n    - cd apps/api\n    - go mod download\n    - go test ./...\n"));
+
+        // The schema check rejects jobs a runner could not start
+        let provider = ci_provider("gitlab-ci").unwrap();
+        let mut pipeline = map([("stages", list([str("test")]))]);
+        for (id, job) in app_jobs(provider, &apps()[0]) {
+            pipeline.insert(&id, job);
+        }
+        assert_eq!(check(provider, &pipeline), Err(".gitlab-ci.yml job 'web-docker': stage 'docker' is not listed in 'stages'".to_string()));
+        let workflow = map([("name", str("CI")), ("on", str("push")), ("jobs", map([("web", map([("runs-on", str("ubuntu-latest")), ("needs", list([str("lint")])), ("steps", list([map([("run", str("make"))])]))]))]))]);
+        assert_eq!(check(ci_provider("gitea-actions").unwrap(), &workflow), Err(".gitea/workflows/ci.yml job 'web': needs unknown job 'lint'".to_string()));
+    }
+}
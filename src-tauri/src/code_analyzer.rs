@@ -1,8 +1,22 @@
+use crate::analysis_cache::{AnalysisCache, CacheStats, CachedFile};
+use crate::analysis_progress::{self, AnalysisProgress, AnalysisRegistry};
+use crate::architecture::{self, ArchitectureProfile};
+use crate::detection_rules::{self, DetectionContext, DetectionRuleSet, RuleEngine, RuleMatch, USER_RULES_FILE};
+use crate::findings::{self, Finding};
+use crate::import_graph::{self, ImportGraph, ModuleSource};
+use crate::infra_detector::{self, CiProfile, InfrastructureProfile};
+use crate::project_generator::STACK_CATALOG;
+use crate::relative_path::file_name;
+use crate::testing::{self, TestingProfile};
+use rayon::prelude::*;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::Path;
-use walkdir::WalkDir;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::OnceLock;
+use tauri::Manager;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DetectedLanguage {
@@ -20,6 +34,7 @@ pub struct DetectedFramework {
     pub category: String,
     pub confidence: f64,
     pub indicators: Vec<String>,
+    pub sources: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -32,11 +47,31 @@ pub struct DetectedDependency {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct DetectedDatabase {
+pub struct DetectedDataStore {
+    pub kind: String, // "database", "broker"
     #[serde(rename = "type")]
-    pub db_type: String,
+    pub store_type: String,
+    pub category: Option<String>, // "sql", "document", "key-value" for databases
     pub confidence: f64,
     pub indicators: Vec<String>,
+    pub sources: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DetectedOrm {
+    pub name: String,
+    pub category: String, // "orm", "odm", "query-builder"
+    pub version: Option<String>,
+    pub confidence: f64,
+    pub indicators: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectedMigrations {
+    pub path: String,
+    pub tool: Option<String>,
+    pub file_count: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -60,12 +95,34 @@ pub struct ProjectProfile {
     pub has_frontend: bool,
     pub has_mobile: bool,
     pub has_tests: bool,
-    pub database: Option<DetectedDatabase>,
+    pub testing: TestingProfile,
+    pub data_stores: Vec<DetectedDataStore>,
+    pub orms: Vec<DetectedOrm>,
+    pub migrations: Vec<DetectedMigrations>,
     pub authentication: Option<DetectedAuthentication>,
+    pub authentication_methods: Vec<DetectedAuthentication>,
     pub has_docker: bool,
+    #[serde(rename = "hasCI")]
     pub has_ci: bool,
+    pub ci: CiProfile,
+    pub infrastructure: InfrastructureProfile,
+    pub architecture: ArchitectureProfile,
+    pub import_graphs: Vec<ImportGraph>,
+    pub findings: Vec<Finding>,
+    pub suggested_deployment_platform: Option<String>,
     pub suggested_stack_id: Option<String>,
     pub stack_match_confidence: Option<f64>,
+    pub stack_matches: Vec<StackMatch>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StackMatch {
+    pub stack_id: String,
+    pub name: String,
+    pub score: f64,
+    pub matched: Vec<String>,
+    pub missing: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -76,12 +133,85 @@ pub struct AnalysisResult {
     pub error: Option<String>,
     pub analysis_time_ms: u64,
     pub files_scanned: usize,
+    pub cache_stats: CacheStats,
+    pub scan_stats: ScanStats,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanStats {
+    pub truncated: bool, // The max files budget was reached and some files were not listed
+    pub max_files: usize,
+    pub max_file_size_bytes: u64,
+    pub large_files_skipped: usize,  // Listed but not read for line counts and imports
+    pub binary_files_skipped: usize, // Binary or non-UTF-8 files with a source extension
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalyzerOptions {
+    pub max_depth: Option<usize>,
+    pub max_files: Option<usize>,
+    pub max_file_size_bytes: Option<u64>,
+}
+
+/// Default budget of files listed per analysis
+const DEFAULT_MAX_FILES: usize = 20_000;
+/// The walk stops descending once it has listed this many entries per file of budget
+const LISTING_HEADROOM: usize = 4;
+/// Default size above which source files are listed but not read
+const DEFAULT_MAX_FILE_SIZE: u64 = 1024 * 1024;
+/// Leading bytes checked for NUL when deciding whether a file is binary
+const BINARY_SNIFF_BYTES: usize = 8000;
+/// Best stack match needed before a stack is suggested
+const MIN_STACK_SCORE: f64 = 0.5;
+
+/// A file or directory found while listing the project
+struct ListedEntry {
+    relative: String,
+    fingerprint: Option<(u64, u64)>, // `(mtime_ns, size)` for files, `None` for directories
+}
+
+struct FileListing {
+    entries: Vec<ListedEntry>,
+    truncated: bool,
+}
+
+/// Entries counted across the parallel walk, which stops descending at `limit`
+struct Walk {
+    listed: AtomicUsize,
+    limit: usize,
+    stopped: AtomicBool,
+}
+
+impl AnalysisResult {
+    fn cancelled(start_time: std::time::Instant, files_scanned: usize) -> Self {
+        AnalysisResult {
+            success: false,
+            profile: None,
+            error: Some(analysis_progress::CANCELLED_ERROR.to_string()),
+            analysis_time_ms: start_time.elapsed().as_millis() as u64,
+            files_scanned,
+            cache_stats: CacheStats::default(),
+            scan_stats: ScanStats::default(),
+        }
+    }
+}
+
+/// Where per-file scan results are cached between analyses
+enum CacheLocation {
+    AppDir(PathBuf),
+    Project,
 }
 
 pub struct CodeAnalyzer {
     max_depth: usize,
+    max_files: usize,
+    max_file_size: u64,
     ignore_patterns: Vec<String>,
     language_extensions: HashMap<String, String>,
+    rule_set: DetectionRuleSet,
+    cache: Option<CacheLocation>,
 }
 
 impl CodeAnalyzer {
@@ -104,6 +234,8 @@ impl CodeAnalyzer {
 
         CodeAnalyzer {
             max_depth: 5,
+            max_files: DEFAULT_MAX_FILES,
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
             ignore_patterns: vec![
                 "node_modules".to_string(),
                 ".git".to_string(),
@@ -114,12 +246,41 @@ impl CodeAnalyzer {
                 "__pycache__".to_string(),
                 "venv".to_string(),
                 ".venv".to_string(),
+                ".vibeforge".to_string(),
             ],
             language_extensions,
+            rule_set: DetectionRuleSet::bundled(),
+            cache: None,
         }
     }
 
-    pub fn analyze_project(&self, project_path: &str) -> AnalysisResult {
+    /// Cache per-file results under `cache_dir`, or in the project's `.vibeforge/cache` when `None`
+    pub fn enable_cache(&mut self, cache_dir: Option<PathBuf>) {
+        self.cache = Some(match cache_dir {
+            Some(dir) => CacheLocation::AppDir(dir),
+            None => CacheLocation::Project,
+        });
+    }
+
+    pub fn apply_options(&mut self, options: &AnalyzerOptions) {
+        if let Some(max_depth) = options.max_depth {
+            self.max_depth = max_depth;
+        }
+        if let Some(max_files) = options.max_files {
+            self.max_files = max_files;
+        }
+        if let Some(max_file_size) = options.max_file_size_bytes {
+            self.max_file_size = max_file_size;
+        }
+    }
+
+    /// Extend the bundled detection rules with a user rule file, if present
+    pub fn load_user_rules(&mut self, rules_path: &Path) -> Result<bool, String> {
+        self.rule_set.merge_file(rules_path)
+    }
+
+    /// Analyze while reporting progress; stops early once `progress` is cancelled
+    pub fn analyze_project(&self, project_path: &str, progress: &AnalysisProgress) -> AnalysisResult {
         let start_time = std::time::Instant::now();
         let path = Path::new(project_path);
 
@@ -130,6 +291,8 @@ impl CodeAnalyzer {
                 error: Some("Path does not exist or is not a directory".to_string()),
                 analysis_time_ms: start_time.elapsed().as_millis() as u64,
                 files_scanned: 0,
+                cache_stats: CacheStats::default(),
+                scan_stats: ScanStats::default(),
             };
         }
 
@@ -139,57 +302,121 @@ impl CodeAnalyzer {
             .unwrap_or("Unknown")
             .to_string();
 
-        // Scan files
-        let mut files_scanned = 0;
+        // List files in parallel, then fill in line counts and imports from the cache or disk
+        progress.phase("scan", "Scanning files...");
+        let listing = self.list_files(path, progress);
+        let files_scanned = listing.entries.len();
+        if progress.is_cancelled() {
+            return AnalysisResult::cancelled(start_time, files_scanned);
+        }
+        let mut scan_stats = ScanStats {
+            truncated: listing.truncated,
+            max_files: self.max_files,
+            max_file_size_bytes: self.max_file_size,
+            ..Default::default()
+        };
+
         let mut language_files: HashMap<String, Vec<String>> = HashMap::new();
         let mut language_lines: HashMap<String, u32> = HashMap::new();
         let mut root_files = Vec::new();
         let mut config_files = HashSet::new();
+        let mut project_files = Vec::new();
+        let mut imports = HashSet::new();
+        let mut module_sources = Vec::new();
+        let mut source_files = Vec::new();
+        let mut cache = self.cache.as_ref().map(|location| {
+            AnalysisCache::load(match location {
+                CacheLocation::AppDir(dir) => AnalysisCache::app_cache_file(dir, path),
+                CacheLocation::Project => AnalysisCache::project_cache_file(path),
+            })
+        });
 
-        for entry in WalkDir::new(path)
-            .max_depth(self.max_depth)
-            .follow_links(false)
-            .into_iter()
-            .filter_entry(|e| !self.should_ignore(e.path()))
-        {
-            if let Ok(entry) = entry {
-                let entry_path = entry.path();
-                files_scanned += 1;
-
-                // Track root files
-                if entry_path.parent() == Some(path) {
-                    if let Some(filename) = entry_path.file_name().and_then(|n| n.to_str()) {
-                        root_files.push(filename.to_string());
-                        
-                        // Track config files
-                        if Self::is_config_file(filename) {
-                            config_files.insert(filename.to_string());
-                        }
-                    }
+        let mut pending = Vec::new();
+        for entry in &listing.entries {
+            // Track root files
+            if !entry.relative.contains('/') {
+                root_files.push(entry.relative.clone());
+
+                // Track config files
+                if Self::is_config_file(&entry.relative) {
+                    config_files.insert(entry.relative.clone());
+                }
+            }
+
+            let Some(fingerprint) = entry.fingerprint else {
+                continue;
+            };
+            project_files.push(entry.relative.clone());
+
+            // Detect languages by extension
+            let Some(lang_id) = entry
+                .relative
+                .rsplit_once('.')
+                .and_then(|(_, ext)| self.language_extensions.get(&format!(".{}", ext)))
+            else {
+                continue;
+            };
+            language_files
+                .entry(lang_id.clone())
+                .or_default()
+                .push(path.join(&entry.relative).to_string_lossy().to_string());
+            source_files.push((entry.relative.clone(), lang_id.clone()));
+
+            // Count lines and collect imported modules, reusing cached results
+            let (_, size) = fingerprint;
+            if size > self.max_file_size {
+                scan_stats.large_files_skipped += 1;
+                continue;
+            }
+            match cache.as_mut().and_then(|c| c.get(&entry.relative, fingerprint)) {
+                Some(cached) if cached.binary => scan_stats.binary_files_skipped += 1,
+                Some(cached) => {
+                    *language_lines.entry(lang_id.clone()).or_insert(0) += cached.line_count;
+                    imports.extend(cached.imports);
+                    module_sources.push(ModuleSource {
+                        file: entry.relative.clone(),
+                        lang_id: lang_id.clone(),
+                        imports: cached.module_imports,
+                    });
                 }
+                None => pending.push((entry, lang_id)),
+            }
+        }
 
-                // Detect languages by extension
-                if entry_path.is_file() {
-                    if let Some(ext) = entry_path.extension().and_then(|e| e.to_str()) {
-                        let ext_with_dot = format!(".{}", ext);
-                        if let Some(lang_id) = self.language_extensions.get(&ext_with_dot) {
-                            let file_path = entry_path.to_string_lossy().to_string();
-                            language_files
-                                .entry(lang_id.clone())
-                                .or_insert_with(Vec::new)
-                                .push(file_path);
-
-                            // Count lines
-                            if let Ok(content) = fs::read_to_string(entry_path) {
-                                let lines = content.lines().count() as u32;
-                                *language_lines.entry(lang_id.clone()).or_insert(0) += lines;
-                            }
-                        }
+        let scanned: Vec<_> = pending
+            .into_par_iter()
+            .filter(|_| !progress.is_cancelled())
+            .map(|(entry, lang_id)| {
+                let fingerprint = entry.fingerprint.unwrap_or_default();
+                (entry, lang_id, read_source(&path.join(&entry.relative), lang_id, fingerprint))
+            })
+            .collect();
+        for (entry, lang_id, result) in scanned {
+            let file = match result {
+                Some(file) if !file.binary => file,
+                binary => {
+                    scan_stats.binary_files_skipped += 1;
+                    if let (Some(cache), Some(file)) = (&mut cache, binary) {
+                        cache.insert(&entry.relative, file);
                     }
+                    continue;
                 }
+            };
+            *language_lines.entry(lang_id.clone()).or_insert(0) += file.line_count;
+            imports.extend(file.imports.iter().cloned());
+            module_sources.push(ModuleSource {
+                file: entry.relative.clone(),
+                lang_id: lang_id.clone(),
+                imports: file.module_imports.clone(),
+            });
+            if let Some(cache) = &mut cache {
+                cache.insert(&entry.relative, file);
             }
         }
 
+        // A cache that cannot be written only costs the next analysis its speedup
+        let cache_stats = cache.map(AnalysisCache::save).unwrap_or_default();
+
         // Build detected languages
         let mut languages: Vec<DetectedLanguage> = language_files
             .into_iter()
@@ -213,27 +440,72 @@ impl CodeAnalyzer {
             .map(|l| l.id.clone())
             .unwrap_or_else(|| "unknown".to_string());
 
-        // Detect dependencies and frameworks
-        let dependencies = self.detect_dependencies(path);
-        let frameworks = self.detect_frameworks(path, &dependencies, &config_files);
+        if progress.is_cancelled() {
+            return AnalysisResult::cancelled(start_time, files_scanned);
+        }
+
+        // Detect dependencies, then evaluate detection rules against the scan
+        progress.phase("dependencies", "Reading dependency manifests...");
+        let dependencies = self.detect_dependencies(path, &project_files);
+        progress.phase("frameworks", "Detecting frameworks and data stores...");
+        let rule_matches = match self.evaluate_rules(path, &project_files, &dependencies, &imports) {
+            Ok(matches) => matches,
+            Err(e) => {
+                return AnalysisResult {
+                    success: false,
+                    profile: None,
+                    error: Some(e),
+                    analysis_time_ms: start_time.elapsed().as_millis() as u64,
+                    files_scanned,
+                    cache_stats,
+                    scan_stats,
+                };
+            }
+        };
+
+        // Detect frameworks
+        let frameworks = Self::frameworks_from_matches(&rule_matches);
 
         // Detect project structure
         let has_frontend = self.has_frontend_indicators(&frameworks, &config_files);
         let has_backend = self.has_backend_indicators(&frameworks, &dependencies);
         let has_mobile = self.has_mobile_indicators(&frameworks, &dependencies);
-        let has_tests = self.has_test_indicators(&config_files, &dependencies);
-        let has_docker = config_files.contains("Dockerfile") || config_files.contains("docker-compose.yml");
-        let has_ci = config_files.iter().any(|f| f.contains("ci.yml") || f.contains("ci.yaml"));
-
-        // Detect database
-        let database = self.detect_database(&dependencies);
-
-        // Detect authentication
-        let authentication = self.detect_authentication(&dependencies);
+        let testing = testing::detect_testing(path, &project_files, &source_files, &rule_matches);
+        let has_tests = testing.has_tests();
+        // Detect CI/CD pipelines and infrastructure
+        let ci = infra_detector::detect_ci(path, &project_files);
+        let infrastructure = infra_detector::detect_infrastructure(path, &project_files, &ci);
+        let has_docker = project_files
+            .iter()
+            .any(|f| infra_detector::is_dockerfile(f) || infra_detector::is_compose_file(f));
+        let has_ci = !ci.workflows.is_empty();
+        let suggested_deployment_platform = infrastructure.deployment_platforms.first().map(|p| p.platform.clone());
+
+        // Detect data stores, ORMs and migrations
+        let data_stores = Self::data_stores_from_matches(&rule_matches);
+        let orms = Self::orms_from_matches(&rule_matches);
+        let migrations = Self::detect_migrations(&project_files, &orms);
+
+        // Detect authentication, strongest match first
+        let authentication_methods = Self::authentication_from_matches(&rule_matches);
+        let authentication = authentication_methods.first().cloned();
+
+        // Entry points, routes and service boundaries
+        let architecture = architecture::extract_architecture(path, &project_files);
+
+        // Module dependency graphs from the recorded import specifiers
+        let import_graphs = import_graph::build_import_graphs(path, &project_files, &module_sources);
+
+        // Security and hygiene checks
+        let findings = findings::collect_findings(path, &project_files, &dependencies, &infrastructure.docker_images);
 
         // Match to known stacks
-        let (suggested_stack_id, stack_match_confidence) = 
-            self.match_stack(&frameworks, &dependencies, has_frontend, has_backend);
+        progress.phase("recommendations", "Matching known stacks...");
+        let stack_matches = Self::match_stack(&frameworks, &dependencies, &orms, &data_stores, &project_files);
+        let (suggested_stack_id, stack_match_confidence) = match stack_matches.first() {
+            Some(best) if best.score >= MIN_STACK_SCORE => (Some(best.stack_id.clone()), Some(best.score)),
+            _ => (None, None),
+        };
 
         let profile = ProjectProfile {
             project_name: project_name.clone(),
@@ -247,27 +519,117 @@ impl CodeAnalyzer {
             has_frontend,
             has_mobile,
             has_tests,
-            database,
+            testing,
+            data_stores,
+            orms,
+            migrations,
             authentication,
+            authentication_methods,
             has_docker,
             has_ci,
+            ci,
+            infrastructure,
+            architecture,
+            import_graphs,
+            findings,
+            suggested_deployment_platform,
             suggested_stack_id,
             stack_match_confidence,
+            stack_matches,
         };
 
+        progress.phase("complete", "Analysis complete");
         AnalysisResult {
             success: true,
             profile: Some(profile),
             error: None,
             analysis_time_ms: start_time.elapsed().as_millis() as u64,
             files_scanned,
+            cache_stats,
+            scan_stats,
         }
     }
 
+    /// List files and directories up to `max_depth`, sorted by relative path.
+    /// The `max_files` budget is applied to the sorted listing, so the same
+    /// tree always keeps the same files however the walk was scheduled. Huge
+    /// trees stop being walked at `LISTING_HEADROOM` entries per budgeted file.
+    fn list_files(&self, root: &Path, progress: &AnalysisProgress) -> FileListing {
+        let walk = Walk {
+            listed: AtomicUsize::new(0),
+            limit: self.max_files.saturating_mul(LISTING_HEADROOM),
+            stopped: AtomicBool::new(false),
+        };
+        let mut entries = self.list_directory(root, root, 1, &walk, progress);
+        entries.sort_by(|a, b| a.relative.cmp(&b.relative));
+        let mut files = 0;
+        let mut truncated = walk.stopped.load(Ordering::Relaxed);
+        entries.retain(|entry| {
+            if entry.fingerprint.is_none() {
+                return true;
+            }
+            files += 1;
+            truncated |= files > self.max_files;
+            files <= self.max_files
+        });
+        FileListing { entries, truncated }
+    }
+
+    fn list_directory(&self, root: &Path, dir: &Path, depth: usize, walk: &Walk, progress: &AnalysisProgress) -> Vec<ListedEntry> {
+        if progress.is_cancelled() {
+            return Vec::new();
+        }
+        if walk.listed.load(Ordering::Relaxed) >= walk.limit {
+            walk.stopped.store(true, Ordering::Relaxed);
+            return Vec::new();
+        }
+        let Ok(read_dir) = fs::read_dir(dir) else {
+            return Vec::new();
+        };
+        progress.directory_visited();
+        let children: Vec<fs::DirEntry> = read_dir
+            .flatten()
+            .filter(|e| !self.should_ignore(&e.path()))
+            .collect();
+        // Counted before descending, so deeper directories see their ancestors' entries
+        walk.listed.fetch_add(children.len(), Ordering::Relaxed);
+
+        children
+            .into_par_iter()
+            .flat_map_iter(|child| {
+                let child_path = child.path();
+                let relative = child_path
+                    .strip_prefix(root)
+                    .map(|r| r.to_string_lossy().replace('\\', "/"))
+                    .unwrap_or_default();
+                let Ok(metadata) = fs::metadata(&child_path) else {
+                    return Vec::new();
+                };
+
+                if metadata.is_dir() {
+                    // Symlinked directories are listed but not followed
+                    let is_symlink = child.file_type().is_ok_and(|t| t.is_symlink());
+                    let mut entries = vec![ListedEntry { relative, fingerprint: None }];
+                    if !is_symlink && depth < self.max_depth {
+                        entries.extend(self.list_directory(root, &child_path, depth + 1, walk, progress));
+                    }
+                    entries
+                } else {
+                    progress.file_scanned();
+                    vec![ListedEntry {
+                        relative,
+                        fingerprint: Some(AnalysisCache::fingerprint(&metadata)),
+                    }]
+                }
+            })
+            .collect()
+    }
+
     fn should_ignore(&self, path: &Path) -> bool {
         path.file_name()
             .and_then(|n| n.to_str())
-            .map(|name| self.ignore_patterns.iter().any(|pattern| name.contains(pattern)))
+            // Exact names: a substring match on `.git` would also skip `.github` and `.gitlab`
+            .map(|name| self.ignore_patterns.iter().any(|pattern| name == pattern))
             .unwrap_or(false)
     }
 
@@ -321,153 +683,74 @@ impl CodeAnalyzer {
         (file_score + line_score).min(1.0)
     }
 
-    fn detect_dependencies(&self, path: &Path) -> Vec<DetectedDependency> {
+    fn detect_dependencies(&self, path: &Path, project_files: &[String]) -> Vec<DetectedDependency> {
         let mut dependencies = Vec::new();
 
-        // Parse package.json
-        if let Ok(content) = fs::read_to_string(path.join("package.json")) {
-            if let Ok(package_json) = serde_json::from_str::<serde_json::Value>(&content) {
-                if let Some(deps) = package_json["dependencies"].as_object() {
-                    for (name, version) in deps {
-                        dependencies.push(DetectedDependency {
-                            name: name.clone(),
-                            version: version.as_str().map(|s| s.to_string()),
-                            dep_type: "runtime".to_string(),
-                            source: "package.json".to_string(),
-                        });
-                    }
-                }
-                if let Some(dev_deps) = package_json["devDependencies"].as_object() {
-                    for (name, version) in dev_deps {
-                        dependencies.push(DetectedDependency {
-                            name: name.clone(),
-                            version: version.as_str().map(|s| s.to_string()),
-                            dep_type: "dev".to_string(),
-                            source: "package.json".to_string(),
-                        });
-                    }
-                }
-            }
-        }
+        // Manifests anywhere in the tree, shallowest first so root deps win lookups
+        let mut manifests: Vec<&String> = project_files
+            .iter()
+            .filter(|f| Self::is_manifest(file_name(f)))
+            .collect();
+        manifests.sort_by_key(|f| (f.matches('/').count(), f.as_str()));
 
-        // Parse requirements.txt
-        if let Ok(content) = fs::read_to_string(path.join("requirements.txt")) {
-            for line in content.lines() {
-                let line = line.trim();
-                if line.is_empty() || line.starts_with('#') {
-                    continue;
-                }
-                let parts: Vec<&str> = line.split("==").collect();
-                dependencies.push(DetectedDependency {
-                    name: parts[0].to_string(),
-                    version: parts.get(1).map(|v| v.to_string()),
-                    dep_type: "runtime".to_string(),
-                    source: "requirements.txt".to_string(),
-                });
+        for relative in manifests {
+            let Ok(content) = fs::read_to_string(path.join(relative)) else {
+                continue;
+            };
+            match file_name(relative) {
+                "package.json" => parse_package_json(&content, relative, &mut dependencies),
+                "composer.json" => parse_composer_json(&content, relative, &mut dependencies),
+                "requirements.txt" => parse_requirements_txt(&content, relative, &mut dependencies),
+                "pyproject.toml" => parse_pyproject_toml(&content, relative, &mut dependencies),
+                "Cargo.toml" => parse_cargo_toml(&content, relative, &mut dependencies),
+                "go.mod" => parse_go_mod(&content, relative, &mut dependencies),
+                _ => {}
             }
         }
 
         dependencies
     }
 
-    fn detect_frameworks(
+    fn is_manifest(filename: &str) -> bool {
+        matches!(
+            filename,
+            "package.json" | "composer.json" | "requirements.txt" | "pyproject.toml" | "Cargo.toml" | "go.mod"
+        )
+    }
+
+    fn evaluate_rules(
         &self,
         path: &Path,
+        project_files: &[String],
         dependencies: &[DetectedDependency],
-        config_files: &HashSet<String>,
-    ) -> Vec<DetectedFramework> {
-        let mut frameworks = Vec::new();
-        let dep_names: HashSet<String> = dependencies.iter().map(|d| d.name.clone()).collect();
-
-        // Next.js
-        if dep_names.contains("next") {
-            frameworks.push(DetectedFramework {
-                name: "Next.js".to_string(),
-                version: dependencies.iter().find(|d| d.name == "next").and_then(|d| d.version.clone()),
-                category: "fullstack".to_string(),
-                confidence: 0.95,
-                indicators: vec!["next dependency".to_string()],
-            });
-        }
-
-        // React
-        if dep_names.contains("react") {
-            frameworks.push(DetectedFramework {
-                name: "React".to_string(),
-                version: dependencies.iter().find(|d| d.name == "react").and_then(|d| d.version.clone()),
-                category: "frontend".to_string(),
-                confidence: 0.9,
-                indicators: vec!["react dependency".to_string()],
-            });
-        }
-
-        // SvelteKit
-        if dep_names.contains("@sveltejs/kit") {
-            frameworks.push(DetectedFramework {
-                name: "SvelteKit".to_string(),
-                version: None,
-                category: "fullstack".to_string(),
-                confidence: 0.95,
-                indicators: vec!["@sveltejs/kit dependency".to_string()],
-            });
-        }
-
-        // Express
-        if dep_names.contains("express") {
-            frameworks.push(DetectedFramework {
-                name: "Express".to_string(),
-                version: None,
-                category: "backend".to_string(),
-                confidence: 0.9,
-                indicators: vec!["express dependency".to_string()],
-            });
-        }
-
-        // FastAPI
-        if dep_names.contains("fastapi") {
-            frameworks.push(DetectedFramework {
-                name: "FastAPI".to_string(),
-                version: None,
-                category: "backend".to_string(),
-                confidence: 0.95,
-                indicators: vec!["fastapi dependency".to_string()],
-            });
-        }
-
-        // Django
-        if dep_names.contains("django") || path.join("manage.py").exists() {
-            frameworks.push(DetectedFramework {
-                name: "Django".to_string(),
-                version: None,
-                category: "fullstack".to_string(),
-                confidence: 0.95,
-                indicators: vec!["django dependency or manage.py".to_string()],
-            });
-        }
-
-        // Prisma
-        if config_files.contains("schema.prisma") {
-            frameworks.push(DetectedFramework {
-                name: "Prisma".to_string(),
-                version: None,
-                category: "backend".to_string(),
-                confidence: 0.95,
-                indicators: vec!["schema.prisma".to_string()],
-            });
-        }
-
-        // TailwindCSS
-        if dep_names.contains("tailwindcss") {
-            frameworks.push(DetectedFramework {
-                name: "TailwindCSS".to_string(),
-                version: None,
-                category: "frontend".to_string(),
-                confidence: 0.9,
-                indicators: vec!["tailwindcss dependency".to_string()],
-            });
-        }
+        imports: &HashSet<String>,
+    ) -> Result<Vec<RuleMatch>, String> {
+        // Project-local rules take precedence over bundled and user rules
+        let mut rule_set = self.rule_set.clone();
+        rule_set.merge_file(&path.join(".vibeforge").join(USER_RULES_FILE))?;
+        let engine = RuleEngine::new(rule_set)?;
+
+        Ok(engine.evaluate(&DetectionContext {
+            project_path: path,
+            files: project_files,
+            dependencies,
+            imports,
+        }))
+    }
 
-        frameworks
+    fn frameworks_from_matches(matches: &[RuleMatch]) -> Vec<DetectedFramework> {
+        matches
+            .iter()
+            .filter(|m| m.kind == "framework")
+            .map(|m| DetectedFramework {
+                name: m.name.clone(),
+                version: m.version.clone(),
+                category: m.category.clone().unwrap_or_else(|| "library".to_string()),
+                confidence: m.confidence,
+                indicators: m.indicators.clone(),
+                sources: m.sources.clone(),
+            })
+            .collect()
     }
 
     fn has_frontend_indicators(&self, frameworks: &[DetectedFramework], config_files: &HashSet<String>) -> bool {
@@ -492,142 +775,672 @@ impl CodeAnalyzer {
             || dependencies.iter().any(|d| d.name == "react-native" || d.name == "expo")
     }
 
-    fn has_test_indicators(&self, config_files: &HashSet<String>, dependencies: &[DetectedDependency]) -> bool {
-        config_files.iter().any(|f| f.contains("jest.config") || f.contains("vitest.config"))
-            || dependencies.iter().any(|d| {
-                d.name == "jest" || d.name == "vitest" || d.name == "pytest" || d.name == "mocha"
+    fn data_stores_from_matches(matches: &[RuleMatch]) -> Vec<DetectedDataStore> {
+        let mut stores: Vec<DetectedDataStore> = matches
+            .iter()
+            .filter(|m| m.kind == "database" || m.kind == "broker")
+            .map(|m| DetectedDataStore {
+                kind: m.kind.clone(),
+                store_type: m.name.clone(),
+                category: m.category.clone(),
+                confidence: m.confidence,
+                indicators: m.indicators.clone(),
+                sources: m.sources.clone(),
+            })
+            .collect();
+        // Databases before brokers, strongest match first
+        stores.sort_by(|a, b| {
+            (a.kind != "database")
+                .cmp(&(b.kind != "database"))
+                .then(b.confidence.total_cmp(&a.confidence))
+        });
+        stores
+    }
+
+    fn orms_from_matches(matches: &[RuleMatch]) -> Vec<DetectedOrm> {
+        matches
+            .iter()
+            .filter(|m| m.kind == "orm")
+            .map(|m| DetectedOrm {
+                name: m.name.clone(),
+                category: m.category.clone().unwrap_or_else(|| "orm".to_string()),
+                version: m.version.clone(),
+                confidence: m.confidence,
+                indicators: m.indicators.clone(),
             })
+            .collect()
     }
 
-    fn detect_database(&self, dependencies: &[DetectedDependency]) -> Option<DetectedDatabase> {
-        let mut db_indicators = Vec::new();
-        let mut db_type = "unknown";
-        let mut confidence = 0.0;
-
-        for dep in dependencies {
-            if dep.name.contains("pg") || dep.name.contains("postgres") {
-                db_type = "postgresql";
-                confidence = 0.9;
-                db_indicators.push(format!("{} dependency", dep.name));
-            } else if dep.name.contains("mongodb") || dep.name == "mongoose" {
-                db_type = "mongodb";
-                confidence = 0.9;
-                db_indicators.push(format!("{} dependency", dep.name));
-            } else if dep.name.contains("mysql") {
-                db_type = "mysql";
-                confidence = 0.9;
-                db_indicators.push(format!("{} dependency", dep.name));
-            } else if dep.name.contains("sqlite") {
-                db_type = "sqlite";
-                confidence = 0.9;
-                db_indicators.push(format!("{} dependency", dep.name));
-            } else if dep.name.contains("redis") {
-                db_type = "redis";
-                confidence = 0.8;
-                db_indicators.push(format!("{} dependency", dep.name));
+    fn detect_migrations(project_files: &[String], orms: &[DetectedOrm]) -> Vec<DetectedMigrations> {
+        let has_file = |name: &str| project_files.iter().any(|f| f == name || f.ends_with(&format!("/{}", name)));
+        let has_orm = |name: &str| orms.iter().any(|o| o.name == name);
+
+        // Group files under the first `migrations` (or alembic `versions`) directory in their path
+        let mut directories: Vec<(String, Vec<&String>)> = Vec::new();
+        for file in project_files {
+            let segments: Vec<&str> = file.split('/').collect();
+            let Some(index) = segments.iter().enumerate().position(|(i, segment)| {
+                i + 1 < segments.len()
+                    && (*segment == "migrations"
+                        || (*segment == "versions" && i > 0 && segments[i - 1] == "alembic")
+                        || (*segment == "drizzle" && has_orm("Drizzle")))
+            }) else {
+                continue;
+            };
+            let directory = segments[..=index].join("/");
+            match directories.iter_mut().find(|(d, _)| *d == directory) {
+                Some((_, files)) => files.push(file),
+                None => directories.push((directory, vec![file])),
             }
         }
 
-        if !db_indicators.is_empty() {
-            Some(DetectedDatabase {
-                db_type: db_type.to_string(),
-                confidence,
-                indicators: db_indicators,
+        directories
+            .into_iter()
+            .map(|(path, files)| {
+                let all_sql = files.iter().all(|f| f.ends_with(".sql"));
+                let tool = if path.contains("prisma/migrations") {
+                    Some("prisma")
+                } else if path.ends_with("alembic/versions") || has_file("alembic.ini") {
+                    Some("alembic")
+                } else if path.ends_with("drizzle") || has_orm("Drizzle") {
+                    Some("drizzle-kit")
+                } else if path == "database/migrations" && has_file("artisan") {
+                    Some("laravel")
+                } else if files.iter().any(|f| f.ends_with("/__init__.py")) && has_file("manage.py") {
+                    Some("django")
+                } else if has_file("diesel.toml") || has_orm("Diesel") {
+                    Some("diesel")
+                } else if has_orm("sqlx") {
+                    Some("sqlx")
+                } else if has_orm("TypeORM") {
+                    Some("typeorm")
+                } else if has_orm("Sequelize") {
+                    Some("sequelize")
+                } else if has_orm("Knex") {
+                    Some("knex")
+                } else if files.iter().any(|f| f.ends_with(".up.sql")) && has_file("go.mod") {
+                    Some("golang-migrate")
+                } else if all_sql {
+                    Some("sql")
+                } else {
+                    None
+                };
+
+                DetectedMigrations {
+                    path,
+                    tool: tool.map(|t| t.to_string()),
+                    file_count: files.len(),
+                }
             })
-        } else {
-            None
+            .collect()
+    }
+
+    fn authentication_from_matches(matches: &[RuleMatch]) -> Vec<DetectedAuthentication> {
+        let mut methods: Vec<DetectedAuthentication> = matches
+            .iter()
+            .filter(|m| m.kind == "auth")
+            .map(|m| DetectedAuthentication {
+                method: m.name.clone(),
+                confidence: m.confidence,
+                indicators: m.indicators.clone(),
+            })
+            .collect();
+        methods.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+        methods
+    }
+
+    /// Score every stack in the generator catalog against the detected evidence, best first
+    fn match_stack(
+        frameworks: &[DetectedFramework],
+        dependencies: &[DetectedDependency],
+        orms: &[DetectedOrm],
+        data_stores: &[DetectedDataStore],
+        project_files: &[String],
+    ) -> Vec<StackMatch> {
+        let has_dependency = |value: &str| match value.strip_suffix('*') {
+            Some(prefix) => dependencies.iter().any(|d| d.name.starts_with(prefix)),
+            None => dependencies.iter().any(|d| d.name.eq_ignore_ascii_case(value)),
+        };
+        let has_file = |glob: &str| {
+            detection_rules::glob_to_regex(glob)
+                .map(|re| project_files.iter().any(|f| re.is_match(f)))
+                .unwrap_or(false)
+        };
+
+        let mut matches: Vec<StackMatch> = STACK_CATALOG
+            .iter()
+            .map(|stack| {
+                let mut matched = Vec::new();
+                let mut missing = Vec::new();
+                let mut miss_probability = 1.0;
+                for indicator in stack.signature {
+                    let found = match indicator.kind {
+                        "framework" => frameworks.iter().any(|f| f.name == indicator.value),
+                        "dependency" => has_dependency(indicator.value),
+                        "file" => has_file(indicator.value),
+                        "orm" => orms.iter().any(|o| o.name == indicator.value),
+                        "database" => data_stores.iter().any(|s| s.store_type == indicator.value),
+                        _ => false,
+                    };
+                    let description = format!("{}: {}", indicator.kind, indicator.value);
+                    if found {
+                        miss_probability *= 1.0 - indicator.weight;
+                        matched.push(description);
+                    } else {
+                        missing.push(description);
+                    }
+                }
+                StackMatch {
+                    stack_id: stack.id.to_string(),
+                    name: stack.name.to_string(),
+                    score: ((1.0 - miss_probability) * 100.0_f64).round() / 100.0,
+                    matched,
+                    missing,
+                }
+            })
+            .collect();
+
+        matches.sort_by(|a, b| b.score.total_cmp(&a.score).then(b.matched.len().cmp(&a.matched.len())));
+        matches
+    }
+}
+
+// ============================================================================
+// MANIFEST PARSING
+// ============================================================================
+
+fn push_dependency(
+    dependencies: &mut Vec<DetectedDependency>,
+    name: &str,
+    version: Option<String>,
+    dep_type: &str,
+    source: &str,
+) {
+    dependencies.push(DetectedDependency {
+        name: name.to_string(),
+        version,
+        dep_type: dep_type.to_string(),
+        source: source.to_string(),
+    });
+}
+
+fn parse_package_json(content: &str, source: &str, dependencies: &mut Vec<DetectedDependency>) {
+    let Ok(package_json) = serde_json::from_str::<serde_json::Value>(content) else {
+        return;
+    };
+    for (section, dep_type) in [("dependencies", "runtime"), ("devDependencies", "dev"), ("peerDependencies", "peer")] {
+        if let Some(deps) = package_json[section].as_object() {
+            for (name, version) in deps {
+                push_dependency(dependencies, name, version.as_str().map(|s| s.to_string()), dep_type, source);
+            }
         }
     }
+}
 
-    fn detect_authentication(&self, dependencies: &[DetectedDependency]) -> Option<DetectedAuthentication> {
-        let mut auth_indicators = Vec::new();
-        let mut auth_method = "unknown";
-        let mut confidence = 0.0;
-
-        for dep in dependencies {
-            if dep.name.contains("jsonwebtoken") || dep.name.contains("jwt") {
-                auth_method = "jwt";
-                confidence = 0.85;
-                auth_indicators.push(format!("{} dependency", dep.name));
-            } else if dep.name.contains("passport") || dep.name.contains("express-session") {
-                auth_method = "session";
-                confidence = 0.8;
-                auth_indicators.push(format!("{} dependency", dep.name));
-            } else if dep.name.contains("oauth") || dep.name.contains("next-auth") {
-                auth_method = "oauth";
-                confidence = 0.85;
-                auth_indicators.push(format!("{} dependency", dep.name));
+fn parse_composer_json(content: &str, source: &str, dependencies: &mut Vec<DetectedDependency>) {
+    let Ok(composer_json) = serde_json::from_str::<serde_json::Value>(content) else {
+        return;
+    };
+    for (section, dep_type) in [("require", "runtime"), ("require-dev", "dev")] {
+        if let Some(deps) = composer_json[section].as_object() {
+            for (name, version) in deps {
+                // Platform requirements are not packages
+                if name == "php" || name.starts_with("ext-") {
+                    continue;
+                }
+                push_dependency(dependencies, name, version.as_str().map(|s| s.to_string()), dep_type, source);
             }
         }
+    }
+}
 
-        if !auth_indicators.is_empty() {
-            Some(DetectedAuthentication {
-                method: auth_method.to_string(),
-                confidence,
-                indicators: auth_indicators,
-            })
-        } else {
-            None
+/// Split a PEP 508 requirement (`name[extra]>=1.0; marker`) into name and pinned version
+fn parse_python_requirement(requirement: &str) -> Option<(String, Option<String>)> {
+    let requirement = requirement.trim();
+    let name_end = requirement
+        .find(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_' || c == '.'))
+        .unwrap_or(requirement.len());
+    let name = &requirement[..name_end];
+    if name.is_empty() {
+        return None;
+    }
+    let version = requirement
+        .split_once("==")
+        .map(|(_, v)| v.split(|c: char| c == ';' || c.is_whitespace()).next().unwrap_or("").to_string())
+        .filter(|v| !v.is_empty());
+    Some((name.to_string(), version))
+}
+
+fn parse_requirements_txt(content: &str, source: &str, dependencies: &mut Vec<DetectedDependency>) {
+    for line in content.lines() {
+        let line = line.trim();
+        // Skip comments and pip options such as `-r other.txt` or `-e .`
+        if line.is_empty() || line.starts_with('#') || line.starts_with('-') {
+            continue;
+        }
+        if let Some((name, version)) = parse_python_requirement(line) {
+            push_dependency(dependencies, &name, version, "runtime", source);
         }
     }
+}
 
-    fn match_stack(
-        &self,
-        frameworks: &[DetectedFramework],
-        dependencies: &[DetectedDependency],
-        has_frontend: bool,
-        has_backend: bool,
-    ) -> (Option<String>, Option<f64>) {
-        let framework_names: HashSet<String> = frameworks.iter().map(|f| f.name.clone()).collect();
-        let dep_names: HashSet<String> = dependencies.iter().map(|d| d.name.clone()).collect();
+/// `name = "1.0"` or `name = { version = "1.0", ... }` from a TOML dependency table
+fn parse_toml_dependency_line(line: &str) -> Option<(String, Option<String>)> {
+    static VERSION: OnceLock<Regex> = OnceLock::new();
+    let (key, value) = line.split_once('=')?;
+    let name = key.trim().trim_matches('"');
+    if name.is_empty() || name.contains(char::is_whitespace) || name.contains('.') {
+        return None;
+    }
+    let value = value.trim();
+    let version = if value.starts_with('"') {
+        Some(value.trim_matches('"').to_string())
+    } else {
+        VERSION
+            .get_or_init(|| Regex::new(r#"version\s*=\s*"([^"]+)""#).unwrap())
+            .captures(value)
+            .map(|c| c[1].to_string())
+    };
+    Some((name.to_string(), version))
+}
 
-        // T3 Stack
-        if framework_names.contains("Next.js")
-            && dep_names.contains("@trpc/server")
-            && dep_names.contains("prisma")
-        {
-            return (Some("t3-stack".to_string()), Some(0.95));
+fn parse_pyproject_toml(content: &str, source: &str, dependencies: &mut Vec<DetectedDependency>) {
+    static QUOTED: OnceLock<Regex> = OnceLock::new();
+    let quoted = QUOTED.get_or_init(|| Regex::new(r#""([^"]+)"|'([^']+)'"#).unwrap());
+    let push_requirements = |text: &str, dep_type: &str, dependencies: &mut Vec<DetectedDependency>| {
+        for cap in quoted.captures_iter(text) {
+            let requirement = cap.get(1).or_else(|| cap.get(2)).map_or("", |m| m.as_str());
+            if let Some((name, version)) = parse_python_requirement(requirement) {
+                push_dependency(dependencies, &name, version, dep_type, source);
+            }
         }
+    };
+
+    let mut section = String::new();
+    let mut in_array: Option<&str> = None;
 
-        // MERN Stack
-        if framework_names.contains("React")
-            && framework_names.contains("Express")
-            && dep_names.iter().any(|d| d.contains("mongo"))
-        {
-            return (Some("mern-stack".to_string()), Some(0.9));
+    for line in content.lines() {
+        let line = line.split(" #").next().unwrap_or("").trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
         }
 
-        // Next.js Full-Stack
-        if framework_names.contains("Next.js") {
-            return (Some("nextjs-fullstack".to_string()), Some(0.85));
+        // PEP 621 arrays: `dependencies = [ "fastapi>=0.100", ... ]`
+        if let Some(dep_type) = in_array {
+            push_requirements(line, dep_type, dependencies);
+            if line.contains(']') {
+                in_array = None;
+            }
+            continue;
         }
 
-        // FastAPI AI Stack
-        if framework_names.contains("FastAPI") {
-            return (Some("fastapi-ai-stack".to_string()), Some(0.85));
+        if line.starts_with('[') {
+            section = line.trim_matches(|c| c == '[' || c == ']').to_string();
+            continue;
         }
 
-        // Django Stack
-        if framework_names.contains("Django") {
-            return (Some("django-stack".to_string()), Some(0.85));
+        let array_type = if section == "project" && line.starts_with("dependencies") {
+            Some("runtime")
+        } else if section == "project.optional-dependencies" {
+            Some("dev")
+        } else {
+            None
+        };
+        if let (Some(dep_type), Some((_, rest))) = (array_type, line.split_once('[')) {
+            // Entries may start on the opening line
+            push_requirements(rest, dep_type, dependencies);
+            if !rest.contains(']') {
+                in_array = Some(dep_type);
+            }
+            continue;
         }
 
-        // SvelteKit
-        if framework_names.contains("SvelteKit") {
-            return (Some("sveltekit-stack".to_string()), Some(0.85));
+        // Poetry tables: `[tool.poetry.dependencies]`, `[tool.poetry.group.dev.dependencies]`
+        let poetry_type = if section == "tool.poetry.dependencies" {
+            Some("runtime")
+        } else if section.starts_with("tool.poetry.") && section.ends_with("dependencies") {
+            Some("dev")
+        } else {
+            None
+        };
+        if let Some(dep_type) = poetry_type {
+            if let Some((name, version)) = parse_toml_dependency_line(line) {
+                if name != "python" {
+                    push_dependency(dependencies, &name, version, dep_type, source);
+                }
+            }
         }
+    }
+}
 
-        // Generic detection
-        if has_frontend && has_backend {
-            return (Some("fullstack-generic".to_string()), Some(0.5));
+fn parse_cargo_toml(content: &str, source: &str, dependencies: &mut Vec<DetectedDependency>) {
+    let mut dep_type: Option<&str> = None;
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
         }
 
-        (None, None)
+        if line.starts_with('[') {
+            let section = line.trim_matches(|c| c == '[' || c == ']');
+            let table_type = |table: &str| match table {
+                "dependencies" => Some("runtime"),
+                "dev-dependencies" => Some("dev"),
+                "build-dependencies" => Some("build"),
+                _ => None,
+            };
+            // `[dependencies.serde]` declares a single dependency as a table
+            if let Some((table, name)) = section.rsplit_once('.') {
+                if let Some(table_dep_type) = table_type(table.rsplit('.').next().unwrap_or(table)) {
+                    push_dependency(dependencies, name, None, table_dep_type, source);
+                    dep_type = None;
+                    continue;
+                }
+            }
+            // Also covers `[workspace.dependencies]` and `[target.'cfg(..)'.dependencies]`
+            dep_type = table_type(section.rsplit('.').next().unwrap_or(section));
+            continue;
+        }
+
+        if let Some(dep_type) = dep_type {
+            if let Some((name, version)) = parse_toml_dependency_line(line) {
+                push_dependency(dependencies, &name, version, dep_type, source);
+            }
+        }
+    }
+}
+
+fn parse_go_mod(content: &str, source: &str, dependencies: &mut Vec<DetectedDependency>) {
+    let mut in_require_block = false;
+
+    for line in content.lines() {
+        let line = line.split("//").next().unwrap_or("").trim();
+        let requirement = if in_require_block {
+            if line == ")" {
+                in_require_block = false;
+                continue;
+            }
+            line
+        } else if line == "require (" {
+            in_require_block = true;
+            continue;
+        } else if let Some(rest) = line.strip_prefix("require ") {
+            rest
+        } else {
+            continue;
+        };
+
+        let mut parts = requirement.split_whitespace();
+        if let Some(module) = parts.next() {
+            push_dependency(dependencies, module, parts.next().map(|v| v.to_string()), "runtime", source);
+        }
+    }
+}
+
+/// Line count and imports of a source file, a binary entry when it is binary
+/// or not UTF-8, or `None` if it cannot be read
+fn read_source(path: &Path, lang_id: &str, fingerprint: (u64, u64)) -> Option<CachedFile> {
+    let bytes = fs::read(path).ok()?;
+    if bytes.iter().take(BINARY_SNIFF_BYTES).any(|b| *b == 0) {
+        return Some(CachedFile::binary(fingerprint));
+    }
+    let Ok(content) = String::from_utf8(bytes) else {
+        return Some(CachedFile::binary(fingerprint));
+    };
+
+    let mut imports = HashSet::new();
+    collect_imports(lang_id, &content, &mut imports);
+    let (mtime_ns, size) = fingerprint;
+    Some(CachedFile {
+        mtime_ns,
+        size,
+        line_count: content.lines().count() as u32,
+        imports: imports.into_iter().collect(),
+        module_imports: import_graph::collect_module_imports(lang_id, &content),
+        binary: false,
+    })
+}
+
+/// Record the root module of each import/use statement in a source file
+fn collect_imports(lang_id: &str, content: &str, imports: &mut HashSet<String>) {
+    static JS_IMPORT: OnceLock<Regex> = OnceLock::new();
+    static PY_IMPORT: OnceLock<Regex> = OnceLock::new();
+    static RUST_USE: OnceLock<Regex> = OnceLock::new();
+    static GO_IMPORT: OnceLock<Regex> = OnceLock::new();
+
+    match lang_id {
+        "javascript-typescript" | "svelte" => {
+            let re = JS_IMPORT.get_or_init(|| {
+                Regex::new(r#"(?:\bfrom\s+|\bimport\s+|\brequire\(\s*|\bimport\(\s*)['"]([^'"]+)['"]"#).unwrap()
+            });
+            for cap in re.captures_iter(content) {
+                let specifier = &cap[1];
+                if specifier.starts_with('.') || specifier.starts_with('/') || specifier.starts_with('$') {
+                    continue;
+                }
+                // Keep the package name: `@scope/pkg/sub` -> `@scope/pkg`, `pkg/sub` -> `pkg`
+                let segments = if specifier.starts_with('@') { 2 } else { 1 };
+                let package: Vec<&str> = specifier.splitn(segments + 1, '/').take(segments).collect();
+                imports.insert(package.join("/"));
+            }
+        }
+        "python" => {
+            let re = PY_IMPORT.get_or_init(|| {
+                Regex::new(r"(?m)^\s*(?:from\s+([A-Za-z_][\w]*)[\w.]*\s+import|import\s+([A-Za-z_][\w]*))").unwrap()
+            });
+            for cap in re.captures_iter(content) {
+                if let Some(module) = cap.get(1).or_else(|| cap.get(2)) {
+                    imports.insert(module.as_str().to_string());
+                }
+            }
+        }
+        "rust" => {
+            let re = RUST_USE.get_or_init(|| {
+                Regex::new(r"(?m)^\s*(?:pub(?:\([^)]*\))?\s+)?(?:use|extern\s+crate)\s+([A-Za-z_]\w*)").unwrap()
+            });
+            for cap in re.captures_iter(content) {
+                let module = &cap[1];
+                if !matches!(module, "crate" | "self" | "super" | "std" | "core" | "alloc") {
+                    imports.insert(module.to_string());
+                }
+            }
+        }
+        "go" => {
+            let re = GO_IMPORT.get_or_init(|| {
+                Regex::new(r#"(?m)^\s*(?:import\s+)?(?:[\w.]+\s+)?"([\w./-]+)"\s*$"#).unwrap()
+            });
+            for cap in re.captures_iter(content) {
+                imports.insert(cap[1].to_string());
+            }
+        }
+        _ => {}
     }
 }
 
 #[tauri::command]
-pub async fn analyze_codebase(project_path: String) -> Result<AnalysisResult, String> {
-    let analyzer = CodeAnalyzer::new();
-    Ok(analyzer.analyze_project(&project_path))
+pub async fn analyze_codebase(
+    app: tauri::AppHandle,
+    registry: tauri::State<'_, AnalysisRegistry>,
+    project_path: String,
+    options: Option<AnalyzerOptions>,
+    analysis_id: Option<String>,
+) -> Result<AnalysisResult, String> {
+    let mut analyzer = CodeAnalyzer::new();
+    if let Some(options) = &options {
+        analyzer.apply_options(options);
+    }
+    if let Ok(config_dir) = app.path().app_config_dir() {
+        analyzer.load_user_rules(&config_dir.join(USER_RULES_FILE))?;
+    }
+    analyzer.enable_cache(app.path().app_data_dir().ok().map(|dir| dir.join("analysis-cache")));
+
+    let analysis_id = analysis_id.unwrap_or_else(analysis_progress::generated_analysis_id);
+    let progress = AnalysisProgress::for_app(&app, &analysis_id, registry.register(&analysis_id)?);
+    let result = analyzer.analyze_project(&project_path, &progress);
+    if progress.is_cancelled() {
+        progress.phase("cancelled", analysis_progress::CANCELLED_ERROR);
+    }
+    registry.finish(&analysis_id);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(dependencies: &[DetectedDependency]) -> Vec<&str> {
+        dependencies.iter().map(|d| d.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_parse_cargo_toml_tables() {
+        let content = r#"
+[package]
+name = "api"
+version = "0.1.0"
+
+[dependencies]
+axum = "0.7"
+sqlx = { version = "0.7", features = ["postgres"] }
+
+[dependencies.serde]
+version = "1"
+
+[dev-dependencies]
+tempfile = "3"
+"#;
+        let mut dependencies = Vec::new();
+        parse_cargo_toml(content, "api/Cargo.toml", &mut dependencies);
+
+        assert_eq!(names(&dependencies), vec!["axum", "sqlx", "serde", "tempfile"]);
+        assert_eq!(dependencies[1].version.as_deref(), Some("0.7"));
+        assert_eq!(dependencies[3].dep_type, "dev");
+        assert_eq!(dependencies[0].source, "api/Cargo.toml");
+    }
+
+    #[test]
+    fn test_parse_python_manifests() {
+        let mut dependencies = Vec::new();
+        parse_requirements_txt(
+            "-r base.txt\nSQLAlchemy[asyncio]>=2.0\npsycopg2-binary==2.9.9 ; python_version > '3.8'\n",
+            "requirements.txt",
+            &mut dependencies,
+        );
+        assert_eq!(names(&dependencies), vec!["SQLAlchemy", "psycopg2-binary"]);
+        assert_eq!(dependencies[1].version.as_deref(), Some("2.9.9"));
+
+        let mut dependencies = Vec::new();
+        parse_pyproject_toml(
+            "[project]\nname = \"svc\"\ndependencies = [\n  \"fastapi>=0.110\",\n  \"alembic\",\n]\n\n[tool.poetry.dependencies]\npython = \"^3.11\"\nredis = \"5.0.1\"\n",
+            "pyproject.toml",
+            &mut dependencies,
+        );
+        assert_eq!(names(&dependencies), vec!["fastapi", "alembic", "redis"]);
+    }
+
+    #[test]
+    fn test_parse_go_mod_require_block() {
+        let content = "module example.com/svc\n\ngo 1.22\n\nrequire github.com/gin-gonic/gin v1.9.1\n\nrequire (\n\tgorm.io/gorm v1.25.5\n\tgithub.com/nats-io/nats.go v1.31.0 // indirect\n)\n";
+        let mut dependencies = Vec::new();
+        parse_go_mod(content, "go.mod", &mut dependencies);
+
+        assert_eq!(
+            names(&dependencies),
+            vec!["github.com/gin-gonic/gin", "gorm.io/gorm", "github.com/nats-io/nats.go"]
+        );
+        assert_eq!(dependencies[2].version.as_deref(), Some("v1.31.0"));
+    }
+
+    #[test]
+    fn test_detect_migrations_infers_tool() {
+        let files: Vec<String> = [
+            "prisma/schema.prisma",
+            "prisma/migrations/20240101_init/migration.sql",
+            "prisma/migrations/migration_lock.toml",
+            "db/migrations/0001_users.up.sql",
+            "db/migrations/0001_users.down.sql",
+            "go.mod",
+        ]
+        .iter()
+        .map(|f| f.to_string())
+        .collect();
+
+        let migrations = CodeAnalyzer::detect_migrations(&files, &[]);
+        assert_eq!(migrations.len(), 2);
+        assert_eq!(migrations[0].path, "prisma/migrations");
+        assert_eq!(migrations[0].tool.as_deref(), Some("prisma"));
+        assert_eq!(migrations[0].file_count, 2);
+        assert_eq!(migrations[1].tool.as_deref(), Some("golang-migrate"));
+    }
+
+    #[test]
+    fn test_scan_budget_and_skipped_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path();
+        fs::create_dir_all(project.join("src")).unwrap();
+        fs::write(project.join("src/a.py"), "print(1)\n").unwrap();
+        fs::write(project.join("src/b.py"), "x = 1\n".repeat(100)).unwrap();
+        fs::write(project.join("src/c.py"), b"\x00\x01binary").unwrap();
+
+        let mut analyzer = CodeAnalyzer::new();
+        analyzer.apply_options(&AnalyzerOptions {
+            max_file_size_bytes: Some(100),
+            ..Default::default()
+        });
+        let result = analyzer.analyze_project(&project.to_string_lossy(), &AnalysisProgress::silent());
+        assert!(!result.scan_stats.truncated);
+        assert_eq!(result.scan_stats.large_files_skipped, 1);
+        assert_eq!(result.scan_stats.binary_files_skipped, 1);
+        assert_eq!(result.profile.unwrap().languages[0].line_count, 1);
+
+        analyzer.apply_options(&AnalyzerOptions {
+            max_files: Some(2),
+            ..Default::default()
+        });
+        let result = analyzer.analyze_project(&project.to_string_lossy(), &AnalysisProgress::silent());
+        assert!(result.scan_stats.truncated);
+        // The budget keeps the first files by path, not whichever thread listed first
+        let files = &result.profile.unwrap().languages[0].files;
+        assert_eq!(files.len(), 2);
+        assert!(files[0].ends_with("a.py") && files[1].ends_with("b.py"));
+
+        // Past the headroom the walk stops descending instead of listing the whole tree
+        let chain = tempfile::tempdir().unwrap();
+        let mut deep = chain.path().join("deep");
+        for level in 0..4 {
+            fs::create_dir_all(&deep).unwrap();
+            fs::write(deep.join("one.py"), "").unwrap();
+            fs::write(deep.join("two.py"), "").unwrap();
+            deep = deep.join(format!("level{}", level));
+        }
+        analyzer.apply_options(&AnalyzerOptions {
+            max_files: Some(1),
+            ..Default::default()
+        });
+        let listing = analyzer.list_files(chain.path(), &AnalysisProgress::silent());
+        assert!(listing.truncated);
+        let listed: Vec<&str> = listing.entries.iter().map(|e| e.relative.as_str()).collect();
+        assert!(listed.contains(&"deep/level0"));
+        assert!(!listed.iter().any(|e| e.starts_with("deep/level0/")));
+    }
+
+    #[test]
+    fn test_match_stack_ranks_whole_catalog() {
+        let dependency = |name: &str| DetectedDependency {
+            name: name.to_string(),
+            version: None,
+            dep_type: "runtime".to_string(),
+            source: "composer.json".to_string(),
+        };
+        let dependencies = vec![dependency("laravel/framework"), dependency("php")];
+        let files = vec!["artisan".to_string(), "composer.json".to_string()];
+
+        let matches = CodeAnalyzer::match_stack(&[], &dependencies, &[], &[], &files);
+        assert_eq!(matches.len(), STACK_CATALOG.len());
+        assert_eq!(matches[0].stack_id, "laravel-stack");
+        assert_eq!(matches[0].score, 0.99);
+        assert_eq!(matches[0].missing, vec!["orm: Eloquent".to_string()]);
+        assert!(matches[1..].iter().all(|m| m.score == 0.0));
+    }
 }
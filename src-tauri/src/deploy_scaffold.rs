@@ -0,0 +1,711 @@
+//! Deploy Scaffold - Platform configuration for generated projects
+//!
+//! Stacks describe how their app is installed, built and started once as an
+//! [`AppRuntime`]. This module turns that description into the configuration
+//! each deployment target expects: `fly.toml`, `render.yaml`, `vercel.json`,
+//! `netlify.toml`, Kubernetes manifests, a Helm chart, or a systemd unit
+//! behind nginx. Every file is linted against the target's required shape
+//! before it is returned, so a broken template fails generation instead of
+//! the first deploy.
+
+use crate::docker_scaffold;
+use crate::yaml_writer::{int, list, map, str, Yaml};
+
+// ============================================================================
+// RUNTIMES
+// ============================================================================
+
+/// How a stack's app runs outside development
+pub struct AppRuntime {
+    pub language: &'static str, // "node", "python", "go", "php"
+    /// Port the production server listens on; 0 for static sites
+    pub port: u16,
+    /// Files `install` reads; Docker copies them ahead of the source
+    pub manifests: &'static [&'static str],
+    pub install: &'static str,
+    pub build: Option<&'static str>,
+    /// Build output and files the production server reads. Node images copy
+    /// only these, `package.json` and production dependencies out of the
+    /// build stage; empty keeps the whole pruned app.
+    pub serve: &'static [&'static str],
+    /// Production server command; `{project}` is the Python package name.
+    /// Empty for static sites.
+    pub start: &'static [&'static str],
+    pub health_path: &'static str,
+    /// Output Vercel and Netlify publish; None when they cannot host the stack
+    pub publish_dir: Option<&'static str>,
+}
+
+impl AppRuntime {
+    pub fn is_static(&self) -> bool {
+        self.start.is_empty()
+    }
+}
+
+/// One app as a deployment target sees it
+pub struct DeployApp<'a> {
+    /// DNS-safe name used for services, units and chart names
+    pub name: String,
+    pub runtime: &'a AppRuntime,
+    /// Start command with placeholders filled in
+    pub start: Vec<String>,
+    /// Variables the platform must provide as secrets
+    pub secrets: Vec<String>,
+}
+
+impl<'a> DeployApp<'a> {
+    pub fn new(project_name: &str, runtime: &'a AppRuntime, secrets: Vec<String>) -> Self {
+        let package = project_name.replace(['-', ' '], "_").to_lowercase();
+        let mut name: String = project_name
+            .to_lowercase()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+            .collect();
+        name = name.trim_matches('-').to_string();
+        if name.is_empty() {
+            name = "app".to_string();
+        }
+        DeployApp {
+            name,
+            runtime,
+            start: runtime.start.iter().map(|part| part.replace("{project}", &package)).collect(),
+            secrets,
+        }
+    }
+
+    fn start_command(&self) -> String {
+        self.start.join(" ")
+    }
+}
+
+// ============================================================================
+// TARGETS
+// ============================================================================
+
+pub struct DeployTarget {
+    pub id: &'static str, // "docker", "fly", "render", "vercel", "netlify", "kubernetes", "helm", "systemd"
+    pub name: &'static str,
+    /// Runs the app from the project's Dockerfile
+    pub container: bool,
+}
+
+pub const DEPLOY_TARGETS: &[DeployTarget] = &[
+    DeployTarget { id: "docker", name: "Docker Compose", container: true },
+    DeployTarget { id: "fly", name: "Fly.io", container: true },
+    DeployTarget { id: "render", name: "Render", container: false },
+    DeployTarget { id: "vercel", name: "Vercel", container: false },
+    DeployTarget { id: "netlify", name: "Netlify", container: false },
+    DeployTarget { id: "kubernetes", name: "Kubernetes", container: true },
+    DeployTarget { id: "helm", name: "Helm", container: true },
+    DeployTarget { id: "systemd", name: "systemd + nginx", container: false },
+];
+
+/// Look up a deployment platform; `None` and platforms without a generator have no target
+pub fn deploy_target(platform: Option<&str>) -> Option<&'static DeployTarget> {
+    let id = platform?;
+    DEPLOY_TARGETS.iter().find(|target| target.id == id)
+}
+
+impl DeployTarget {
+    /// Whether the target can run an app with this runtime
+    pub fn supports(&self, runtime: &AppRuntime) -> bool {
+        match self.id {
+            "vercel" | "netlify" => runtime.publish_dir.is_some(),
+            _ => !runtime.is_static(),
+        }
+    }
+
+    /// Whether the project needs a Dockerfile on this target; Render builds
+    /// PHP apps from one
+    pub fn needs_dockerfile(&self, runtime: &AppRuntime) -> bool {
+        self.container || (self.id == "render" && runtime.language == "php")
+    }
+}
+
+/// A generated file, relative to the project root
+pub type DeployFile = (String, String);
+
+/// Platform configuration for `app` on `target`; Docker files are written by
+/// the project generator itself. YAML documents are checked against the keys
+/// their platform requires and text formats are linted, so errors name the
+/// file that failed.
+pub fn deploy_files(target: &DeployTarget, app: &DeployApp) -> Result<Vec<DeployFile>, String> {
+    let mut files = Vec::new();
+    let mut yaml = |path: String, documents: Vec<Yaml>, required: &[&str]| -> Result<(), String> {
+        for document in &documents {
+            document.require(&path, required)?;
+        }
+        files.push((path, documents.iter().map(Yaml::to_yaml).collect::<Vec<_>>().join("---\n")));
+        Ok(())
+    };
+    match target.id {
+        "render" => yaml(
+            "render.yaml".to_string(),
+            vec![render_yaml(app)],
+            &["services.0.type", "services.0.name", "services.0.runtime", "services.0.plan"],
+        )?,
+        "kubernetes" => yaml(
+            format!("deploy/k8s/{}.yaml", app.name),
+            kubernetes_manifests(app),
+            &["apiVersion", "kind", "metadata.name", "metadata.labels.app", "spec"],
+        )?,
+        "helm" => {
+            let dir = format!("deploy/helm/{}", app.name);
+            let (chart, values) = helm_chart(app);
+            if chart.get("apiVersion").and_then(Yaml::as_str) != Some("v2") {
+                return Err(format!("{}/Chart.yaml: Helm 3 charts use apiVersion v2", dir));
+            }
+            yaml(format!("{}/Chart.yaml", dir), vec![chart], &["name", "version", "appVersion"])?;
+            // Every value the templates read must be defined
+            let referenced: Vec<&str> = [HELM_DEPLOYMENT, HELM_SERVICE].iter().flat_map(|t| helm_values(t)).collect();
+            yaml(format!("{}/values.yaml", dir), vec![values], &referenced)?;
+            files.push((format!("{}/templates/deployment.yaml", dir), HELM_DEPLOYMENT.to_string()));
+            files.push((format!("{}/templates/service.yaml", dir), HELM_SERVICE.to_string()));
+        }
+        "fly" => files.push(("fly.toml".to_string(), fly_toml(app))),
+        "vercel" => files.push(("vercel.json".to_string(), vercel_json(app))),
+        "netlify" => files.push(("netlify.toml".to_string(), netlify_toml(app))),
+        "systemd" => {
+            files.push((format!("deploy/systemd/{}.service", app.name), systemd_unit(app)));
+            files.push((format!("deploy/nginx/{}.conf", app.name), nginx_site(app)));
+        }
+        _ => {}
+    }
+    for (path, content) in &files {
+        lint(path, content)?;
+    }
+    Ok(files)
+}
+
+/// README section for `target`, including the command that lints the files locally
+pub fn deploy_instructions(target: &DeployTarget, app: &DeployApp) -> String {
+    let secrets = if app.secrets.is_empty() {
+        String::new()
+    } else {
+        format!("\n\nProvide {} as platform secrets.", app.secrets.iter().map(|s| format!("`{}`", s)).collect::<Vec<_>>().join(", "))
+    };
+    let steps = match target.id {
+        "fly" => format!(
+            "`fly.toml` builds the Dockerfile and checks `{}`.\n\n```bash\nfly launch --no-deploy --copy-config\nfly config validate\nfly deploy\n```",
+            app.runtime.health_path
+        ),
+        "render" => "`render.yaml` is a Render Blueprint; create it from the dashboard under **New > Blueprint**.\n\n```bash\n# Validate the blueprint locally\nrender blueprints validate render.yaml\n```".to_string(),
+        "vercel" => "`vercel.json` pins the install and build commands.\n\n```bash\nnpm install -g vercel\nvercel build\nvercel deploy --prebuilt\n```".to_string(),
+        "netlify" => "`netlify.toml` holds the build command and publish directory.\n\n```bash\nnpm install -g netlify-cli\nnetlify build\nnetlify deploy --prod\n```".to_string(),
+        "kubernetes" => format!(
+            "`deploy/k8s/{0}.yaml` holds a Deployment and Service; variables come from the optional `{0}-env` Secret.\n\n```bash\ndocker build -t {0}:latest .\nkubectl apply --dry-run=client -f deploy/k8s/{0}.yaml\nkubectl apply -f deploy/k8s/{0}.yaml\n```",
+            app.name
+        ),
+        "helm" => format!(
+            "`deploy/helm/{0}` is a Helm chart; image, replicas and resources live in `values.yaml`.\n\n```bash\ndocker build -t {0}:latest .\nhelm lint deploy/helm/{0}\nhelm upgrade --install {0} deploy/helm/{0}\n```",
+            app.name
+        ),
+        "systemd" => format!(
+            "`deploy/systemd/{0}.service` runs the app from `/opt/{0}` as the `{0}` user, reading variables from `/etc/{0}/env`; `deploy/nginx/{0}.conf` proxies port 80 to it.\n\n```bash\nsudo cp deploy/systemd/{0}.service /etc/systemd/system/\nsudo systemd-analyze verify /etc/systemd/system/{0}.service\nsudo systemctl enable --now {0}\nsudo cp deploy/nginx/{0}.conf /etc/nginx/conf.d/\nsudo nginx -t && sudo systemctl reload nginx\n```",
+            app.name
+        ),
+        _ => return String::new(),
+    };
+    format!("This project is configured for {}. {}{}", target.name, steps, secrets)
+}
+
+// ============================================================================
+// PLATFORMS
+// ============================================================================
+
+fn fly_toml(app: &DeployApp) -> String {
+    format!(
+        r#"app = "{name}"
+primary_region = "iad"
+
+[build]
+  dockerfile = "Dockerfile"
+
+[env]
+  PORT = "{port}"
+
+[http_service]
+  internal_port = {port}
+  force_https = true
+  auto_stop_machines = true
+  auto_start_machines = true
+  min_machines_running = 0
+
+[[http_service.checks]]
+  grace_period = "10s"
+  interval = "30s"
+  method = "GET"
+  path = "{health}"
+  timeout = "5s"
+"#,
+        name = app.name,
+        port = app.runtime.port,
+        health = app.runtime.health_path
+    )
+}
+
+fn render_yaml(app: &DeployApp) -> Yaml {
+    let runtime = app.runtime;
+    let mut service = map([("type", str("web")), ("name", str(&app.name))]);
+    // Render has no native PHP runtime
+    if runtime.language == "php" {
+        service.insert("runtime", str("docker"));
+        service.insert("dockerfilePath", str("./Dockerfile"));
+    } else {
+        service.insert("runtime", str(runtime.language));
+        let build = match runtime.build {
+            Some(build) => format!("{} && {}", runtime.install, build),
+            None => runtime.install.to_string(),
+        };
+        service.insert("buildCommand", str(build));
+        service.insert("startCommand", str(app.start_command()));
+    }
+    service.insert("plan", str("free"));
+    service.insert("healthCheckPath", str(runtime.health_path));
+
+    let mut env = vec![map([("key", str("PORT")), ("value", str(runtime.port.to_string()))])];
+    env.extend(app.secrets.iter().map(|key| map([("key", str(key)), ("sync", Yaml::Bool(false))])));
+    service.insert("envVars", Yaml::List(env));
+    map([("services", list([service]))])
+}
+
+fn vercel_json(app: &DeployApp) -> String {
+    let runtime = app.runtime;
+    let mut config = serde_json::json!({
+        "$schema": "https://openapi.vercel.sh/vercel.json",
+        "installCommand": runtime.install,
+    });
+    if let Some(build) = runtime.build {
+        config["buildCommand"] = build.into();
+    }
+    // Frameworks publish through their adapters; static exports are a single-page app
+    if runtime.is_static() {
+        config["outputDirectory"] = runtime.publish_dir.unwrap_or("dist").into();
+        config["rewrites"] = serde_json::json!([{ "source": "/(.*)", "destination": "/" }]);
+    }
+    format!("{}\n", serde_json::to_string_pretty(&config).unwrap_or_default())
+}
+
+fn netlify_toml(app: &DeployApp) -> String {
+    let runtime = app.runtime;
+    let command = match runtime.build {
+        Some(build) => format!("{} && {}", runtime.install, build),
+        None => runtime.install.to_string(),
+    };
+    let mut content = format!(
+        "[build]\n  command = \"{}\"\n  publish = \"{}\"\n\n[build.environment]\n  NODE_VERSION = \"{}\"\n",
+        command,
+        runtime.publish_dir.unwrap_or("dist"),
+        docker_scaffold::NODE_VERSION
+    );
+    if runtime.publish_dir == Some(".next") {
+        content.push_str("\n[[plugins]]\n  package = \"@netlify/plugin-nextjs\"\n");
+    }
+    if runtime.is_static() {
+        content.push_str("\n[[redirects]]\n  from = \"/*\"\n  to = \"/index.html\"\n  status = 200\n");
+    }
+    content
+}
+
+fn kubernetes_manifests(app: &DeployApp) -> Vec<Yaml> {
+    let port = app.runtime.port as i64;
+    let labels = || map([("app", str(&app.name))]);
+    let probe = |delay: i64| {
+        map([
+            ("httpGet", map([("path", str(app.runtime.health_path)), ("port", str("http"))])),
+            ("initialDelaySeconds", int(delay)),
+            ("periodSeconds", int(10)),
+        ])
+    };
+    let container = map([
+        ("name", str(&app.name)),
+        ("image", str(format!("{}:latest", app.name))),
+        ("imagePullPolicy", str("IfNotPresent")),
+        ("ports", list([map([("name", str("http")), ("containerPort", int(port))])])),
+        ("envFrom", list([map([("secretRef", map([("name", str(format!("{}-env", app.name))), ("optional", Yaml::Bool(true))]))])])),
+        ("readinessProbe", probe(5)),
+        ("livenessProbe", probe(15)),
+        (
+            "resources",
+            map([
+                ("requests", map([("cpu", str("100m")), ("memory", str("128Mi"))])),
+                ("limits", map([("memory", str("512Mi"))])),
+            ]),
+        ),
+        (
+            "securityContext",
+            map([("runAsNonRoot", Yaml::Bool(true)), ("allowPrivilegeEscalation", Yaml::Bool(false))]),
+        ),
+    ]);
+    let deployment = map([
+        ("apiVersion", str("apps/v1")),
+        ("kind", str("Deployment")),
+        ("metadata", map([("name", str(&app.name)), ("labels", labels())])),
+        (
+            "spec",
+            map([
+                ("replicas", int(2)),
+                ("selector", map([("matchLabels", labels())])),
+                (
+                    "template",
+                    map([("metadata", map([("labels", labels())])), ("spec", map([("containers", list([container]))]))]),
+                ),
+            ]),
+        ),
+    ]);
+    let service = map([
+        ("apiVersion", str("v1")),
+        ("kind", str("Service")),
+        ("metadata", map([("name", str(&app.name)), ("labels", labels())])),
+        (
+            "spec",
+            map([
+                ("type", str("ClusterIP")),
+                ("selector", labels()),
+                ("ports", list([map([("name", str("http")), ("port", int(80)), ("targetPort", str("http"))])])),
+            ]),
+        ),
+    ]);
+    vec![deployment, service]
+}
+
+/// Chart.yaml and values.yaml; the templates are fixed
+fn helm_chart(app: &DeployApp) -> (Yaml, Yaml) {
+    let chart = map([
+        ("apiVersion", str("v2")),
+        ("name", str(&app.name)),
+        ("description", str(format!("Helm chart for {}", app.name))),
+        ("type", str("application")),
+        ("version", str("0.1.0")),
+        ("appVersion", str("0.1.0")),
+    ]);
+    let values = map([
+        ("replicaCount", int(2)),
+        (
+            "image",
+            map([("repository", str(&app.name)), ("tag", str("latest")), ("pullPolicy", str("IfNotPresent"))]),
+        ),
+        ("containerPort", int(app.runtime.port as i64)),
+        ("healthPath", str(app.runtime.health_path)),
+        ("envSecret", str(format!("{}-env", app.name))),
+        ("service", map([("type", str("ClusterIP")), ("port", int(80))])),
+        (
+            "resources",
+            map([
+                ("requests", map([("cpu", str("100m")), ("memory", str("128Mi"))])),
+                ("limits", map([("memory", str("512Mi"))])),
+            ]),
+        ),
+    ]);
+    (chart, values)
+}
+
+/// `.Values` paths a template reads, e.g. `image.repository`
+fn helm_values(template: &str) -> Vec<&str> {
+    template
+        .split(".Values.")
+        .skip(1)
+        .map(|rest| rest.split(|c: char| !(c.is_ascii_alphanumeric() || c == '.')).next().unwrap_or(""))
+        .collect()
+}
+
+const HELM_DEPLOYMENT: &str = r#"apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: {{ .Release.Name }}
+  labels:
+    app.kubernetes.io/name: {{ .Chart.Name }}
+    app.kubernetes.io/instance: {{ .Release.Name }}
+spec:
+  replicas: {{ .Values.replicaCount }}
+  selector:
+    matchLabels:
+      app.kubernetes.io/name: {{ .Chart.Name }}
+      app.kubernetes.io/instance: {{ .Release.Name }}
+  template:
+    metadata:
+      labels:
+        app.kubernetes.io/name: {{ .Chart.Name }}
+        app.kubernetes.io/instance: {{ .Release.Name }}
+    spec:
+      containers:
+        - name: {{ .Chart.Name }}
+          image: "{{ .Values.image.repository }}:{{ .Values.image.tag }}"
+          imagePullPolicy: {{ .Values.image.pullPolicy }}
+          ports:
+            - name: http
+              containerPort: {{ .Values.containerPort }}
+          envFrom:
+            - secretRef:
+                name: {{ .Values.envSecret }}
+                optional: true
+          readinessProbe:
+            httpGet:
+              path: {{ .Values.healthPath }}
+              port: http
+            initialDelaySeconds: 5
+          livenessProbe:
+            httpGet:
+              path: {{ .Values.healthPath }}
+              port: http
+            initialDelaySeconds: 15
+          resources:
+            {{- toYaml .Values.resources | nindent 12 }}
+          securityContext:
+            runAsNonRoot: true
+            allowPrivilegeEscalation: false
+"#;
+
+const HELM_SERVICE: &str = r#"apiVersion: v1
+kind: Service
+metadata:
+  name: {{ .Release.Name }}
+  labels:
+    app.kubernetes.io/name: {{ .Chart.Name }}
+    app.kubernetes.io/instance: {{ .Release.Name }}
+spec:
+  type: {{ .Values.service.type }}
+  selector:
+    app.kubernetes.io/name: {{ .Chart.Name }}
+    app.kubernetes.io/instance: {{ .Release.Name }}
+  ports:
+    - name: http
+      port: {{ .Values.service.port }}
+      targetPort: http
+"#;
+
+/// Absolute executable for systemd's ExecStart; Python tools come from the app's virtualenv
+fn systemd_exec(app: &DeployApp) -> String {
+    let root = format!("/opt/{}", app.name);
+    let mut parts = app.start.clone();
+    if let Some(program) = parts.first_mut() {
+        *program = match program.as_str() {
+            "npm" | "node" | "php" => format!("/usr/bin/{}", program),
+            "python" | "uvicorn" | "gunicorn" => format!("{}/.venv/bin/{}", root, program),
+            relative if relative.starts_with("./") => format!("{}/{}", root, &relative[2..]),
+            other => other.to_string(),
+        };
+    }
+    parts.join(" ")
+}
+
+fn systemd_unit(app: &DeployApp) -> String {
+    format!(
+        r#"[Unit]
+Description={name}
+After=network-online.target
+Wants=network-online.target
+
+[Service]
+Type=simple
+User={name}
+Group={name}
+WorkingDirectory=/opt/{name}
+Environment=PORT={port}
+EnvironmentFile=-/etc/{name}/env
+ExecStart={exec}
+Restart=on-failure
+RestartSec=5
+NoNewPrivileges=true
+PrivateTmp=true
+ProtectSystem=strict
+ProtectHome=true
+ReadWritePaths=/opt/{name}
+
+[Install]
+WantedBy=multi-user.target
+"#,
+        name = app.name,
+        port = app.runtime.port,
+        exec = systemd_exec(app)
+    )
+}
+
+fn nginx_site(app: &DeployApp) -> String {
+    format!(
+        r#"upstream {name} {{
+    server 127.0.0.1:{port};
+}}
+
+server {{
+    listen 80;
+    server_name _;
+
+    client_max_body_size 10m;
+
+    location / {{
+        proxy_pass http://{name};
+        proxy_http_version 1.1;
+        proxy_set_header Host $host;
+        proxy_set_header X-Real-IP $remote_addr;
+        proxy_set_header X-Forwarded-For $proxy_add_x_forwarded_for;
+        proxy_set_header X-Forwarded-Proto $scheme;
+        proxy_set_header Upgrade $http_upgrade;
+        proxy_set_header Connection "upgrade";
+    }}
+}}
+"#,
+        name = app.name,
+        port = app.runtime.port
+    )
+}
+
+// ============================================================================
+// LINT
+// ============================================================================
+
+/// Lint a generated text file: TOML and JSON must parse with the keys their
+/// platform reads, Helm templates must be balanced, systemd units need an
+/// absolute ExecStart and nginx blocks must close
+pub fn lint(path: &str, content: &str) -> Result<(), String> {
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    match file_name {
+        "fly.toml" => lint_fly(content),
+        "netlify.toml" => lint_netlify(content),
+        "vercel.json" => lint_vercel(content),
+        _ if path.contains("/templates/") => lint_helm_template(path, content),
+        _ if path.ends_with(".service") => lint_systemd(path, content),
+        _ if path.ends_with(".conf") => lint_nginx(path, content),
+        _ => Ok(()),
+    }
+}
+
+fn parse_toml(path: &str, content: &str) -> Result<toml::Table, String> {
+    toml::from_str(content).map_err(|e| format!("{}: {}", path, e))
+}
+
+fn lint_fly(content: &str) -> Result<(), String> {
+    let config = parse_toml("fly.toml", content)?;
+    let service = config.get("http_service").and_then(|s| s.as_table()).ok_or("fly.toml: missing [http_service]")?;
+    if config.get("app").and_then(|a| a.as_str()).is_none_or(str::is_empty) {
+        return Err("fly.toml: 'app' must name the Fly app".to_string());
+    }
+    match service.get("internal_port").and_then(|p| p.as_integer()) {
+        Some(port) if (1..=65535).contains(&port) => {}
+        _ => return Err("fly.toml: http_service.internal_port must be a port number".to_string()),
+    }
+    let checks = service.get("checks").and_then(|c| c.as_array()).map(Vec::as_slice).unwrap_or_default();
+    if checks.iter().any(|check| !check.get("path").and_then(|p| p.as_str()).is_some_and(|p| p.starts_with('/'))) {
+        return Err("fly.toml: every http_service check needs an absolute path".to_string());
+    }
+    Ok(())
+}
+
+fn lint_netlify(content: &str) -> Result<(), String> {
+    let config = parse_toml("netlify.toml", content)?;
+    let build = config.get("build").and_then(|b| b.as_table()).ok_or("netlify.toml: missing [build]")?;
+    for key in ["command", "publish"] {
+        if build.get(key).and_then(|v| v.as_str()).is_none() {
+            return Err(format!("netlify.toml: missing build.{}", key));
+        }
+    }
+    Ok(())
+}
+
+fn lint_vercel(content: &str) -> Result<(), String> {
+    const KEYS: &[&str] = &["$schema", "installCommand", "buildCommand", "outputDirectory", "rewrites", "framework", "headers", "redirects"];
+    let config: serde_json::Value = serde_json::from_str(content).map_err(|e| format!("vercel.json: {}", e))?;
+    let object = config.as_object().ok_or("vercel.json: expected an object")?;
+    match object.keys().find(|key| !KEYS.contains(&key.as_str())) {
+        Some(key) => Err(format!("vercel.json: unknown property '{}'", key)),
+        None => Ok(()),
+    }
+}
+
+fn lint_helm_template(path: &str, content: &str) -> Result<(), String> {
+    if content.matches("{{").count() != content.matches("}}").count() {
+        return Err(format!("{}: unbalanced template delimiters", path));
+    }
+    if content.contains('\t') {
+        return Err(format!("{}: tabs are not allowed in YAML indentation", path));
+    }
+    match ["apiVersion:", "kind:", "metadata:", "spec:"].iter().find(|key| !content.lines().any(|l| l.starts_with(*key))) {
+        Some(key) => Err(format!("{}: missing top-level '{}'", path, key.trim_end_matches(':'))),
+        None => Ok(()),
+    }
+}
+
+fn lint_systemd(path: &str, content: &str) -> Result<(), String> {
+    let mut sections = Vec::new();
+    for line in content.lines().map(str::trim).filter(|l| !l.is_empty() && !l.starts_with('#')) {
+        if line.starts_with('[') && line.ends_with(']') {
+            sections.push(line);
+        } else if !line.contains('=') {
+            return Err(format!("{}: expected Key=Value, got '{}'", path, line));
+        } else if let Some(exec) = line.strip_prefix("ExecStart=") {
+            if !exec.starts_with('/') {
+                return Err(format!("{}: ExecStart must use an absolute path", path));
+            }
+        }
+    }
+    match ["[Unit]", "[Service]", "[Install]"].iter().find(|s| !sections.contains(s)) {
+        Some(section) => Err(format!("{}: missing {} section", path, section)),
+        None if !content.contains("\nExecStart=") => Err(format!("{}: missing ExecStart", path)),
+        None => Ok(()),
+    }
+}
+
+fn lint_nginx(path: &str, content: &str) -> Result<(), String> {
+    let mut depth = 0i32;
+    for line in content.lines().map(str::trim).filter(|l| !l.is_empty() && !l.starts_with('#')) {
+        if line.ends_with('{') {
+            depth += 1;
+        } else if line == "}" {
+            depth -= 1;
+            if depth < 0 {
+                return Err(format!("{}: unexpected '}}'", path));
+            }
+        } else if !line.ends_with(';') {
+            return Err(format!("{}: directive '{}' must end with ';'", path, line));
+        }
+    }
+    if depth != 0 {
+        return Err(format!("{}: unclosed block", path));
+    }
+    Ok(())
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FASTAPI: AppRuntime = AppRuntime {
+        language: "python",
+        port: 8000,
+        manifests: &["requirements.txt"],
+        install: "pip install -r requirements.txt",
+        build: None,
+        serve: &[],
+        start: &["uvicorn", "app.main:app", "--host", "0.0.0.0", "--port", "8000"],
+        health_path: "/health",
+        publish_dir: None,
+    };
+
+    #[test]
+    fn test_deploy_files_pass_their_lint() {
+        let app = DeployApp::new("My Shop", &FASTAPI, vec!["DATABASE_URL".to_string()]);
+        assert_eq!(app.name, "my-shop");
+        for target in DEPLOY_TARGETS.iter().filter(|t| t.supports(&FASTAPI)) {
+            deploy_files(target, &app).unwrap();
+        }
+        assert!(!deploy_target(Some("vercel")).unwrap().supports(&FASTAPI));
+
+        let files = deploy_files(deploy_target(Some("systemd")).unwrap(), &app).unwrap();
+        assert!(files[0].1.contains("ExecStart=/opt/my-shop/.venv/bin/uvicorn app.main:app"));
+        let render = &deploy_files(deploy_target(Some("render")).unwrap(), &app).unwrap()[0].1;
+        assert!(render.contains("      - key: DATABASE_URL\n        sync: false\n"));
+
+        let vite = AppRuntime { language: "node", build: Some("npm run build"), publish_dir: Some("dist"), ..FASTAPI };
+        let netlify = &deploy_files(deploy_target(Some("netlify")).unwrap(), &DeployApp::new("My Shop", &vite, vec![])).unwrap()[0].1;
+        assert!(netlify.contains(&format!("  NODE_VERSION = \"{}\"\n", docker_scaffold::NODE_VERSION)));
+
+        assert!(lint("deploy/systemd/x.service", "[Unit]\n[Service]\nExecStart=npm start\n[Install]\n").is_err());
+        assert!(lint("deploy/nginx/x.conf", "server {\n    listen 80\n}\n").is_err());
+        assert!(lint("fly.toml", "app = \"x\"\n").is_err());
+        assert!(lint("vercel.json", "{\"buildCommand\": \"x\", \"outDir\": \"dist\"}").is_err());
+    }
+}
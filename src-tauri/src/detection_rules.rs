@@ -0,0 +1,458 @@
+//! Detection Rules - Declarative indicator engine
+//!
+//! Frameworks, data stores, ORMs, authentication methods and test frameworks
+//! are described as weighted indicators in JSON rule files. The bundled rule
+//! set ships with the app; users can extend or override it with their own
+//! rule files.
+
+use crate::code_analyzer::DetectedDependency;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+const BUNDLED_RULES: &str = include_str!("../rules/detection-rules.json");
+
+/// File name looked up in `<project>/.vibeforge/` and the app config dir
+pub const USER_RULES_FILE: &str = "detection-rules.json";
+
+/// Upper bound on files read per content indicator
+const MAX_CONTENT_FILES: usize = 50;
+/// Files larger than this are never read for content indicators
+const MAX_CONTENT_BYTES: u64 = 1024 * 1024;
+
+// ============================================================================
+// RULE DEFINITIONS
+// ============================================================================
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectionRuleSet {
+    #[serde(default)]
+    pub rules: Vec<DetectionRule>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectionRule {
+    pub id: String,
+    pub kind: String, // "framework", "database", "broker", "orm", "auth", "testing"
+    pub name: String,
+    #[serde(default)]
+    pub category: Option<String>,
+    #[serde(default = "default_min_confidence")]
+    pub min_confidence: f64,
+    pub indicators: Vec<Indicator>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Indicator {
+    #[serde(flatten)]
+    pub matcher: IndicatorMatcher,
+    pub weight: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum IndicatorMatcher {
+    /// Dependency with this exact name (case-insensitive), or matching `pattern`
+    Dependency {
+        #[serde(default)]
+        name: Option<String>,
+        #[serde(default)]
+        pattern: Option<String>,
+    },
+    /// Any project file whose relative path matches the glob
+    File { pattern: String },
+    /// Regex found inside files matching the `file` glob
+    Content { file: String, pattern: String },
+    /// Module imported from source code (`import x`, `require('x')`, `use x::`)
+    Import { module: String },
+}
+
+fn default_min_confidence() -> f64 {
+    0.5
+}
+
+impl DetectionRuleSet {
+    pub fn bundled() -> Self {
+        serde_json::from_str(BUNDLED_RULES).expect("bundled detection rules are valid JSON")
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read rules {}: {}", path.display(), e))?;
+        serde_json::from_str(&content)
+            .map_err(|e| format!("Invalid detection rules in {}: {}", path.display(), e))
+    }
+
+    /// Merge another rule set; rules with an existing id replace the original.
+    pub fn merge(&mut self, other: DetectionRuleSet) {
+        for rule in other.rules {
+            match self.rules.iter_mut().find(|r| r.id == rule.id) {
+                Some(existing) => *existing = rule,
+                None => self.rules.push(rule),
+            }
+        }
+    }
+
+    /// Merge a rule file if it exists. Returns whether the file was found.
+    pub fn merge_file(&mut self, path: &Path) -> Result<bool, String> {
+        if !path.is_file() {
+            return Ok(false);
+        }
+        self.merge(Self::load(path)?);
+        Ok(true)
+    }
+}
+
+// ============================================================================
+// RULE ENGINE
+// ============================================================================
+
+/// Everything the engine can match indicators against
+pub struct DetectionContext<'a> {
+    pub project_path: &'a Path,
+    /// Project-relative file paths, `/`-separated
+    pub files: &'a [String],
+    pub dependencies: &'a [DetectedDependency],
+    pub imports: &'a HashSet<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleMatch {
+    pub rule_id: String,
+    pub kind: String,
+    pub name: String,
+    pub category: Option<String>,
+    pub version: Option<String>,
+    pub confidence: f64,
+    pub indicators: Vec<String>,
+    /// Where the evidence came from: manifest, config file or `imports`
+    pub sources: Vec<String>,
+}
+
+struct IndicatorHit {
+    evidence: String,
+    version: Option<String>,
+    source: String,
+}
+
+enum CompiledMatcher {
+    Dependency { name: Option<String>, pattern: Option<Regex> },
+    File { regex: Regex },
+    Content { file: Regex, pattern: Regex },
+    Import { module: String },
+}
+
+struct CompiledRule {
+    rule: DetectionRule,
+    matchers: Vec<(CompiledMatcher, f64)>,
+}
+
+pub struct RuleEngine {
+    rules: Vec<CompiledRule>,
+}
+
+impl RuleEngine {
+    pub fn new(rule_set: DetectionRuleSet) -> Result<Self, String> {
+        let mut rules = Vec::new();
+
+        for rule in rule_set.rules {
+            let mut matchers = Vec::new();
+            for indicator in &rule.indicators {
+                let compiled = compile_matcher(&indicator.matcher)
+                    .map_err(|e| format!("Rule '{}': {}", rule.id, e))?;
+                matchers.push((compiled, indicator.weight.clamp(0.0, 1.0)));
+            }
+            rules.push(CompiledRule { rule, matchers });
+        }
+
+        Ok(Self { rules })
+    }
+
+    /// Evaluate every rule and return those reaching their minimum confidence,
+    /// in rule order.
+    pub fn evaluate(&self, context: &DetectionContext) -> Vec<RuleMatch> {
+        let mut content_cache: HashMap<String, Option<String>> = HashMap::new();
+        let mut matches = Vec::new();
+
+        for compiled in &self.rules {
+            let mut miss_probability = 1.0;
+            let mut indicators = Vec::new();
+            let mut sources: Vec<String> = Vec::new();
+            let mut version = None;
+
+            for (matcher, weight) in &compiled.matchers {
+                if let Some(hit) = match_indicator(matcher, context, &mut content_cache) {
+                    // Indicators are independent evidence: combine as a noisy-OR
+                    miss_probability *= 1.0 - weight;
+                    indicators.push(hit.evidence);
+                    if !sources.contains(&hit.source) {
+                        sources.push(hit.source);
+                    }
+                    if version.is_none() {
+                        version = hit.version;
+                    }
+                }
+            }
+
+            let confidence = 1.0 - miss_probability;
+            if !indicators.is_empty() && confidence >= compiled.rule.min_confidence {
+                matches.push(RuleMatch {
+                    rule_id: compiled.rule.id.clone(),
+                    kind: compiled.rule.kind.clone(),
+                    name: compiled.rule.name.clone(),
+                    category: compiled.rule.category.clone(),
+                    version,
+                    confidence: (confidence * 100.0).round() / 100.0,
+                    indicators,
+                    sources,
+                });
+            }
+        }
+
+        matches
+    }
+}
+
+fn compile_matcher(matcher: &IndicatorMatcher) -> Result<CompiledMatcher, String> {
+    let compile = |pattern: &str| {
+        Regex::new(pattern).map_err(|e| format!("invalid pattern '{}': {}", pattern, e))
+    };
+
+    Ok(match matcher {
+        IndicatorMatcher::Dependency { name, pattern } => {
+            if name.is_none() && pattern.is_none() {
+                return Err("dependency indicator needs a name or pattern".to_string());
+            }
+            CompiledMatcher::Dependency {
+                name: name.as_ref().map(|n| n.to_lowercase()),
+                pattern: pattern.as_deref().map(compile).transpose()?,
+            }
+        }
+        IndicatorMatcher::File { pattern } => CompiledMatcher::File {
+            regex: glob_to_regex(pattern)?,
+        },
+        IndicatorMatcher::Content { file, pattern } => CompiledMatcher::Content {
+            file: glob_to_regex(file)?,
+            pattern: compile(pattern)?,
+        },
+        IndicatorMatcher::Import { module } => CompiledMatcher::Import {
+            module: module.clone(),
+        },
+    })
+}
+
+fn match_indicator(
+    matcher: &CompiledMatcher,
+    context: &DetectionContext,
+    content_cache: &mut HashMap<String, Option<String>>,
+) -> Option<IndicatorHit> {
+    match matcher {
+        CompiledMatcher::Dependency { name, pattern } => context
+            .dependencies
+            .iter()
+            .find(|d| {
+                let dep_name = d.name.to_lowercase();
+                name.as_ref().is_some_and(|n| *n == dep_name)
+                    || pattern.as_ref().is_some_and(|p| p.is_match(&d.name))
+            })
+            .map(|d| IndicatorHit {
+                evidence: format!("{} dependency", d.name),
+                version: d.version.clone(),
+                source: d.source.clone(),
+            }),
+        CompiledMatcher::File { regex } => context
+            .files
+            .iter()
+            .find(|f| regex.is_match(f))
+            .map(|f| IndicatorHit {
+                evidence: f.clone(),
+                version: None,
+                source: f.clone(),
+            }),
+        CompiledMatcher::Content { file, pattern } => context
+            .files
+            .iter()
+            .filter(|f| file.is_match(f))
+            .take(MAX_CONTENT_FILES)
+            .find_map(|f| {
+                let content = content_cache
+                    .entry(f.clone())
+                    .or_insert_with(|| read_small_file(&context.project_path.join(f)));
+                // Report the matched text rather than the regex itself
+                let matched = pattern.find(content.as_deref()?)?.as_str().trim().to_string();
+                Some(IndicatorHit {
+                    evidence: format!("{} in {}", matched, f),
+                    version: None,
+                    source: f.clone(),
+                })
+            }),
+        CompiledMatcher::Import { module } => {
+            context.imports.contains(module).then(|| IndicatorHit {
+                evidence: format!("import {}", module),
+                version: None,
+                source: "imports".to_string(),
+            })
+        }
+    }
+}
+
+/// Read a text file, skipping anything larger than `MAX_CONTENT_BYTES`
+pub(crate) fn read_small_file(path: &Path) -> Option<String> {
+    let metadata = fs::metadata(path).ok()?;
+    if metadata.len() > MAX_CONTENT_BYTES {
+        return None;
+    }
+    fs::read_to_string(path).ok()
+}
+
+// ============================================================================
+// GLOB MATCHING
+// ============================================================================
+
+/// Translate a glob into an anchored regex over `/`-separated relative paths.
+///
+/// `*` and `?` stay within one path segment, `**/` spans any number of
+/// directories. A pattern without `/` matches the file name at any depth.
+pub fn glob_to_regex(glob: &str) -> Result<Regex, String> {
+    let mut regex = String::from("^");
+    if !glob.contains('/') {
+        regex.push_str("(?:.*/)?");
+    }
+
+    let chars: Vec<char> = glob.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '*' if chars.get(i + 1) == Some(&'*') => {
+                if chars.get(i + 2) == Some(&'/') {
+                    regex.push_str("(?:.*/)?");
+                    i += 3;
+                } else {
+                    regex.push_str(".*");
+                    i += 2;
+                }
+                continue;
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            '{' => regex.push_str("(?:"),
+            '}' => regex.push(')'),
+            ',' => regex.push('|'),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+        i += 1;
+    }
+    regex.push('$');
+
+    Regex::new(&regex).map_err(|e| format!("invalid glob '{}': {}", glob, e))
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn dep(name: &str) -> DetectedDependency {
+        DetectedDependency {
+            name: name.to_string(),
+            version: Some("1.0.0".to_string()),
+            dep_type: "runtime".to_string(),
+            source: "package.json".to_string(),
+        }
+    }
+
+    fn evaluate(files: &[&str], deps: &[&str], imports: &[&str]) -> Vec<RuleMatch> {
+        let engine = RuleEngine::new(DetectionRuleSet::bundled()).unwrap();
+        let files: Vec<String> = files.iter().map(|f| f.to_string()).collect();
+        let dependencies: Vec<DetectedDependency> = deps.iter().map(|d| dep(d)).collect();
+        let imports: HashSet<String> = imports.iter().map(|i| i.to_string()).collect();
+        let project_path = PathBuf::from("/nonexistent");
+
+        engine.evaluate(&DetectionContext {
+            project_path: &project_path,
+            files: &files,
+            dependencies: &dependencies,
+            imports: &imports,
+        })
+    }
+
+    #[test]
+    fn test_bundled_rules_compile() {
+        let rule_set = DetectionRuleSet::bundled();
+        assert!(!rule_set.rules.is_empty());
+        assert!(RuleEngine::new(rule_set).is_ok());
+    }
+
+    #[test]
+    fn test_glob_to_regex() {
+        let re = glob_to_regex("**/schema.prisma").unwrap();
+        assert!(re.is_match("schema.prisma"));
+        assert!(re.is_match("prisma/schema.prisma"));
+
+        let re = glob_to_regex("next.config.*").unwrap();
+        assert!(re.is_match("apps/web/next.config.mjs"));
+        assert!(!re.is_match("next.config"));
+
+        let re = glob_to_regex(".github/workflows/*.{yml,yaml}").unwrap();
+        assert!(re.is_match(".github/workflows/ci.yaml"));
+        assert!(!re.is_match("sub/.github/workflows/ci.yaml"));
+    }
+
+    #[test]
+    fn test_pg_substring_does_not_match_postgres() {
+        // "pg" used to match any dependency containing the substring
+        let matches = evaluate(&[], &["pg-boss-utils", "svg-loader"], &[]);
+        assert!(!matches.iter().any(|m| m.name == "postgresql"));
+    }
+
+    #[test]
+    fn test_multiple_databases_reported() {
+        let matches = evaluate(&[], &["pg", "ioredis"], &[]);
+        let databases: Vec<&str> = matches
+            .iter()
+            .filter(|m| m.kind == "database")
+            .map(|m| m.name.as_str())
+            .collect();
+        assert_eq!(databases, vec!["postgresql", "redis"]);
+    }
+
+    #[test]
+    fn test_indicators_combine_confidence() {
+        let single = evaluate(&[], &["prisma"], &[]);
+        let combined = evaluate(&["prisma/schema.prisma"], &["prisma"], &["@prisma/client"]);
+        let confidence = |matches: &[RuleMatch]| {
+            matches.iter().find(|m| m.name == "Prisma").map(|m| m.confidence).unwrap()
+        };
+        assert!(confidence(&combined) > confidence(&single));
+    }
+
+    #[test]
+    fn test_user_rules_override_bundled() {
+        let mut rule_set = DetectionRuleSet::bundled();
+        let before = rule_set.rules.len();
+        let user: DetectionRuleSet = serde_json::from_str(
+            r#"{ "rules": [
+                { "id": "framework.express", "kind": "framework", "name": "Express",
+                  "category": "backend", "indicators": [{ "type": "dependency", "name": "express", "weight": 0.5 }] },
+                { "id": "framework.hono", "kind": "framework", "name": "Hono",
+                  "category": "backend", "indicators": [{ "type": "dependency", "name": "hono", "weight": 0.9 }] }
+            ] }"#,
+        )
+        .unwrap();
+        rule_set.merge(user);
+
+        assert_eq!(rule_set.rules.len(), before + 1);
+        let express = rule_set.rules.iter().find(|r| r.id == "framework.express").unwrap();
+        assert_eq!(express.indicators[0].weight, 0.5);
+    }
+}
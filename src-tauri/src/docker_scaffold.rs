@@ -0,0 +1,426 @@
+//! Docker Scaffold - Container images and compose files for generated projects
+//!
+//! Dockerfiles are multi-stage: dependencies are installed and the app is
+//! built in one stage, then the result is copied into a slim runtime image
+//! that runs as an unprivileged user and reports its own health. Compose
+//! files are modelled as a [`Compose`] and serialized through [`Yaml`], so
+//! named volumes and networks are declared once however many services use
+//! them, and every `depends_on` points at a service that exists.
+
+use crate::database_scaffold::DatabaseSpec;
+use crate::deploy_scaffold::{AppRuntime, DeployApp};
+use crate::yaml_writer::{list, map, str, Yaml};
+
+// ============================================================================
+// IMAGES
+// ============================================================================
+
+/// Runtime releases the generated images, CI jobs and hosting configs pin.
+/// Keep these at or above the end-of-life floor `findings` checks for.
+pub const NODE_VERSION: &str = "22";
+pub const PYTHON_VERSION: &str = "3.12";
+pub const GO_VERSION: &str = "1.25";
+
+/// User the runtime stage runs as; numeric so Kubernetes can verify
+/// `runAsNonRoot` without reading the image's passwd file
+pub const APP_UID: u32 = 10001;
+
+/// Multi-stage Dockerfile for `app`. `database` selects the native client
+/// libraries and PHP extensions the runtime image needs.
+pub fn dockerfile(app: &DeployApp, database: Option<&DatabaseSpec>) -> String {
+    let runtime = app.runtime;
+    let stages = match runtime.language {
+        "python" => python_stages(runtime, database),
+        "go" => go_stages(runtime, app.start.first().map(String::as_str).unwrap_or("./server")),
+        "php" => php_stages(runtime, database),
+        _ => node_stages(runtime),
+    };
+    format!(
+        "# syntax=docker/dockerfile:1\n\n{}\nUSER {}\n\nEXPOSE {}\n\nHEALTHCHECK --interval=30s --timeout=5s --start-period=30s --retries=3 \\\n  CMD {}\n\nCMD {}\n",
+        stages,
+        APP_UID,
+        runtime.port,
+        exec_form(&health_command(runtime)),
+        exec_form(&app.start)
+    )
+}
+
+/// Request the health path from inside the container with a tool the
+/// runtime image already ships
+pub fn health_command(runtime: &AppRuntime) -> Vec<String> {
+    let url = format!("http://127.0.0.1:{}{}", runtime.port, runtime.health_path);
+    match runtime.language {
+        "python" => vec![
+            "python".to_string(),
+            "-c".to_string(),
+            format!("import urllib.request; urllib.request.urlopen('{}', timeout=4)", url),
+        ],
+        "php" => vec![
+            "php".to_string(),
+            "-r".to_string(),
+            format!("exit(@file_get_contents('{}') === false ? 1 : 0);", url),
+        ],
+        // busybox wget in the Alpine images
+        _ => vec!["wget".to_string(), "-qO".to_string(), "/dev/null".to_string(), url],
+    }
+}
+
+/// Files kept out of the build context: dependencies the image installs
+/// itself, local build output and anything holding secrets
+pub fn dockerignore(runtime: &AppRuntime) -> String {
+    let language = match runtime.language {
+        "python" => "**/__pycache__\n*.pyc\n.venv\nvenv\n.pytest_cache\nstaticfiles\n",
+        "go" => "server\nbin\n",
+        "php" => "vendor\nnode_modules\nstorage/logs/*\nbootstrap/cache/*.php\n",
+        _ => "**/node_modules\n.next\n.svelte-kit\n.vinxi\n.output\ndist\nbuild\ncoverage\n",
+    };
+    format!(
+        ".git\n.env\n.env.*\n!.env.example\n*.log\nDockerfile\n.dockerignore\ndocker-compose.yml\n\n{}",
+        language
+    )
+}
+
+fn exec_form(command: &[String]) -> String {
+    let parts: Vec<String> = command.iter().map(|part| serde_json::Value::from(part.as_str()).to_string()).collect();
+    format!("[{}]", parts.join(", "))
+}
+
+/// `COPY` lines for the install manifests, ahead of the source so the
+/// dependency layer is reused until a manifest changes
+fn copy_manifests(runtime: &AppRuntime) -> String {
+    runtime
+        .manifests
+        .iter()
+        .map(|manifest| match manifest.rsplit_once('/') {
+            Some((dir, _)) => format!("COPY {} ./{}/\n", manifest, dir),
+            None => format!("COPY {} ./\n", manifest),
+        })
+        .collect()
+}
+
+fn build_step(runtime: &AppRuntime) -> String {
+    runtime.build.map(|build| format!("RUN {}\n", build)).unwrap_or_default()
+}
+
+/// Builds with dev dependencies, then prunes them and stages what the server
+/// reads in `/dist`, so sources, caches and build tooling stay out of the
+/// runtime image
+fn node_stages(runtime: &AppRuntime) -> String {
+    let (staging, output) = if runtime.serve.is_empty() {
+        (String::new(), "/app")
+    } else {
+        (
+            format!(
+                "RUN for path in package.json node_modules {}; do \\\n    if [ -e \"$path\" ]; then mkdir -p \"/dist/$(dirname \"$path\")\" && cp -a \"$path\" \"/dist/$path\"; fi; \\\n  done\n",
+                runtime.serve.join(" ")
+            ),
+            "/dist",
+        )
+    };
+    format!(
+        r#"FROM node:{node}-alpine AS build
+WORKDIR /app
+{}RUN --mount=type=cache,target=/root/.npm {}
+COPY . .
+{}RUN npm prune --omit=dev
+{}
+FROM node:{node}-alpine
+ENV NODE_ENV=production PORT={}
+RUN adduser -D -H -u {uid} app
+WORKDIR /app
+COPY --from=build --chown={uid}:{uid} {} ./
+"#,
+        copy_manifests(runtime),
+        runtime.install,
+        build_step(runtime),
+        staging,
+        runtime.port,
+        output,
+        node = NODE_VERSION,
+        uid = APP_UID
+    )
+}
+
+fn python_stages(runtime: &AppRuntime, database: Option<&DatabaseSpec>) -> String {
+    // mysqlclient compiles against the MySQL client headers
+    let (build_packages, runtime_packages) = match database.map(|db| db.id) {
+        Some("mysql") => (
+            "RUN apt-get update && apt-get install -y --no-install-recommends build-essential pkg-config default-libmysqlclient-dev \\\n  && rm -rf /var/lib/apt/lists/*\n",
+            "RUN apt-get update && apt-get install -y --no-install-recommends libmariadb3 \\\n  && rm -rf /var/lib/apt/lists/*\n",
+        ),
+        _ => ("", ""),
+    };
+    format!(
+        r#"FROM python:{python}-slim AS build
+{}RUN python -m venv /opt/venv
+ENV PATH="/opt/venv/bin:$PATH"
+WORKDIR /app
+{}RUN --mount=type=cache,target=/root/.cache/pip {}
+COPY . .
+{}
+FROM python:{python}-slim
+ENV PATH="/opt/venv/bin:$PATH" PYTHONDONTWRITEBYTECODE=1 PYTHONUNBUFFERED=1
+{}RUN useradd --uid {uid} --no-create-home app
+WORKDIR /app
+COPY --from=build /opt/venv /opt/venv
+COPY --from=build --chown={uid}:{uid} /app ./
+"#,
+        build_packages,
+        copy_manifests(runtime),
+        runtime.install,
+        build_step(runtime),
+        runtime_packages,
+        python = PYTHON_VERSION,
+        uid = APP_UID
+    )
+}
+
+fn go_stages(runtime: &AppRuntime, binary: &str) -> String {
+    let binary = binary.trim_start_matches("./");
+    format!(
+        r#"FROM golang:{go}-alpine AS build
+WORKDIR /app
+{}RUN {}
+COPY . .
+# go.sum is completed here when the project does not ship one yet
+RUN go mod tidy && CGO_ENABLED=0 {}
+
+FROM alpine:3.20
+RUN apk add --no-cache ca-certificates && adduser -D -H -u {uid} app
+WORKDIR /app
+COPY --from=build /app/{} ./
+"#,
+        copy_manifests(runtime),
+        runtime.install,
+        runtime.build.unwrap_or("go build ./..."),
+        binary,
+        go = GO_VERSION,
+        uid = APP_UID
+    )
+}
+
+fn php_stages(runtime: &AppRuntime, database: Option<&DatabaseSpec>) -> String {
+    // Laravel connects to MySQL unless another database is selected
+    let extension = match database.map(|db| db.id) {
+        Some("sqlite") => "",
+        Some("postgresql") => {
+            "RUN apt-get update && apt-get install -y --no-install-recommends libpq-dev \\\n  && docker-php-ext-install pdo_pgsql \\\n  && rm -rf /var/lib/apt/lists/*\n"
+        }
+        _ => "RUN docker-php-ext-install pdo_mysql\n",
+    };
+    format!(
+        r#"FROM composer:2 AS build
+WORKDIR /app
+{}RUN composer install --no-dev --no-scripts --no-autoloader --prefer-dist --ignore-platform-reqs
+COPY . .
+RUN composer dump-autoload --optimize --no-dev
+
+FROM php:8.3-cli
+{}RUN useradd --uid {uid} --no-create-home app
+WORKDIR /app
+COPY --from=build --chown={uid}:{uid} /app ./
+"#,
+        copy_manifests(runtime),
+        extension,
+        uid = APP_UID
+    )
+}
+
+// ============================================================================
+// COMPOSE
+// ============================================================================
+
+/// One service in a compose file
+#[derive(Default)]
+pub struct ComposeService {
+    pub name: String,
+    pub image: Option<String>,
+    /// Build context relative to the compose file
+    pub build: Option<String>,
+    /// Load the project's `.env` when it exists; `environment` wins over it
+    pub env_file: bool,
+    pub environment: Vec<(String, String)>,
+    pub ports: Vec<String>,
+    /// `source:target[:mode]`; sources that are not paths are named volumes
+    pub volumes: Vec<String>,
+    /// Services started first; ones with a healthcheck must also be healthy
+    pub depends_on: Vec<String>,
+    /// Exec-form test command
+    pub healthcheck: Option<Vec<String>>,
+    pub networks: Vec<String>,
+}
+
+pub struct Compose {
+    pub services: Vec<ComposeService>,
+}
+
+impl Compose {
+    /// Serialize to `docker-compose.yml`, declaring every named volume and
+    /// network the services use
+    pub fn to_yaml(&self) -> Result<String, String> {
+        let mut services = map::<String>([]);
+        let mut volumes: Vec<&str> = Vec::new();
+        let mut networks: Vec<&str> = Vec::new();
+
+        for service in &self.services {
+            if services.get(&service.name).is_some() {
+                return Err(format!("docker-compose.yml: service '{}' is defined twice", service.name));
+            }
+            if service.image.is_none() && service.build.is_none() {
+                return Err(format!("docker-compose.yml: service '{}' needs an image or a build context", service.name));
+            }
+            for source in service.volumes.iter().filter_map(|v| v.split(':').next()) {
+                if !source.starts_with(['.', '/', '~']) && !volumes.contains(&source) {
+                    volumes.push(source);
+                }
+            }
+            for network in &service.networks {
+                if !networks.contains(&network.as_str()) {
+                    networks.push(network);
+                }
+            }
+            services.insert(&service.name, self.service_yaml(service)?);
+        }
+
+        let mut document = map([("services", services)]);
+        if !volumes.is_empty() {
+            document.insert("volumes", map(volumes.into_iter().map(|v| (v, map::<&str>([])))));
+        }
+        if !networks.is_empty() {
+            document.insert("networks", map(networks.into_iter().map(|n| (n, map::<&str>([])))));
+        }
+        Ok(document.to_yaml())
+    }
+
+    fn service_yaml(&self, service: &ComposeService) -> Result<Yaml, String> {
+        let mut entry = map::<&str>([]);
+        if let Some(image) = &service.image {
+            entry.insert("image", str(image));
+        }
+        if let Some(build) = &service.build {
+            entry.insert("build", str(build));
+        }
+        if service.env_file {
+            entry.insert("env_file", list([map([("path", str(".env")), ("required", Yaml::Bool(false))])]));
+        }
+        if !service.environment.is_empty() {
+            entry.insert("environment", map(service.environment.iter().map(|(k, v)| (k.as_str(), str(v)))));
+        }
+        if !service.ports.is_empty() {
+            entry.insert("ports", list(service.ports.iter().map(str)));
+        }
+        if !service.volumes.is_empty() {
+            entry.insert("volumes", list(service.volumes.iter().map(str)));
+        }
+        if !service.depends_on.is_empty() {
+            let mut depends_on = map::<&str>([]);
+            for name in &service.depends_on {
+                let dependency = self
+                    .services
+                    .iter()
+                    .find(|s| &s.name == name)
+                    .ok_or_else(|| format!("docker-compose.yml: {} depends on unknown service '{}'", service.name, name))?;
+                let condition = if dependency.healthcheck.is_some() { "service_healthy" } else { "service_started" };
+                depends_on.insert(name, map([("condition", str(condition))]));
+            }
+            entry.insert("depends_on", depends_on);
+        }
+        if let Some(test) = &service.healthcheck {
+            entry.insert(
+                "healthcheck",
+                map([
+                    ("test", list(std::iter::once(str("CMD")).chain(test.iter().map(str)))),
+                    ("interval", str("10s")),
+                    ("timeout", str("5s")),
+                    ("retries", Yaml::Int(5)),
+                    ("start_period", str("30s")),
+                ]),
+            );
+        }
+        if !service.networks.is_empty() {
+            entry.insert("networks", list(service.networks.iter().map(str)));
+        }
+        Ok(entry)
+    }
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GO: AppRuntime = AppRuntime {
+        language: "go",
+        port: 8080,
+        manifests: &["go.*"],
+        install: "go mod download",
+        build: Some("go build -o server ./cmd/api"),
+        serve: &[],
+        start: &["./server"],
+        health_path: "/health",
+        publish_dir: None,
+    };
+
+    #[test]
+    fn test_dockerfile_and_compose_model() {
+        let app = DeployApp::new("Orders", &GO, Vec::new());
+        let dockerfile = dockerfile(&app, None);
+        assert_eq!(dockerfile.matches("\nFROM ").count(), 2);
+        assert!(dockerfile.contains("COPY go.* ./\nRUN go mod download\n"));
+        assert!(dockerfile.contains("COPY --from=build /app/server ./\n\nUSER 10001\n\nEXPOSE 8080\n"));
+        assert!(dockerfile.contains("CMD [\"wget\", \"-qO\", \"/dev/null\", \"http://127.0.0.1:8080/health\"]\n"));
+        assert!(dockerfile.ends_with("CMD [\"./server\"]\n"));
+
+        // Node images keep production dependencies and the build output only
+        let next = AppRuntime {
+            language: "node",
+            port: 3000,
+            manifests: &["package*.json"],
+            install: "npm install",
+            build: Some("npm run build"),
+            serve: &[".next", "public"],
+            start: &["npm", "start"],
+            ..GO
+        };
+        let node = super::dockerfile(&DeployApp::new("web", &next, Vec::new()), None);
+        assert!(node.contains("RUN npm run build\nRUN npm prune --omit=dev\nRUN for path in package.json node_modules .next public; do"));
+        assert!(node.contains("ENV NODE_ENV=production PORT=3000\n"));
+        assert!(node.contains("COPY --from=build --chown=10001:10001 /dist ./\n"));
+
+        let mut compose = Compose {
+            services: vec![
+                ComposeService {
+                    name: "api".to_string(),
+                    build: Some(".".to_string()),
+                    volumes: vec!["uploads:/app/uploads".to_string(), "./config:/app/config:ro".to_string()],
+                    depends_on: vec!["db".to_string(), "cache".to_string()],
+                    networks: vec!["data".to_string()],
+                    ..Default::default()
+                },
+                ComposeService {
+                    name: "db".to_string(),
+                    image: Some("postgres:16-alpine".to_string()),
+                    volumes: vec!["db_data:/var/lib/postgresql/data".to_string()],
+                    healthcheck: Some(vec!["pg_isready".to_string()]),
+                    networks: vec!["data".to_string()],
+                    ..Default::default()
+                },
+                ComposeService {
+                    name: "cache".to_string(),
+                    image: Some("redis:7-alpine".to_string()),
+                    volumes: vec!["uploads:/data".to_string()],
+                    ..Default::default()
+                },
+            ],
+        };
+        let yaml = compose.to_yaml().unwrap();
+        assert!(yaml.contains("    depends_on:\n      db:\n        condition: service_healthy\n      cache:\n        condition: service_started\n"));
+        assert!(yaml.ends_with("volumes:\n  uploads: {}\n  db_data: {}\nnetworks:\n  data: {}\n"));
+
+        compose.services[0].depends_on.push("queue".to_string());
+        assert_eq!(compose.to_yaml(), Err("docker-compose.yml: api depends on unknown service 'queue'".to_string()));
+    }
+}
@@ -0,0 +1,633 @@
+//! Import Graph - Module dependency graphs for JS/TS, Python, Rust and Go
+//!
+//! The scan records the raw module specifiers of each source file (relative
+//! JS imports, Python module names, Rust `use crate::`/`mod` paths, Go import
+//! paths). This module resolves them against the project's own files and
+//! reports cycles, orphan modules and fan-in/fan-out per module.
+
+use crate::detection_rules::read_small_file;
+use crate::relative_path::{file_name, join_path, parent_dir};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::Path;
+use std::sync::OnceLock;
+
+const JS_EXTENSIONS: &[&str] = &["ts", "tsx", "js", "jsx", "svelte"];
+
+/// Raw module specifiers of one source file, as recorded during the scan
+#[derive(Debug, Clone)]
+pub struct ModuleSource {
+    pub file: String,
+    pub lang_id: String,
+    pub imports: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportGraph {
+    pub language: String, // "javascript-typescript", "python", "rust", "go"
+    pub modules: Vec<ModuleMetrics>,
+    pub edges: Vec<ImportEdge>,
+    pub cycles: Vec<Vec<String>>,
+    pub orphans: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleMetrics {
+    pub module: String, // file path; package directory for Go
+    pub fan_in: usize,
+    pub fan_out: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportEdge {
+    pub from: String,
+    pub to: String,
+}
+
+// ============================================================================
+// SPECIFIER COLLECTION
+// ============================================================================
+
+/// Module specifiers in a source file that may refer to the project's own modules
+pub fn collect_module_imports(lang_id: &str, content: &str) -> Vec<String> {
+    static JS_IMPORT: OnceLock<Regex> = OnceLock::new();
+    static PY_FROM: OnceLock<Regex> = OnceLock::new();
+    static PY_IMPORT: OnceLock<Regex> = OnceLock::new();
+    static RUST_MOD: OnceLock<Regex> = OnceLock::new();
+    static RUST_USE: OnceLock<Regex> = OnceLock::new();
+    static GO_IMPORT: OnceLock<Regex> = OnceLock::new();
+
+    let mut specifiers = Vec::new();
+    match lang_id {
+        "javascript-typescript" | "svelte" => {
+            let re = JS_IMPORT.get_or_init(|| {
+                Regex::new(r#"(?:\bfrom\s+|\bimport\s+|\brequire\(\s*|\bimport\(\s*)['"]([^'"]+)['"]"#).unwrap()
+            });
+            for cap in re.captures_iter(content) {
+                let specifier = &cap[1];
+                if specifier.starts_with('.') || specifier.starts_with("$lib") || specifier.starts_with("@/") || specifier.starts_with("~/") {
+                    specifiers.push(specifier.to_string());
+                }
+            }
+        }
+        "python" => {
+            let from = PY_FROM.get_or_init(|| Regex::new(r"(?m)^\s*from\s+(\.*[\w.]*)\s+import\s+\(?([^#\n]*)").unwrap());
+            let import = PY_IMPORT.get_or_init(|| Regex::new(r"(?m)^\s*import\s+([\w., ]+)").unwrap());
+            for cap in from.captures_iter(content) {
+                let base = &cap[1];
+                specifiers.push(base.to_string());
+                // `from pkg import module` may import submodules as well as names
+                for name in cap[2].split(',') {
+                    let name = name.split(" as ").next().unwrap_or("").trim().trim_end_matches(')');
+                    if name.is_empty() || name == "*" || name == "\\" {
+                        continue;
+                    }
+                    let separator = if base.ends_with('.') { "" } else { "." };
+                    specifiers.push(format!("{}{}{}", base, separator, name));
+                }
+            }
+            for cap in import.captures_iter(content) {
+                for module in cap[1].split(',') {
+                    let module = module.split(" as ").next().unwrap_or("").trim();
+                    if !module.is_empty() {
+                        specifiers.push(module.to_string());
+                    }
+                }
+            }
+        }
+        "rust" => {
+            let module = RUST_MOD.get_or_init(|| Regex::new(r"(?m)^\s*(?:pub(?:\([^)]*\))?\s+)?mod\s+(\w+)\s*;").unwrap());
+            let use_path = RUST_USE.get_or_init(|| {
+                Regex::new(r"(?m)^\s*(?:pub(?:\([^)]*\))?\s+)?use\s+((?:crate|super|self)::[^;]+);").unwrap()
+            });
+            for cap in module.captures_iter(content) {
+                specifiers.push(format!("mod:{}", &cap[1]));
+            }
+            // Inline test modules import from their own file through `super`
+            let code = content.split("#[cfg(test)]").next().unwrap_or(content);
+            for cap in use_path.captures_iter(code) {
+                specifiers.extend(expand_use(&cap[1]));
+            }
+        }
+        "go" => {
+            let re = GO_IMPORT.get_or_init(|| {
+                Regex::new(r#"(?m)^\s*(?:import\s+)?(?:[\w.]+\s+)?"([\w./-]+)"\s*$"#).unwrap()
+            });
+            for cap in re.captures_iter(content) {
+                specifiers.push(cap[1].to_string());
+            }
+        }
+        _ => {}
+    }
+
+    specifiers.sort();
+    specifiers.dedup();
+    specifiers
+}
+
+/// Expand one level of `use a::{b, c::D}` into `a::b`, `a::c::D`
+fn expand_use(path: &str) -> Vec<String> {
+    let path: String = path.split_whitespace().collect::<Vec<_>>().join(" ");
+    let clean = |p: &str| -> String {
+        let p = p.split(" as ").next().unwrap_or(p).trim();
+        p.trim_end_matches("::*").trim_end_matches("::self").to_string()
+    };
+    let Some((prefix, group)) = path.split_once('{') else {
+        return vec![clean(&path)];
+    };
+
+    let group = group.strip_suffix('}').unwrap_or(group);
+    let mut items = Vec::new();
+    let mut depth = 0;
+    let mut current = String::new();
+    for c in group.chars() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            ',' if depth == 0 => {
+                items.push(std::mem::take(&mut current));
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    items.push(current);
+
+    items
+        .iter()
+        .map(|item| item.split('{').next().unwrap_or(item).trim().trim_end_matches("::"))
+        .filter(|item| !item.is_empty())
+        .map(|item| {
+            if item == "self" {
+                clean(prefix.trim_end_matches("::"))
+            } else {
+                clean(&format!("{}{}", prefix, item))
+            }
+        })
+        .collect()
+}
+
+// ============================================================================
+// RESOLUTION
+// ============================================================================
+
+/// Directory that owns `src/` for path aliases like `$lib` and `@/`
+fn src_prefix(file: &str) -> String {
+    match file.find("src/") {
+        Some(index) if index == 0 || file[..index].ends_with('/') => file[..index + 4].to_string(),
+        _ => String::new(),
+    }
+}
+
+fn resolve_js(importer: &str, specifier: &str, files: &HashSet<&str>) -> Option<String> {
+    let base = if specifier.starts_with('.') {
+        join_path(parent_dir(importer), specifier)
+    } else if let Some(rest) = specifier.strip_prefix("$lib") {
+        join_path(&format!("{}lib", src_prefix(importer)), rest)
+    } else {
+        join_path(&src_prefix(importer), &specifier[2..])
+    };
+
+    let mut candidates = vec![base.clone()];
+    // TypeScript ESM imports name the emitted `.js` file
+    let stem = [".js", ".jsx", ".mjs"]
+        .iter()
+        .find_map(|ext| base.strip_suffix(ext))
+        .unwrap_or(&base);
+    for ext in JS_EXTENSIONS {
+        candidates.push(format!("{}.{}", stem, ext));
+    }
+    for ext in JS_EXTENSIONS {
+        candidates.push(format!("{}/index.{}", base, ext));
+    }
+    candidates.into_iter().find(|c| files.contains(c.as_str()))
+}
+
+/// Python modules by dotted name; every path suffix is a candidate so
+/// `backend/app/db.py` is reachable as `app.db` from inside `backend/`
+fn python_index<'a>(files: &[&'a str]) -> HashMap<String, Vec<(String, &'a str)>> {
+    let mut index: HashMap<String, Vec<(String, &str)>> = HashMap::new();
+    for file in files {
+        let module = file.trim_end_matches(".py").trim_end_matches("/__init__");
+        let parts: Vec<&str> = module.split('/').collect();
+        for start in 0..parts.len() {
+            let root = parts[..start].join("/");
+            index.entry(parts[start..].join(".")).or_default().push((root, file));
+        }
+    }
+    index
+}
+
+fn resolve_python(
+    importer: &str,
+    specifier: &str,
+    files: &HashSet<&str>,
+    index: &HashMap<String, Vec<(String, &str)>>,
+) -> Option<String> {
+    let dots = specifier.chars().take_while(|c| *c == '.').count();
+    if dots > 0 {
+        let mut dir = parent_dir(importer).to_string();
+        for _ in 1..dots {
+            dir = parent_dir(&dir).to_string();
+        }
+        let module = join_path(&dir, &specifier[dots..].replace('.', "/"));
+        return [format!("{}.py", module), format!("{}/__init__.py", module)]
+            .into_iter()
+            .find(|c| files.contains(c.as_str()));
+    }
+
+    // The module's source root must contain the importer
+    index.get(specifier).and_then(|candidates| {
+        candidates
+            .iter()
+            .filter(|(root, _)| root.is_empty() || importer.starts_with(&format!("{}/", root)))
+            .max_by_key(|(root, _)| root.len())
+            .map(|(_, file)| file.to_string())
+    })
+}
+
+/// Rust crate root directory (`.../src`) and module path of a file
+fn rust_module(file: &str) -> Option<(String, Vec<String>)> {
+    let index = file.rfind("src/")?;
+    if index > 0 && !file[..index].ends_with('/') {
+        return None;
+    }
+    let root = file[..index + 3].to_string();
+    let mut path: Vec<String> = file[index + 4..].trim_end_matches(".rs").split('/').map(|s| s.to_string()).collect();
+    if path.first().is_some_and(|p| p == "bin") {
+        return None;
+    }
+    if path.last().is_some_and(|p| p == "mod") || matches!(path.as_slice(), [p] if p == "main" || p == "lib") {
+        path.pop();
+    }
+    Some((root, path))
+}
+
+fn resolve_rust(
+    importer: &str,
+    specifier: &str,
+    modules: &HashMap<(String, Vec<String>), String>,
+) -> Option<String> {
+    let (root, importer_path) = rust_module(importer)?;
+    // Child modules of `a.rs` live in `a/`, the same as for `a/mod.rs`
+    let (mut path, rest) = if let Some(child) = specifier.strip_prefix("mod:") {
+        (importer_path.clone(), vec![child])
+    } else {
+        let mut segments = specifier.split("::");
+        let mut path = match segments.next()? {
+            "crate" => Vec::new(),
+            "self" => importer_path.clone(),
+            "super" => importer_path[..importer_path.len().saturating_sub(1)].to_vec(),
+            _ => return None,
+        };
+        let mut rest: Vec<&str> = segments.collect();
+        while rest.first() == Some(&"super") {
+            path.pop();
+            rest.remove(0);
+        }
+        (path, rest)
+    };
+    path.extend(rest.iter().map(|s| s.to_string()));
+
+    // Longest module prefix; the remainder names items inside that module
+    while !path.is_empty() {
+        if let Some(file) = modules.get(&(root.clone(), path.clone())) {
+            return Some(file.clone());
+        }
+        path.pop();
+    }
+    modules.get(&(root, path)).cloned()
+}
+
+fn go_modules(project_path: &Path, project_files: &[String]) -> Vec<(String, String)> {
+    project_files
+        .iter()
+        .filter(|f| file_name(f) == "go.mod")
+        .filter_map(|f| {
+            let content = read_small_file(&project_path.join(f))?;
+            let module = content.lines().find_map(|l| l.strip_prefix("module "))?;
+            Some((module.trim().to_string(), parent_dir(f).to_string()))
+        })
+        .collect()
+}
+
+fn go_package(file: &str) -> String {
+    match parent_dir(file) {
+        "" => ".".to_string(),
+        dir => dir.to_string(),
+    }
+}
+
+fn resolve_go(specifier: &str, modules: &[(String, String)], packages: &HashSet<String>) -> Option<String> {
+    modules.iter().find_map(|(module, dir)| {
+        let rest = specifier.strip_prefix(module.as_str())?;
+        if !rest.is_empty() && !rest.starts_with('/') {
+            return None;
+        }
+        let package = join_path(dir, rest);
+        let package = if package.is_empty() { ".".to_string() } else { package };
+        packages.contains(&package).then_some(package)
+    })
+}
+
+// ============================================================================
+// GRAPHS
+// ============================================================================
+
+/// Modules that are expected to have no importers
+fn is_entry_module(language: &str, module: &str) -> bool {
+    let name = file_name(module);
+    let in_dir = |dir: &str| module.starts_with(&format!("{}/", dir)) || module.contains(&format!("/{}/", dir));
+    match language {
+        "javascript-typescript" => {
+            let stem = name.split('.').next().unwrap_or(name);
+            name.starts_with('+')
+                || name.ends_with(".d.ts")
+                || name.contains(".config.")
+                || name.contains(".test.")
+                || name.contains(".spec.")
+                || matches!(stem, "index" | "main" | "app" | "server" | "page" | "layout" | "route" | "middleware" | "hooks")
+                || ["routes", "pages", "app", "tests", "__tests__", "scripts"].iter().any(|d| in_dir(d))
+        }
+        "python" => {
+            matches!(name, "__init__.py" | "__main__.py" | "main.py" | "app.py" | "manage.py" | "wsgi.py" | "asgi.py" | "conftest.py" | "setup.py")
+                || name.starts_with("test_")
+                || name.ends_with("_test.py")
+                || ["tests", "migrations", "scripts"].iter().any(|d| in_dir(d))
+        }
+        "rust" => {
+            matches!(name, "main.rs" | "lib.rs" | "build.rs")
+                || ["bin", "tests", "benches", "examples"].iter().any(|d| in_dir(d))
+        }
+        "go" => module == "." || in_dir("cmd") || module.starts_with("cmd"),
+        _ => false,
+    }
+}
+
+/// Strongly connected components with more than one module (Kosaraju, iterative)
+fn find_cycles(nodes: &[String], edges: &BTreeSet<(usize, usize)>) -> Vec<Vec<String>> {
+    let mut forward = vec![Vec::new(); nodes.len()];
+    let mut reverse = vec![Vec::new(); nodes.len()];
+    for &(from, to) in edges {
+        forward[from].push(to);
+        reverse[to].push(from);
+    }
+
+    // First pass: order nodes by finish time
+    let mut visited = vec![false; nodes.len()];
+    let mut order = Vec::with_capacity(nodes.len());
+    for start in 0..nodes.len() {
+        if visited[start] {
+            continue;
+        }
+        visited[start] = true;
+        let mut stack = vec![(start, 0)];
+        while let Some((node, next)) = stack.pop() {
+            if let Some(&child) = forward[node].get(next) {
+                stack.push((node, next + 1));
+                if !visited[child] {
+                    visited[child] = true;
+                    stack.push((child, 0));
+                }
+            } else {
+                order.push(node);
+            }
+        }
+    }
+
+    // Second pass: components on the transposed graph
+    let mut component = vec![usize::MAX; nodes.len()];
+    let mut cycles = Vec::new();
+    for &start in order.iter().rev() {
+        if component[start] != usize::MAX {
+            continue;
+        }
+        component[start] = start;
+        let mut members = vec![start];
+        let mut stack = vec![start];
+        while let Some(node) = stack.pop() {
+            for &parent in &reverse[node] {
+                if component[parent] == usize::MAX {
+                    component[parent] = start;
+                    members.push(parent);
+                    stack.push(parent);
+                }
+            }
+        }
+        if members.len() > 1 {
+            let mut names: Vec<String> = members.iter().map(|&m| nodes[m].clone()).collect();
+            names.sort();
+            cycles.push(names);
+        }
+    }
+    cycles.sort();
+    cycles
+}
+
+fn build_graph(language: &str, nodes: BTreeSet<String>, edges: BTreeSet<(String, String)>, declared: Option<&HashSet<String>>) -> ImportGraph {
+    let nodes: Vec<String> = nodes.into_iter().collect();
+    let position: HashMap<&str, usize> = nodes.iter().enumerate().map(|(i, n)| (n.as_str(), i)).collect();
+    let indexed: BTreeSet<(usize, usize)> = edges
+        .iter()
+        .filter(|(from, to)| from != to)
+        .filter_map(|(from, to)| Some((*position.get(from.as_str())?, *position.get(to.as_str())?)))
+        .collect();
+
+    let mut fan_in = vec![0; nodes.len()];
+    let mut fan_out = vec![0; nodes.len()];
+    for &(from, to) in &indexed {
+        fan_out[from] += 1;
+        fan_in[to] += 1;
+    }
+
+    // Rust files are orphaned when no `mod` declares them; elsewhere when nothing imports them
+    let orphans = if nodes.len() > 1 {
+        nodes
+            .iter()
+            .enumerate()
+            .filter(|(i, module)| match declared {
+                Some(declared) => !declared.contains(*module),
+                None => fan_in[*i] == 0,
+            })
+            .map(|(_, module)| module.clone())
+            .filter(|module| !is_entry_module(language, module))
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    ImportGraph {
+        language: language.to_string(),
+        modules: nodes
+            .iter()
+            .enumerate()
+            .map(|(i, module)| ModuleMetrics {
+                module: module.clone(),
+                fan_in: fan_in[i],
+                fan_out: fan_out[i],
+            })
+            .collect(),
+        edges: indexed
+            .iter()
+            .map(|&(from, to)| ImportEdge {
+                from: nodes[from].clone(),
+                to: nodes[to].clone(),
+            })
+            .collect(),
+        cycles: find_cycles(&nodes, &indexed),
+        orphans,
+    }
+}
+
+/// Resolve recorded specifiers into one graph per language that has source files
+pub fn build_import_graphs(project_path: &Path, project_files: &[String], sources: &[ModuleSource]) -> Vec<ImportGraph> {
+    let mut by_language: BTreeMap<&str, Vec<&ModuleSource>> = BTreeMap::new();
+    for source in sources {
+        let language = match source.lang_id.as_str() {
+            "javascript-typescript" | "svelte" => "javascript-typescript",
+            "python" | "rust" | "go" => source.lang_id.as_str(),
+            _ => continue,
+        };
+        by_language.entry(language).or_default().push(source);
+    }
+
+    let mut graphs = Vec::new();
+    for (language, sources) in by_language {
+        let files: Vec<&str> = sources.iter().map(|s| s.file.as_str()).collect();
+        let file_set: HashSet<&str> = files.iter().copied().collect();
+        let mut nodes: BTreeSet<String> = files.iter().map(|f| f.to_string()).collect();
+        let mut edges = BTreeSet::new();
+        let mut declared = None;
+
+        match language {
+            "javascript-typescript" => {
+                for source in &sources {
+                    for specifier in &source.imports {
+                        if let Some(target) = resolve_js(&source.file, specifier, &file_set) {
+                            edges.insert((source.file.clone(), target));
+                        }
+                    }
+                }
+            }
+            "python" => {
+                let index = python_index(&files);
+                for source in &sources {
+                    for specifier in &source.imports {
+                        if let Some(target) = resolve_python(&source.file, specifier, &file_set, &index) {
+                            edges.insert((source.file.clone(), target));
+                        }
+                    }
+                }
+            }
+            "rust" => {
+                let mut modules = HashMap::new();
+                for file in &files {
+                    if let Some(key) = rust_module(file) {
+                        modules.entry(key).or_insert_with(|| file.to_string());
+                    }
+                }
+                let mut declared_files = HashSet::new();
+                for source in &sources {
+                    for specifier in &source.imports {
+                        let Some(target) = resolve_rust(&source.file, specifier, &modules) else {
+                            continue;
+                        };
+                        if specifier.starts_with("mod:") {
+                            declared_files.insert(target);
+                        } else {
+                            edges.insert((source.file.clone(), target));
+                        }
+                    }
+                }
+                declared = Some(declared_files);
+            }
+            "go" => {
+                let manifests = go_modules(project_path, project_files);
+                nodes = files.iter().map(|f| go_package(f)).collect();
+                let packages: HashSet<String> = nodes.iter().cloned().collect();
+                for source in &sources {
+                    for specifier in &source.imports {
+                        if let Some(target) = resolve_go(specifier, &manifests, &packages) {
+                            edges.insert((go_package(&source.file), target));
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        graphs.push(build_graph(language, nodes, edges, declared.as_ref()));
+    }
+    graphs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(file: &str, lang_id: &str, content: &str) -> ModuleSource {
+        ModuleSource {
+            file: file.to_string(),
+            lang_id: lang_id.to_string(),
+            imports: collect_module_imports(lang_id, content),
+        }
+    }
+
+    #[test]
+    fn test_js_cycle_and_orphan() {
+        let sources = vec![
+            source("src/index.ts", "javascript-typescript", "import { a } from './a';\n"),
+            source("src/a.ts", "javascript-typescript", "import { b } from './lib/b.js';\nimport x from 'lodash';\n"),
+            source("src/lib/b.ts", "javascript-typescript", "import { a } from '$lib/../a';\n"),
+            source("src/unused.ts", "javascript-typescript", "export const x = 1;\n"),
+        ];
+        let graphs = build_import_graphs(Path::new("/nonexistent"), &[], &sources);
+        let graph = &graphs[0];
+
+        assert_eq!(graph.cycles, vec![vec!["src/a.ts".to_string(), "src/lib/b.ts".to_string()]]);
+        assert_eq!(graph.orphans, vec!["src/unused.ts".to_string()]);
+        let a = graph.modules.iter().find(|m| m.module == "src/a.ts").unwrap();
+        assert_eq!((a.fan_in, a.fan_out), (2, 1));
+    }
+
+    #[test]
+    fn test_python_relative_and_absolute_imports() {
+        let sources = vec![
+            source("backend/app/main.py", "python", "from app.db import session\nfrom . import models\nimport os\n"),
+            source("backend/app/db.py", "python", "from .models import User\n"),
+            source("backend/app/models.py", "python", "import sqlalchemy\n"),
+            source("backend/app/__init__.py", "python", ""),
+        ];
+        let graph = &build_import_graphs(Path::new("/nonexistent"), &[], &sources)[0];
+        let edges: Vec<(&str, &str)> = graph.edges.iter().map(|e| (e.from.as_str(), e.to.as_str())).collect();
+
+        assert!(edges.contains(&("backend/app/main.py", "backend/app/db.py")));
+        assert!(edges.contains(&("backend/app/main.py", "backend/app/models.py")));
+        assert!(edges.contains(&("backend/app/db.py", "backend/app/models.py")));
+        assert!(graph.cycles.is_empty());
+        assert!(graph.orphans.is_empty());
+    }
+
+    #[test]
+    fn test_rust_modules_and_undeclared_files() {
+        assert_eq!(
+            expand_use("crate::infra_detector::{self, indentation, yaml_children}"),
+            vec!["crate::infra_detector", "crate::infra_detector::indentation", "crate::infra_detector::yaml_children"]
+        );
+
+        let sources = vec![
+            source("src/main.rs", "rust", "mod analyzer;\nmod rules;\nuse analyzer::run;\n"),
+            source("src/analyzer.rs", "rust", "use crate::rules::{Rule, load};\n"),
+            source("src/rules/mod.rs", "rust", "use super::analyzer::Context;\n"),
+            source("src/stale.rs", "rust", "pub fn old() {}\n"),
+        ];
+        let graph = &build_import_graphs(Path::new("/nonexistent"), &[], &sources)[0];
+
+        assert_eq!(graph.cycles, vec![vec!["src/analyzer.rs".to_string(), "src/rules/mod.rs".to_string()]]);
+        assert_eq!(graph.orphans, vec!["src/stale.rs".to_string()]);
+    }
+}
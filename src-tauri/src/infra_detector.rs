@@ -0,0 +1,615 @@
+//! Infrastructure Detector - CI/CD pipelines and infrastructure-as-code
+//!
+//! Finds CI workflow files for the common providers and classifies their jobs
+//! (lint/test/build/deploy), detects IaC tooling and Dockerfile base images,
+//! and turns platform config files into deployment platform suggestions.
+
+use crate::detection_rules::read_small_file;
+use crate::relative_path::file_name;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Upper bound on YAML files inspected for Kubernetes manifests
+const MAX_MANIFEST_FILES: usize = 200;
+
+// ============================================================================
+// TYPES
+// ============================================================================
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CiProfile {
+    pub providers: Vec<String>,
+    pub workflows: Vec<CiWorkflow>,
+    pub stages: Vec<String>, // Union of "lint", "test", "build", "deploy" across workflows
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CiWorkflow {
+    pub provider: String, // "github-actions", "gitlab-ci", "jenkins", "circleci", ...
+    pub file: String,
+    pub jobs: Vec<CiJob>,
+    pub stages: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CiJob {
+    pub name: String,
+    pub stages: Vec<String>, // "lint", "test", "build", "deploy"
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InfrastructureProfile {
+    pub iac: Vec<DetectedIac>,
+    pub docker_images: Vec<DockerBaseImage>,
+    pub platform_files: Vec<String>,
+    pub deployment_platforms: Vec<DeploymentPlatformSuggestion>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DetectedIac {
+    pub tool: String, // "terraform", "pulumi", "helm", "kubernetes"
+    pub files: Vec<String>,
+    pub details: Vec<String>, // Terraform providers, chart names, manifest kinds, Pulumi runtime
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DockerBaseImage {
+    pub file: String,
+    pub image: String,
+    pub tag: Option<String>,
+    pub stage: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeploymentPlatformSuggestion {
+    pub platform: String, // Same ids as ProjectConfig.deployment_platform: "vercel", "netlify", "docker", ...
+    pub confidence: f64,
+    pub indicators: Vec<String>,
+}
+
+// ============================================================================
+// CI/CD DETECTION
+// ============================================================================
+
+/// CI provider for a workflow file path, if it is one
+fn ci_provider(file: &str) -> Option<&'static str> {
+    let is_yaml = file.ends_with(".yml") || file.ends_with(".yaml");
+    let name = file_name(file);
+
+    if is_yaml && file.starts_with(".github/workflows/") {
+        Some("github-actions")
+    } else if is_yaml && file.starts_with(".gitea/workflows/") {
+        Some("gitea-actions")
+    } else if is_yaml && file.starts_with(".forgejo/workflows/") {
+        Some("forgejo-actions")
+    } else if file == ".gitlab-ci.yml" || (is_yaml && file.starts_with(".gitlab/ci/")) {
+        Some("gitlab-ci")
+    } else if name == "Jenkinsfile" {
+        Some("jenkins")
+    } else if file == ".circleci/config.yml" {
+        Some("circleci")
+    } else if file == "azure-pipelines.yml" || (is_yaml && file.starts_with(".azure-pipelines/")) {
+        Some("azure-pipelines")
+    } else if file == "bitbucket-pipelines.yml" {
+        Some("bitbucket-pipelines")
+    } else if file == ".travis.yml" {
+        Some("travis-ci")
+    } else if file == ".drone.yml" {
+        Some("drone")
+    } else if file == ".woodpecker.yml" || (is_yaml && file.starts_with(".woodpecker/")) {
+        Some("woodpecker")
+    } else {
+        None
+    }
+}
+
+/// Classify a job by its name and commands into lint/test/build/deploy
+fn classify_stages(text: &str) -> Vec<String> {
+    const STAGE_KEYWORDS: &[(&str, &[&str])] = &[
+        ("lint", &["lint", "clippy", "fmt", "format", "prettier", "ruff", "flake8", "black", "golangci", "typecheck", "svelte-check"]),
+        ("test", &["test", "pytest", "jest", "vitest", "playwright", "cypress", "phpunit", "coverage"]),
+        ("build", &["build", "compile", "bundle", "package", "docker/build-push-action"]),
+        ("deploy", &["deploy", "publish", "release", "flyctl", "vercel", "netlify", "kubectl", "helm upgrade", "terraform apply"]),
+    ];
+
+    let text = text.to_lowercase();
+    // Keywords must start a word so `ubuntu-latest` does not read as a test job
+    let has_word = |keyword: &str| {
+        text.match_indices(keyword)
+            .any(|(i, _)| !text[..i].ends_with(|c: char| c.is_ascii_alphanumeric()))
+    };
+    STAGE_KEYWORDS
+        .iter()
+        .filter(|(_, keywords)| keywords.iter().any(|k| has_word(k)))
+        .map(|(stage, _)| stage.to_string())
+        .collect()
+}
+
+pub(crate) fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+/// YAML mapping key on this line, if the line is `key:` or `key: value`
+pub(crate) fn yaml_key(line: &str) -> Option<&str> {
+    let trimmed = line.trim();
+    if trimmed.starts_with('#') || trimmed.starts_with('-') {
+        return None;
+    }
+    let (key, _) = trimmed.split_once(':')?;
+    let key = key.trim_matches(|c| c == '"' || c == '\'');
+    (!key.is_empty() && !key.contains(' ')).then_some(key)
+}
+
+/// Child mappings of a top-level key (`jobs:` in GitHub Actions/CircleCI), with their bodies
+pub(crate) fn yaml_children<'a>(content: &'a str, parent: &str) -> Vec<(&'a str, String)> {
+    let mut children: Vec<(&str, String)> = Vec::new();
+    let mut in_parent = false;
+    let mut child_indent = None;
+
+    for line in content.lines() {
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        let indent = indentation(line);
+        if indent == 0 {
+            in_parent = yaml_key(line) == Some(parent);
+            continue;
+        }
+        if !in_parent {
+            continue;
+        }
+        let child_indent = *child_indent.get_or_insert(indent);
+        if indent == child_indent {
+            if let Some(key) = yaml_key(line) {
+                children.push((key, String::new()));
+            }
+        } else if let Some((_, body)) = children.last_mut() {
+            body.push_str(line);
+            body.push('\n');
+        }
+    }
+
+    children
+}
+
+/// Top-level GitLab CI keys that are jobs (not keywords or hidden templates)
+fn gitlab_jobs(content: &str) -> Vec<(&str, String)> {
+    const KEYWORDS: &[&str] = &[
+        "stages", "variables", "default", "include", "image", "services", "before_script",
+        "after_script", "workflow", "cache",
+    ];
+
+    let mut jobs: Vec<(&str, String)> = Vec::new();
+    let mut current: Option<usize> = None;
+    for line in content.lines() {
+        if indentation(line) == 0 && !line.trim().is_empty() {
+            current = None;
+            if let Some(key) = yaml_key(line) {
+                if !key.starts_with('.') && !KEYWORDS.contains(&key) {
+                    jobs.push((key, String::new()));
+                    current = Some(jobs.len() - 1);
+                }
+            }
+        } else if let Some(index) = current {
+            jobs[index].1.push_str(line);
+            jobs[index].1.push('\n');
+        }
+    }
+    jobs
+}
+
+fn parse_workflow(provider: &str, file: &str, content: &str) -> CiWorkflow {
+    static JENKINS_STAGE: OnceLock<Regex> = OnceLock::new();
+
+    let raw_jobs: Vec<(String, String)> = match provider {
+        "github-actions" | "gitea-actions" | "forgejo-actions" | "circleci" => yaml_children(content, "jobs")
+            .into_iter()
+            .map(|(name, body)| (name.to_string(), body))
+            .collect(),
+        "gitlab-ci" => gitlab_jobs(content)
+            .into_iter()
+            .map(|(name, body)| (name.to_string(), body))
+            .collect(),
+        "jenkins" => JENKINS_STAGE
+            .get_or_init(|| Regex::new(r#"stage\s*\(\s*['"]([^'"]+)['"]"#).unwrap())
+            .captures_iter(content)
+            .map(|c| (c[1].to_string(), String::new()))
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    let jobs: Vec<CiJob> = raw_jobs
+        .into_iter()
+        .map(|(name, body)| {
+            // `needs: [lint]` names other jobs, not what this one does
+            let body: Vec<&str> = body
+                .lines()
+                .filter(|l| !l.trim_start().starts_with("needs:") && !l.trim_start().starts_with("dependencies:"))
+                .collect();
+            CiJob {
+                stages: classify_stages(&format!("{}\n{}", name, body.join("\n"))),
+                name,
+            }
+        })
+        .collect();
+
+    // Providers without job parsing are classified from the whole file
+    let stages = if jobs.is_empty() {
+        classify_stages(content)
+    } else {
+        let union: BTreeSet<&String> = jobs.iter().flat_map(|j| &j.stages).collect();
+        ordered_stages(union.into_iter())
+    };
+
+    CiWorkflow {
+        provider: provider.to_string(),
+        file: file.to_string(),
+        jobs,
+        stages,
+    }
+}
+
+/// Keep stages in pipeline order rather than alphabetical
+fn ordered_stages<'a>(stages: impl Iterator<Item = &'a String>) -> Vec<String> {
+    let stages: Vec<&String> = stages.collect();
+    ["lint", "test", "build", "deploy"]
+        .iter()
+        .filter(|s| stages.iter().any(|found| found == s))
+        .map(|s| s.to_string())
+        .collect()
+}
+
+pub fn detect_ci(project_path: &Path, project_files: &[String]) -> CiProfile {
+    let mut workflows = Vec::new();
+
+    for file in project_files {
+        let Some(provider) = ci_provider(file) else {
+            continue;
+        };
+        let content = read_small_file(&project_path.join(file)).unwrap_or_default();
+        workflows.push(parse_workflow(provider, file, &content));
+    }
+
+    let providers: BTreeSet<String> = workflows.iter().map(|w| w.provider.clone()).collect();
+    let stages = ordered_stages(workflows.iter().flat_map(|w| &w.stages));
+
+    CiProfile {
+        providers: providers.into_iter().collect(),
+        workflows,
+        stages,
+    }
+}
+
+// ============================================================================
+// INFRASTRUCTURE DETECTION
+// ============================================================================
+
+pub fn is_dockerfile(file: &str) -> bool {
+    let name = file_name(file);
+    name == "Dockerfile" || name.starts_with("Dockerfile.") || name.ends_with(".Dockerfile") || name == "Containerfile"
+}
+
+pub fn is_compose_file(file: &str) -> bool {
+    let name = file_name(file);
+    (name.starts_with("docker-compose") || name.starts_with("compose"))
+        && (name.ends_with(".yml") || name.ends_with(".yaml"))
+}
+
+/// `FROM image[:tag] [AS stage]` lines, skipping references to earlier build stages
+fn parse_dockerfile(file: &str, content: &str) -> Vec<DockerBaseImage> {
+    let mut images = Vec::new();
+    let mut stage_names: Vec<String> = Vec::new();
+
+    for line in content.lines() {
+        let mut parts = line.split_whitespace();
+        if !parts.next().is_some_and(|p| p.eq_ignore_ascii_case("FROM")) {
+            continue;
+        }
+        let Some(reference) = parts.find(|p| !p.starts_with("--")) else {
+            continue;
+        };
+        let stage = match (parts.next(), parts.next()) {
+            (Some(as_kw), Some(name)) if as_kw.eq_ignore_ascii_case("AS") => Some(name.to_string()),
+            _ => None,
+        };
+
+        if !stage_names.iter().any(|s| s == reference) {
+            // Split the tag off without mistaking a registry port for one
+            let (image, tag) = match reference.rsplit_once(':') {
+                Some((image, tag)) if !tag.contains('/') => (image.to_string(), Some(tag.to_string())),
+                _ => (reference.to_string(), None),
+            };
+            images.push(DockerBaseImage {
+                file: file.to_string(),
+                image,
+                tag,
+                stage: stage.clone(),
+            });
+        }
+        stage_names.extend(stage);
+    }
+
+    images
+}
+
+fn push_iac(iac: &mut Vec<DetectedIac>, tool: &str, file: &str, details: impl IntoIterator<Item = String>) {
+    let index = match iac.iter().position(|i| i.tool == tool) {
+        Some(index) => index,
+        None => {
+            iac.push(DetectedIac {
+                tool: tool.to_string(),
+                files: Vec::new(),
+                details: Vec::new(),
+            });
+            iac.len() - 1
+        }
+    };
+    let entry = &mut iac[index];
+    entry.files.push(file.to_string());
+    for detail in details {
+        if !entry.details.contains(&detail) {
+            entry.details.push(detail);
+        }
+    }
+}
+
+fn platform_for_file(file: &str) -> Option<&'static str> {
+    match file_name(file) {
+        "vercel.json" => Some("vercel"),
+        "netlify.toml" => Some("netlify"),
+        "fly.toml" => Some("fly"),
+        "render.yaml" => Some("render"),
+        "railway.json" | "railway.toml" => Some("railway"),
+        "Procfile" | "app.json" if !file.contains('/') => Some("heroku"),
+        _ => None,
+    }
+}
+
+pub fn detect_infrastructure(project_path: &Path, project_files: &[String], ci: &CiProfile) -> InfrastructureProfile {
+    static TF_PROVIDER: OnceLock<Regex> = OnceLock::new();
+    static K8S_KIND: OnceLock<Regex> = OnceLock::new();
+    let tf_provider = TF_PROVIDER.get_or_init(|| Regex::new(r#"(?m)^\s*provider\s+"([\w-]+)""#).unwrap());
+    let k8s_kind = K8S_KIND.get_or_init(|| Regex::new(r"(?m)^kind:\s*(\w+)").unwrap());
+
+    let mut iac = Vec::new();
+    let mut docker_images = Vec::new();
+    let mut platform_files = Vec::new();
+    let mut manifests_read = 0;
+
+    // `templates/` of each Helm chart; chart templates are not plain manifests
+    let chart_templates: Vec<String> = project_files
+        .iter()
+        .filter_map(|f| f.strip_suffix("Chart.yaml"))
+        .filter(|dir| dir.is_empty() || dir.ends_with('/'))
+        .map(|dir| format!("{}templates/", dir))
+        .collect();
+
+    for file in project_files {
+        let name = file_name(file);
+        let full_path = project_path.join(file);
+
+        if name.ends_with(".tf") {
+            let providers = read_small_file(&full_path)
+                .map(|c| tf_provider.captures_iter(&c).map(|m| m[1].to_string()).collect::<Vec<_>>())
+                .unwrap_or_default();
+            push_iac(&mut iac, "terraform", file, providers);
+        } else if name == "Pulumi.yaml" || name == "Pulumi.yml" {
+            let runtime = read_small_file(&full_path).and_then(|c| {
+                c.lines()
+                    .find_map(|l| l.strip_prefix("runtime:"))
+                    .map(|r| r.trim().to_string())
+            });
+            push_iac(&mut iac, "pulumi", file, runtime);
+        } else if name == "Chart.yaml" {
+            let chart = read_small_file(&full_path).and_then(|c| {
+                c.lines()
+                    .find_map(|l| l.strip_prefix("name:"))
+                    .map(|n| n.trim().to_string())
+            });
+            push_iac(&mut iac, "helm", file, chart);
+        } else if is_dockerfile(file) {
+            if let Some(content) = read_small_file(&full_path) {
+                docker_images.extend(parse_dockerfile(file, &content));
+            }
+        } else if platform_for_file(file).is_some() {
+            platform_files.push(file.clone());
+        } else if (name.ends_with(".yaml") || name.ends_with(".yml"))
+            && ci_provider(file).is_none()
+            && !is_compose_file(file)
+            && !chart_templates.iter().any(|t| file.starts_with(t.as_str()))
+            && manifests_read < MAX_MANIFEST_FILES
+        {
+            manifests_read += 1;
+            let Some(content) = read_small_file(&full_path) else {
+                continue;
+            };
+            if content.lines().any(|l| l.starts_with("apiVersion:")) {
+                let kinds: Vec<String> = k8s_kind.captures_iter(&content).map(|c| c[1].to_string()).collect();
+                if !kinds.is_empty() {
+                    push_iac(&mut iac, "kubernetes", file, kinds);
+                }
+            }
+        }
+    }
+
+    let has_docker = !docker_images.is_empty() || project_files.iter().any(|f| is_compose_file(f));
+    let deployment_platforms = suggest_platforms(&iac, &platform_files, has_docker, ci);
+
+    InfrastructureProfile {
+        iac,
+        docker_images,
+        platform_files,
+        deployment_platforms,
+    }
+}
+
+// ============================================================================
+// DEPLOYMENT PLATFORM SUGGESTIONS
+// ============================================================================
+
+fn suggest_platforms(
+    iac: &[DetectedIac],
+    platform_files: &[String],
+    has_docker: bool,
+    ci: &CiProfile,
+) -> Vec<DeploymentPlatformSuggestion> {
+    let mut evidence: Vec<(&str, f64, String)> = Vec::new();
+
+    for file in platform_files {
+        if let Some(platform) = platform_for_file(file) {
+            let weight = if platform == "heroku" { 0.6 } else { 0.9 };
+            evidence.push((platform, weight, file.clone()));
+        }
+    }
+
+    for entry in iac {
+        match entry.tool.as_str() {
+            "helm" | "kubernetes" => evidence.push(("kubernetes", 0.8, format!("{} manifests", entry.tool))),
+            "terraform" => {
+                for (provider, platform) in [("aws", "aws"), ("google", "gcp"), ("azurerm", "azure")] {
+                    if entry.details.iter().any(|d| d == provider) {
+                        evidence.push((platform, 0.6, format!("terraform provider {}", provider)));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if has_docker {
+        evidence.push(("docker", 0.6, "Dockerfile or compose file".to_string()));
+    }
+
+    // Deploy jobs that call a platform CLI corroborate the config files
+    for workflow in &ci.workflows {
+        for job in workflow.jobs.iter().filter(|j| j.stages.iter().any(|s| s == "deploy")) {
+            let name = job.name.to_lowercase();
+            for platform in ["vercel", "netlify", "fly", "render", "railway", "heroku", "kubernetes"] {
+                if name.contains(platform) {
+                    evidence.push((platform, 0.3, format!("{} job in {}", job.name, workflow.file)));
+                }
+            }
+        }
+    }
+
+    let mut suggestions: Vec<DeploymentPlatformSuggestion> = Vec::new();
+    for (platform, weight, indicator) in evidence {
+        match suggestions.iter_mut().find(|s| s.platform == platform) {
+            Some(suggestion) => {
+                // Independent evidence combines as a noisy-OR, like detection rules
+                suggestion.confidence = 1.0 - (1.0 - suggestion.confidence) * (1.0 - weight);
+                suggestion.indicators.push(indicator);
+            }
+            None => suggestions.push(DeploymentPlatformSuggestion {
+                platform: platform.to_string(),
+                confidence: weight,
+                indicators: vec![indicator],
+            }),
+        }
+    }
+
+    for suggestion in &mut suggestions {
+        suggestion.confidence = (suggestion.confidence * 100.0).round() / 100.0;
+    }
+    suggestions.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+    suggestions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_github_actions_jobs_classified() {
+        let workflow = r#"
+name: CI
+on: [push]
+jobs:
+  lint:
+    runs-on: ubuntu-latest
+    steps:
+      - run: npm run lint
+  unit:
+    runs-on: ubuntu-latest
+    steps:
+      - run: npx vitest run
+  ship:
+    needs: [lint, unit]
+    steps:
+      - run: flyctl deploy --remote-only
+"#;
+        let parsed = parse_workflow("github-actions", ".github/workflows/ci.yml", workflow);
+
+        let jobs: Vec<(&str, Vec<String>)> = parsed.jobs.iter().map(|j| (j.name.as_str(), j.stages.clone())).collect();
+        assert_eq!(
+            jobs,
+            vec![
+                ("lint", vec!["lint".to_string()]),
+                ("unit", vec!["test".to_string()]),
+                ("ship", vec!["deploy".to_string()]),
+            ]
+        );
+        assert_eq!(parsed.stages, vec!["lint", "test", "deploy"]);
+    }
+
+    #[test]
+    fn test_gitlab_jobs_skip_keywords_and_templates() {
+        let pipeline = "stages:\n  - test\n\n.base:\n  image: node\n\nunit-tests:\n  stage: test\n  script: npm test\n\nbuild-image:\n  stage: build\n  script: docker build .\n";
+        let parsed = parse_workflow("gitlab-ci", ".gitlab-ci.yml", pipeline);
+
+        let names: Vec<&str> = parsed.jobs.iter().map(|j| j.name.as_str()).collect();
+        assert_eq!(names, vec!["unit-tests", "build-image"]);
+    }
+
+    #[test]
+    fn test_dockerfile_skips_stage_references() {
+        let dockerfile = "FROM node:20-alpine AS build\nRUN npm ci\nFROM --platform=linux/amd64 build AS test\nFROM registry.local:5000/nginx\n";
+        let images = parse_dockerfile("Dockerfile", dockerfile);
+
+        assert_eq!(images.len(), 2);
+        assert_eq!(images[0].image, "node");
+        assert_eq!(images[0].tag.as_deref(), Some("20-alpine"));
+        assert_eq!(images[0].stage.as_deref(), Some("build"));
+        assert_eq!(images[1].image, "registry.local:5000/nginx");
+        assert_eq!(images[1].tag, None);
+    }
+
+    #[test]
+    fn test_platform_files_outrank_docker() {
+        let platforms = suggest_platforms(&[], &["fly.toml".to_string()], true, &CiProfile::default());
+        let ids: Vec<&str> = platforms.iter().map(|p| p.platform.as_str()).collect();
+        assert_eq!(ids, vec!["fly", "docker"]);
+    }
+
+    #[test]
+    fn test_root_chart_only_hides_its_templates() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path();
+        let deployment = "apiVersion: apps/v1\nkind: Deployment\n";
+        for (file, content) in [
+            ("Chart.yaml", "name: shop\n"),
+            ("templates/deployment.yaml", deployment),
+            ("k8s/deployment.yaml", deployment),
+            ("config/MyChart.yaml", deployment),
+        ] {
+            let path = project.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        let files: Vec<String> = ["Chart.yaml", "templates/deployment.yaml", "k8s/deployment.yaml", "config/MyChart.yaml"]
+            .iter()
+            .map(|f| f.to_string())
+            .collect();
+        let profile = detect_infrastructure(project, &files, &CiProfile::default());
+
+        let kubernetes = profile.iac.iter().find(|i| i.tool == "kubernetes").unwrap();
+        assert_eq!(kubernetes.files, vec!["k8s/deployment.yaml", "config/MyChart.yaml"]);
+        let helm = profile.iac.iter().find(|i| i.tool == "helm").unwrap();
+        assert_eq!(helm.files, vec!["Chart.yaml"]);
+    }
+}
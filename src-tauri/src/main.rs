@@ -2,18 +2,42 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 mod runtime_check;
+mod analysis_cache;
+mod analysis_progress;
+mod architecture;
+mod auth_scaffold;
+mod ci_scaffold;
 mod code_analyzer;
+mod database_scaffold;
+mod deploy_scaffold;
+mod docker_scaffold;
+mod detection_rules;
+mod findings;
+mod import_graph;
+mod infra_detector;
+mod migration_planner;
 mod project_generator;
+mod pattern_catalog;
 mod pattern_generator;
+mod profile_mapper;
+mod relative_path;
 mod runtime_detector;
+mod testing;
+mod yaml_writer;
 
 use runtime_check::{check_all_runtimes, RuntimeCheckResult, RuntimeCache};
-use code_analyzer::analyze_codebase;
-use project_generator::generate_project;
-use pattern_generator::{generate_pattern_project, ArchitecturePatternConfig, PatternGenerationResult};
+use analysis_progress::{cancel_analysis, AnalysisProgress, AnalysisRegistry};
+use code_analyzer::{analyze_codebase, ProjectProfile};
+use migration_planner::{MigrationPlan, MigrationRequest};
+use project_generator::{generate_project, list_stack_modules};
+use pattern_catalog::PatternCatalog;
+use pattern_generator::{
+    generate_pattern_project, AddComponentRequest, AddComponentResult, ArchitecturePatternConfig, PatternGenerationResult,
+};
+use profile_mapper::{profile_to_config, ProfileConfigMapping};
 use runtime_detector::{analyze_project, RuntimeAnalysisOptions, RecommendationResult};
 use std::sync::Mutex;
-use tauri::State;
+use tauri::{Manager, State};
 
 // Global runtime cache with 5-minute TTL
 struct AppState {
@@ -113,11 +137,48 @@ async fn generate_pattern_project_command(
     generate_pattern_project_with_progress(config, window).await
 }
 
+/// Render one pattern component into an existing project
+#[tauri::command]
+async fn add_component(request: AddComponentRequest, app: tauri::AppHandle) -> Result<AddComponentResult, String> {
+    let catalog = PatternCatalog::with_user_catalogs(app.path().app_config_dir().ok().as_deref())?;
+    pattern_generator::add_component(request, &catalog)
+}
+
+/// Bundled architecture patterns merged with the user's catalog files
+#[tauri::command]
+fn list_architecture_patterns(app: tauri::AppHandle) -> Result<PatternCatalog, String> {
+    PatternCatalog::with_user_catalogs(app.path().app_config_dir().ok().as_deref())
+}
+
+/// Ordered steps that move an analyzed project towards a catalog pattern
+#[tauri::command]
+fn plan_migration(request: MigrationRequest, app: tauri::AppHandle) -> Result<MigrationPlan, String> {
+    let catalog = PatternCatalog::with_user_catalogs(app.path().app_config_dir().ok().as_deref())?;
+    migration_planner::plan_migration(&request, &catalog)
+}
+
+#[tauri::command]
+fn profile_to_config_command(profile: ProjectProfile, app: tauri::AppHandle) -> Result<ProfileConfigMapping, String> {
+    let catalog = PatternCatalog::with_user_catalogs(app.path().app_config_dir().ok().as_deref())?;
+    Ok(profile_to_config(&profile, &catalog))
+}
+
 #[tauri::command]
 async fn analyze_project_runtime(
-    options: RuntimeAnalysisOptions
+    options: RuntimeAnalysisOptions,
+    analysis_id: Option<String>,
+    app: tauri::AppHandle,
+    registry: State<'_, AnalysisRegistry>,
 ) -> Result<RecommendationResult, String> {
-    analyze_project(options)
+    let analysis_id = analysis_id.unwrap_or_else(analysis_progress::generated_analysis_id);
+    let progress = AnalysisProgress::for_app(&app, &analysis_id, registry.register(&analysis_id)?);
+    let config_dir = app.path().app_config_dir().ok();
+    let result = analyze_project(options, progress.clone(), config_dir.as_deref());
+    if progress.is_cancelled() {
+        progress.phase("cancelled", analysis_progress::CANCELLED_ERROR);
+    }
+    registry.finish(&analysis_id);
+    result
 }
 
 fn main() {
@@ -125,14 +186,21 @@ fn main() {
         .manage(AppState {
             cache: Mutex::new(RuntimeCache::new(300)), // 5-minute TTL
         })
+        .manage(AnalysisRegistry::default())
         .invoke_handler(tauri::generate_handler![
             check_runtimes,
             refresh_runtime_cache,
             get_install_instructions,
             analyze_codebase,
             generate_project,
+            list_stack_modules,
             generate_pattern_project_command,
-            analyze_project_runtime
+            add_component,
+            list_architecture_patterns,
+            plan_migration,
+            profile_to_config_command,
+            analyze_project_runtime,
+            cancel_analysis
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,554 @@
+//! Migration Planner - Move an existing codebase towards a catalog pattern
+//!
+//! Diffs the layout found by the runtime detector (and, when available, the
+//! code analyzer's `ProjectProfile`) against a pattern's components and
+//! scaffolding, and produces an ordered plan: directories to create, files
+//! to move, configs to add and dependencies to introduce. The plan is
+//! returned as JSON with a Markdown rendering for export.
+
+use crate::code_analyzer::ProjectProfile;
+use crate::pattern_catalog::{PatternCatalog, PatternComponent};
+use crate::pattern_generator::{DirectoryDef, FileDef, COMPOSE_FILES};
+use crate::relative_path::{file_name, join_path};
+use crate::runtime_detector::{DirectoryStack, TechnologyStack};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+/// Directories moved along with a root-level stack
+const ROOT_SOURCE_DIRS: &[&str] = &[
+    "src", "app", "lib", "public", "static", "tests", "routes", "pages", "components", "cmd", "internal", "pkg",
+];
+
+/// Lockfiles moved along with their manifest
+const LOCKFILES: &[&str] = &["package-lock.json", "pnpm-lock.yaml", "yarn.lock", "Cargo.lock", "poetry.lock", "go.sum"];
+
+/// Package that introduces a framework, where it differs from the framework id
+const FRAMEWORK_PACKAGES: &[(&str, &str)] = &[
+    ("sveltekit", "@sveltejs/kit"),
+    ("nextjs", "next"),
+    ("apollo-server", "@apollo/server"),
+    ("react-native", "react-native"),
+    ("spring-boot", "spring-boot-starter-web"),
+    ("gin", "github.com/gin-gonic/gin"),
+    ("echo", "github.com/labstack/echo/v4"),
+    ("fiber", "github.com/gofiber/fiber/v2"),
+];
+
+/// Component roles that run as their own service
+const SERVICE_ROLES: &[&str] = &["frontend", "backend"];
+
+// ============================================================================
+// TYPES
+// ============================================================================
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationRequest {
+    pub project_path: String,
+    pub pattern_id: String,
+    pub tech_stack: TechnologyStack,
+    #[serde(default)]
+    pub profile: Option<ProjectProfile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationPlan {
+    pub pattern_id: String,
+    pub pattern_name: String,
+    pub project_path: String,
+    pub steps: Vec<MigrationStep>,
+    pub warnings: Vec<String>,
+    pub markdown: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationStep {
+    pub order: usize,
+    pub action: String, // "create-directory", "move", "add-config", "add-dependency"
+    pub component: Option<String>,
+    pub source: Option<String>, // moved path, or the manifest a dependency goes into
+    pub target: String,         // relative path, or the package for dependencies
+    pub reason: String,
+}
+
+// ============================================================================
+// PLANNING
+// ============================================================================
+
+struct Planner<'a> {
+    project: &'a Path,
+    request: &'a MigrationRequest,
+    moves: Vec<(String, String)>, // (source, target) relative paths
+    steps: Vec<MigrationStep>,
+    warnings: Vec<String>,
+    planned: HashSet<String>,
+}
+
+pub fn plan_migration(request: &MigrationRequest, catalog: &PatternCatalog) -> Result<MigrationPlan, String> {
+    let project = Path::new(&request.project_path);
+    if !project.is_dir() {
+        return Err(format!("Project directory does not exist: {}", request.project_path));
+    }
+    let pattern = catalog
+        .get(&request.pattern_id)
+        .ok_or_else(|| format!("Unknown architecture pattern '{}'", request.pattern_id))?;
+
+    let mut planner = Planner {
+        project,
+        request,
+        moves: Vec::new(),
+        steps: Vec::new(),
+        warnings: Vec::new(),
+        planned: HashSet::new(),
+    };
+
+    // Moves are decided first so existence checks can follow files to their new place
+    let moves = planner.plan_moves(&pattern.components);
+    for component in &pattern.components {
+        planner.plan_directories(component);
+    }
+    planner.steps.extend(moves);
+    for component in &pattern.components {
+        planner.plan_scaffolding_configs(component);
+    }
+    planner.plan_service_configs(&pattern.components);
+    for component in &pattern.components {
+        planner.plan_dependency(component);
+    }
+
+    for (index, step) in planner.steps.iter_mut().enumerate() {
+        step.order = index + 1;
+    }
+    let mut plan = MigrationPlan {
+        pattern_id: pattern.id.clone(),
+        pattern_name: pattern.name.clone(),
+        project_path: request.project_path.clone(),
+        steps: planner.steps,
+        warnings: planner.warnings,
+        markdown: String::new(),
+    };
+    plan.markdown = to_markdown(&plan);
+    Ok(plan)
+}
+
+impl Planner<'_> {
+    fn step(&mut self, action: &str, component: &str, source: Option<String>, target: String, reason: String) -> MigrationStep {
+        self.planned.insert(target.clone());
+        MigrationStep {
+            order: 0,
+            action: action.to_string(),
+            component: Some(component.to_string()),
+            source,
+            target,
+            reason,
+        }
+    }
+
+    /// Whether a path exists now, or will once the planned moves are done
+    fn exists(&self, target: &str) -> bool {
+        if self.planned.contains(target) || self.project.join(target).exists() {
+            return true;
+        }
+        self.moves.iter().any(|(source, moved_to)| {
+            let rest = if moved_to == "." {
+                Some(target)
+            } else {
+                target.strip_prefix(moved_to.as_str()).and_then(|r| r.strip_prefix('/'))
+            };
+            rest.is_some_and(|rest| self.project.join(join_path(source, rest)).exists())
+        })
+    }
+
+    /// Pair detected stack directories with the pattern's components and move
+    /// the ones that live somewhere else
+    fn plan_moves(&mut self, components: &[PatternComponent]) -> Vec<MigrationStep> {
+        let stack = &self.request.tech_stack;
+        let mut used: HashSet<&str> = HashSet::new();
+        let mut root_moved = false;
+        let mut steps = Vec::new();
+
+        for component in components {
+            let Some(directory) = matching_directory(component, &stack.directories, &used) else {
+                continue;
+            };
+            used.insert(&directory.path);
+
+            if let Some(detected) = slot_framework(component, directory) {
+                if detected != component.framework && component.framework != "none" {
+                    self.warnings.push(format!(
+                        "{}: detected {} in {} but the pattern uses {}; the existing code is moved, not rewritten",
+                        component.id, detected, directory.path, component.framework
+                    ));
+                }
+            }
+            if directory.path == component.location {
+                continue;
+            }
+
+            if directory.path != "." {
+                self.moves.push((directory.path.clone(), component.location.clone()));
+                let reason = format!("{} code for the {} component", directory.path, component.id);
+                steps.push(self.step("move", &component.id, Some(format!("{}/", directory.path)), format!("{}/", component.location), reason));
+                continue;
+            }
+
+            // A root stack can only move out once; the rest of the root stays shared
+            if root_moved {
+                self.warnings.push(format!(
+                    "{}: its code shares the project root with another component; split it into {} by hand",
+                    component.id, component.location
+                ));
+                continue;
+            }
+            root_moved = true;
+            for entry in root_entries(self.project, &directory.manifests) {
+                let target = join_path(&component.location, &entry);
+                self.moves.push((entry.clone(), target.clone()));
+                let reason = format!("root {} project becomes the {} component", component.framework, component.id);
+                steps.push(self.step("move", &component.id, Some(entry), target, reason));
+            }
+            self.warnings.push(format!(
+                "{}: only manifests, lockfiles, build configs and source directories are moved from the root; review the remaining root files",
+                component.id
+            ));
+        }
+
+        for directory in &stack.directories {
+            if !used.contains(directory.path.as_str()) {
+                self.warnings.push(format!(
+                    "{} has no matching component in this pattern and is left in place",
+                    directory.path
+                ));
+            }
+        }
+        steps
+    }
+
+    fn plan_directories(&mut self, component: &PatternComponent) {
+        let mut directories = Vec::new();
+        if component.location != "." {
+            directories.push(component.location.clone());
+        }
+        for directory in &component.scaffolding.directories {
+            collect_directories(directory, &component.location, &mut directories);
+        }
+        for directory in directories {
+            if !self.exists(&directory) {
+                let reason = format!("{} scaffolding", component.id);
+                let step = self.step("create-directory", &component.id, None, directory, reason);
+                self.steps.push(step);
+            }
+        }
+    }
+
+    fn plan_scaffolding_configs(&mut self, component: &PatternComponent) {
+        let mut files = Vec::new();
+        for file in &component.scaffolding.files {
+            files.push(join_path(&component.location, &file.path));
+        }
+        for directory in &component.scaffolding.directories {
+            collect_files(directory, &component.location, &mut files);
+        }
+        for file in files.into_iter().filter(|f| is_config(f)) {
+            if !self.exists(&file) {
+                let reason = format!("{} {} configuration from the pattern", component.framework, component.role);
+                let step = self.step("add-config", &component.id, None, file, reason);
+                self.steps.push(step);
+            }
+        }
+    }
+
+    /// Dockerfiles per service, compose when several services run together, and CI
+    fn plan_service_configs(&mut self, components: &[PatternComponent]) {
+        let services: Vec<&PatternComponent> = components.iter().filter(|c| SERVICE_ROLES.contains(&c.role.as_str())).collect();
+        let profile = self.request.profile.as_ref();
+
+        for service in &services {
+            let dockerfile = join_path(&service.location, "Dockerfile");
+            if !self.exists(&dockerfile) {
+                let reason = format!("container image for the {} service", service.id);
+                let step = self.step("add-config", &service.id, None, dockerfile, reason);
+                self.steps.push(step);
+            }
+        }
+
+        let has_database = components.iter().any(|c| c.role == "database");
+        let has_compose = self.request.tech_stack.config_files.docker_compose
+            || COMPOSE_FILES.iter().any(|file| self.exists(file));
+        if (services.len() > 1 || has_database) && !has_compose {
+            let names: Vec<&str> = components.iter().map(|c| c.id.as_str()).collect();
+            let reason = format!("runs {} together", names.join(", "));
+            let step = self.step("add-config", "project", None, "docker-compose.yml".to_string(), reason);
+            self.steps.push(step);
+        }
+
+        let has_ci = profile.is_some_and(|p| p.has_ci) || self.exists(".github/workflows") || self.exists(".gitlab-ci.yml");
+        if !has_ci {
+            let reason = "no CI configuration detected; build and test every component".to_string();
+            let step = self.step("add-config", "project", None, ".github/workflows/ci.yml".to_string(), reason);
+            self.steps.push(step);
+        }
+    }
+
+    fn plan_dependency(&mut self, component: &PatternComponent) {
+        if component.framework == "none" || component.role == "database" {
+            return;
+        }
+        let package = FRAMEWORK_PACKAGES
+            .iter()
+            .find(|(framework, _)| *framework == component.framework)
+            .map(|(_, package)| package.to_string())
+            .unwrap_or_else(|| component.framework.clone());
+        if self.framework_present(&component.framework, &package) {
+            return;
+        }
+
+        let manifest = manifest_for(&component.language).map(|m| join_path(&component.location, m));
+        let reason = format!("{} is the {} framework of the {} component", package, component.role, component.id);
+        let step = MigrationStep {
+            order: 0,
+            action: "add-dependency".to_string(),
+            component: Some(component.id.clone()),
+            source: manifest,
+            target: package,
+            reason,
+        };
+        self.steps.push(step);
+    }
+
+    fn framework_present(&self, framework: &str, package: &str) -> bool {
+        let stack = &self.request.tech_stack;
+        let in_stack = [&stack.frontend_framework, &stack.backend_framework, &stack.desktop_framework]
+            .into_iter()
+            .chain(stack.directories.iter().flat_map(|d| [&d.frontend_framework, &d.backend_framework, &d.desktop_framework]))
+            .any(|f| f.as_deref() == Some(framework));
+        let in_profile = self.request.profile.as_ref().is_some_and(|p| {
+            p.frameworks.iter().any(|f| f.name.eq_ignore_ascii_case(framework))
+                || p.dependencies.iter().any(|d| d.name == package)
+        });
+        in_stack || in_profile
+    }
+}
+
+/// The detected directory holding a component's slot, preferring the same framework
+fn matching_directory<'a>(
+    component: &PatternComponent,
+    directories: &'a [DirectoryStack],
+    used: &HashSet<&str>,
+) -> Option<&'a DirectoryStack> {
+    let candidates: Vec<&DirectoryStack> = directories
+        .iter()
+        .filter(|d| !used.contains(d.path.as_str()) && slot_framework(component, d).is_some())
+        .collect();
+    candidates
+        .iter()
+        .find(|d| slot_framework(component, d) == Some(component.framework.as_str()))
+        .or_else(|| candidates.first())
+        .copied()
+}
+
+fn slot_framework<'a>(component: &PatternComponent, directory: &'a DirectoryStack) -> Option<&'a str> {
+    match component.role.as_str() {
+        "frontend" => directory.frontend_framework.as_deref(),
+        "backend" => directory.backend_framework.as_deref().or(directory.desktop_framework.as_deref()),
+        _ => None,
+    }
+}
+
+/// Root entries that belong to the root stack: manifests, lockfiles, build configs and sources
+fn root_entries(project: &Path, manifests: &[String]) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(project) else {
+        return manifests.to_vec();
+    };
+    let mut moved: Vec<String> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let is_dir = entry.path().is_dir();
+            let belongs = if is_dir {
+                ROOT_SOURCE_DIRS.contains(&name.as_str())
+            } else {
+                manifests.contains(&name)
+                    || LOCKFILES.contains(&name.as_str())
+                    || name.contains(".config.")
+                    || (name.starts_with("tsconfig") && name.ends_with(".json"))
+                    || name == "index.html"
+            };
+            belongs.then_some(name)
+        })
+        .collect();
+    moved.sort();
+    moved
+}
+
+fn collect_directories(directory: &DirectoryDef, parent: &str, out: &mut Vec<String>) {
+    let path = join_path(parent, &directory.path);
+    out.push(path.clone());
+    for sub in directory.subdirectories.iter().flatten() {
+        collect_directories(sub, &path, out);
+    }
+}
+
+fn collect_files(directory: &DirectoryDef, parent: &str, out: &mut Vec<String>) {
+    let path = join_path(parent, &directory.path);
+    out.extend(directory.files.iter().flatten().map(|f: &FileDef| join_path(&path, &f.path)));
+    for sub in directory.subdirectories.iter().flatten() {
+        collect_files(sub, &path, out);
+    }
+}
+
+/// Manifests and tool configuration, as opposed to source files
+fn is_config(path: &str) -> bool {
+    let name = file_name(path);
+    name == "Dockerfile"
+        || name.starts_with(".env")
+        || ["requirements.txt", "go.mod", "Makefile"].contains(&name)
+        || [".json", ".toml", ".yaml", ".yml", ".cfg", ".ini"].iter().any(|ext| name.ends_with(ext))
+        || name.contains(".config.")
+}
+
+fn manifest_for(language: &str) -> Option<&'static str> {
+    match language {
+        "typescript" | "javascript" => Some("package.json"),
+        "python" => Some("requirements.txt"),
+        "rust" => Some("Cargo.toml"),
+        "go" => Some("go.mod"),
+        "php" => Some("composer.json"),
+        "dart" => Some("pubspec.yaml"),
+        _ => None,
+    }
+}
+
+// ============================================================================
+// MARKDOWN
+// ============================================================================
+
+const SECTIONS: &[(&str, &str)] = &[
+    ("create-directory", "Create directories"),
+    ("move", "Move files"),
+    ("add-config", "Add configuration"),
+    ("add-dependency", "Add dependencies"),
+];
+
+pub fn to_markdown(plan: &MigrationPlan) -> String {
+    let mut md = format!("# Migration plan: {}\n\nProject: `{}`\n", plan.pattern_name, plan.project_path);
+    if plan.steps.is_empty() {
+        md.push_str("\nThe project already matches this pattern's layout.\n");
+    }
+
+    for (action, title) in SECTIONS {
+        let steps: Vec<&MigrationStep> = plan.steps.iter().filter(|s| s.action == *action).collect();
+        if steps.is_empty() {
+            continue;
+        }
+        md.push_str(&format!("\n## {}\n\n", title));
+        for step in steps {
+            let line = match (step.action.as_str(), &step.source) {
+                ("move", Some(source)) => format!("`{}` → `{}`", source, step.target),
+                ("add-dependency", Some(manifest)) => format!("`{}` in `{}`", step.target, manifest),
+                _ => format!("`{}`", step.target),
+            };
+            md.push_str(&format!("{}. {} — {}\n", step.order, line, step.reason));
+        }
+    }
+
+    if !plan.warnings.is_empty() {
+        md.push_str("\n## Warnings\n\n");
+        for warning in &plan.warnings {
+            md.push_str(&format!("- {}\n", warning));
+        }
+    }
+    md
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime_detector::{ConfigFiles, PackageFiles};
+
+    #[test]
+    fn test_plan_moves_root_app_into_fullstack_layout() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path();
+        fs::create_dir_all(project.join("src/routes")).unwrap();
+        fs::write(project.join("package.json"), r#"{"devDependencies": {"@sveltejs/kit": "^2"}}"#).unwrap();
+        fs::write(project.join("svelte.config.js"), "export default {};").unwrap();
+        fs::write(project.join("README.md"), "# app").unwrap();
+
+        let request = MigrationRequest {
+            project_path: project.to_string_lossy().to_string(),
+            pattern_id: "fullstack-web".to_string(),
+            tech_stack: TechnologyStack {
+                primary_language: "typescript".to_string(),
+                additional_languages: vec![],
+                frontend_framework: Some("sveltekit".to_string()),
+                backend_framework: None,
+                desktop_framework: None,
+                build_tools: vec!["npm".to_string()],
+                databases: vec![],
+                structure: "single-app".to_string(),
+                package_files: PackageFiles {
+                    package_json: true,
+                    cargo_toml: false,
+                    requirements_txt: false,
+                    poetry_lock: false,
+                    go_mod: false,
+                },
+                config_files: ConfigFiles {
+                    vite_config: false,
+                    svelte_config: true,
+                    tauri_config: false,
+                    docker_compose: false,
+                    dockerfile: false,
+                },
+                directories: vec![DirectoryStack {
+                    path: ".".to_string(),
+                    manifests: vec!["package.json".to_string()],
+                    languages: vec!["typescript".to_string()],
+                    frontend_framework: Some("sveltekit".to_string()),
+                    backend_framework: None,
+                    desktop_framework: None,
+                }],
+                technology_confidence: vec![],
+                confidence: 90,
+            },
+            profile: None,
+        };
+        let plan = plan_migration(&request, &PatternCatalog::bundled()).unwrap();
+
+        let targets = |action: &str| -> Vec<String> {
+            plan.steps.iter().filter(|s| s.action == action).map(|s| s.target.clone()).collect()
+        };
+        let moved = targets("move");
+        assert_eq!(moved, vec!["frontend/package.json", "frontend/src", "frontend/svelte.config.js"]);
+        let created = targets("create-directory");
+        assert!(created.contains(&"backend/app".to_string()));
+        // src/routes moves along with src, so it is not created again
+        assert!(!created.contains(&"frontend/src/routes".to_string()));
+        let configs = targets("add-config");
+        assert!(configs.contains(&"docker-compose.yml".to_string()));
+        assert!(configs.contains(&"backend/Dockerfile".to_string()));
+        assert!(configs.contains(&".github/workflows/ci.yml".to_string()));
+        assert!(!configs.contains(&"frontend/package.json".to_string()));
+        assert_eq!(targets("add-dependency"), vec!["fastapi"]);
+
+        // Steps are numbered in section order
+        let orders: Vec<usize> = plan.steps.iter().map(|s| s.order).collect();
+        assert_eq!(orders, (1..=plan.steps.len()).collect::<Vec<_>>());
+        assert!(plan.markdown.contains("## Move files"));
+        assert!(plan.markdown.contains("`fastapi` in `backend/requirements.txt`"));
+
+        // Any of the compose file names counts as an existing compose setup
+        fs::write(project.join("compose.yml"), "services: {}\n").unwrap();
+        let plan = plan_migration(&request, &PatternCatalog::bundled()).unwrap();
+        assert!(!plan.steps.iter().any(|s| s.target == "docker-compose.yml"));
+    }
+}
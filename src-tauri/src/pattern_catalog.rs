@@ -0,0 +1,356 @@
+//! Pattern Catalog - Architecture patterns shared by recommendation and generation
+//!
+//! Each pattern declares its components with their default scaffolding, the
+//! weighted criteria it is recommended by and the conflicts it warns about.
+//! The bundled catalog ships with the app; users can add or override patterns
+//! with JSON or TOML catalog files.
+
+use crate::pattern_generator::{ArchitecturePatternConfig, ComponentGenerationConfig, ScaffoldingConfig};
+use crate::runtime_detector::TechnologyStack;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+const BUNDLED_CATALOG: &str = include_str!("../rules/pattern-catalog.json");
+
+/// File names looked up in the app config dir, in merge order
+pub const USER_CATALOG_FILES: &[&str] = &["pattern-catalog.json", "pattern-catalog.toml"];
+
+// ============================================================================
+// CATALOG DEFINITIONS
+// ============================================================================
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PatternCatalog {
+    #[serde(default)]
+    pub patterns: Vec<PatternDefinition>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatternDefinition {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub category: String,
+    #[serde(default)]
+    pub components: Vec<PatternComponent>,
+    #[serde(default)]
+    pub criteria: Vec<PatternCriterion>,
+    #[serde(default)]
+    pub warnings: Vec<PatternWarning>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatternComponent {
+    pub id: String,
+    pub role: String, // "frontend", "backend", "database", "library", "cli"
+    pub name: String,
+    pub language: String,
+    pub framework: String,
+    pub location: String, // relative path within project
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    #[serde(default)]
+    pub scaffolding: ScaffoldingConfig,
+}
+
+/// A condition over one field of the detected `TechnologyStack`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldCondition {
+    /// Dotted path into the serialized stack, e.g. `config_files.vite_config` or `directories.path`
+    pub field: String,
+    /// Values that satisfy the condition; empty means any value
+    #[serde(default)]
+    pub any_of: Vec<String>,
+    /// Values treated as absent, e.g. `none` for databases
+    #[serde(default)]
+    pub ignore: Vec<String>,
+    #[serde(default = "default_min_count")]
+    pub min_count: usize,
+    /// Holds when the field has no matching value instead
+    #[serde(default)]
+    pub absent: bool,
+}
+
+/// A weighted condition that speaks for a pattern
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatternCriterion {
+    /// Reason shown when the criterion holds; `{value}` is replaced by the matched value
+    pub description: String,
+    pub weight: u8,
+    /// The pattern is only recommended when this criterion holds
+    #[serde(default)]
+    pub required: bool,
+    #[serde(flatten)]
+    pub condition: FieldCondition,
+}
+
+/// A condition that conflicts with a pattern
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatternWarning {
+    /// `{value}` is replaced by the matched value
+    pub message: String,
+    #[serde(flatten)]
+    pub condition: FieldCondition,
+}
+
+fn default_min_count() -> usize {
+    1
+}
+
+impl PatternCatalog {
+    pub fn bundled() -> Self {
+        serde_json::from_str(BUNDLED_CATALOG).expect("bundled pattern catalog is valid JSON")
+    }
+
+    /// Load a catalog file; `.toml` files are parsed as TOML, anything else as JSON
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read pattern catalog {}: {}", path.display(), e))?;
+        let parsed = if path.extension().is_some_and(|e| e == "toml") {
+            toml::from_str(&content).map_err(|e| e.to_string())
+        } else {
+            serde_json::from_str(&content).map_err(|e| e.to_string())
+        };
+        parsed.map_err(|e| format!("Invalid pattern catalog in {}: {}", path.display(), e))
+    }
+
+    /// Merge another catalog; patterns with an existing id replace the original.
+    pub fn merge(&mut self, other: PatternCatalog) {
+        for pattern in other.patterns {
+            match self.patterns.iter_mut().find(|p| p.id == pattern.id) {
+                Some(existing) => *existing = pattern,
+                None => self.patterns.push(pattern),
+            }
+        }
+    }
+
+    /// Merge a catalog file if it exists. Returns whether the file was found.
+    pub fn merge_file(&mut self, path: &Path) -> Result<bool, String> {
+        if !path.is_file() {
+            return Ok(false);
+        }
+        self.merge(Self::load(path)?);
+        Ok(true)
+    }
+
+    /// Bundled catalog extended with the user catalog files in `config_dir`
+    pub fn with_user_catalogs(config_dir: Option<&Path>) -> Result<Self, String> {
+        let mut catalog = Self::bundled();
+        if let Some(dir) = config_dir {
+            for file in USER_CATALOG_FILES {
+                catalog.merge_file(&dir.join(file))?;
+            }
+        }
+        Ok(catalog)
+    }
+
+    pub fn get(&self, pattern_id: &str) -> Option<&PatternDefinition> {
+        self.patterns.iter().find(|p| p.id == pattern_id)
+    }
+
+    /// Check a generation request against its catalog pattern and fill in
+    /// what the request left out: all components when none are given, and
+    /// default scaffolding for known components that arrive without any.
+    pub fn prepare_generation(&self, config: &mut ArchitecturePatternConfig) -> Result<(), String> {
+        let pattern = self
+            .get(&config.pattern_id)
+            .ok_or_else(|| format!("Unknown architecture pattern '{}'", config.pattern_id))?;
+
+        if config.components.is_empty() {
+            config.components = pattern.components.iter().map(PatternComponent::to_generation_config).collect();
+        }
+
+        let mut ids = HashSet::new();
+        let mut locations = HashSet::new();
+        for component in &mut config.components {
+            if !ids.insert(component.id.clone()) {
+                return Err(format!("Duplicate component id '{}'", component.id));
+            }
+            if !locations.insert(component.location.clone()) && component.location != "." {
+                return Err(format!("Components share the location '{}'", component.location));
+            }
+            let scaffolding = &component.scaffolding;
+            if scaffolding.directories.is_empty() && scaffolding.files.is_empty() {
+                if let Some(default) = pattern.components.iter().find(|c| c.id == component.id) {
+                    component.scaffolding = default.scaffolding.clone();
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Scaffolding of the first catalog component with the same role, language and framework
+    pub fn default_scaffolding(&self, component: &ComponentGenerationConfig) -> Option<ScaffoldingConfig> {
+        self.patterns
+            .iter()
+            .flat_map(|p| &p.components)
+            .find(|c| c.role == component.role && c.language == component.language && c.framework == component.framework)
+            .map(|c| c.scaffolding.clone())
+    }
+}
+
+impl PatternComponent {
+    pub fn to_generation_config(&self) -> ComponentGenerationConfig {
+        ComponentGenerationConfig {
+            id: self.id.clone(),
+            role: self.role.clone(),
+            name: self.name.clone(),
+            language: self.language.clone(),
+            framework: self.framework.clone(),
+            location: self.location.clone(),
+            scaffolding: self.scaffolding.clone(),
+            custom_config: None,
+        }
+    }
+}
+
+// ============================================================================
+// CRITERIA
+// ============================================================================
+
+/// Values at a dotted path; arrays are flattened, `true` reads as "true"
+fn field_values(value: &serde_json::Value, path: &str) -> Vec<String> {
+    let mut current = vec![value];
+    for key in path.split('.') {
+        current = current
+            .into_iter()
+            .flat_map(|v| match v {
+                serde_json::Value::Array(items) => items.iter().filter_map(|i| i.get(key)).collect(),
+                other => other.get(key).into_iter().collect::<Vec<_>>(),
+            })
+            .collect();
+    }
+
+    let mut values = Vec::new();
+    for value in current {
+        match value {
+            serde_json::Value::String(s) => values.push(s.clone()),
+            serde_json::Value::Bool(true) => values.push("true".to_string()),
+            serde_json::Value::Number(n) => values.push(n.to_string()),
+            serde_json::Value::Array(items) => values.extend(items.iter().filter_map(|i| i.as_str()).map(String::from)),
+            _ => {}
+        }
+    }
+    values
+}
+
+impl FieldCondition {
+    /// Evaluate against a stack serialized with `stack_fields`. Returns the
+    /// matched values (empty for `absent` conditions) when the condition holds.
+    pub fn evaluate(&self, stack: &serde_json::Value) -> Option<Vec<String>> {
+        let matching: Vec<String> = field_values(stack, &self.field)
+            .into_iter()
+            .filter(|v| !self.ignore.contains(v))
+            .filter(|v| self.any_of.is_empty() || self.any_of.contains(v))
+            .collect();
+
+        let holds = matching.len() >= self.min_count.max(1);
+        (holds != self.absent).then_some(matching)
+    }
+
+    /// What the condition expects, for criteria that did not hold
+    pub fn expected(&self) -> String {
+        let values = if self.any_of.is_empty() { "any value".to_string() } else { self.any_of.join(" or ") };
+        if self.absent {
+            format!("{} without {}", self.field, values)
+        } else {
+            format!("{} with {}", self.field, values)
+        }
+    }
+}
+
+fn fill(template: &str, values: &[String]) -> String {
+    template.replace("{value}", values.first().map(String::as_str).unwrap_or_default())
+}
+
+impl PatternCriterion {
+    /// The reason for a criterion that held with these values
+    pub fn reason(&self, values: &[String]) -> String {
+        fill(&self.description, values)
+    }
+}
+
+impl PatternWarning {
+    /// The warning message when the conflict is present
+    pub fn evaluate(&self, stack: &serde_json::Value) -> Option<String> {
+        self.condition.evaluate(stack).map(|values| fill(&self.message, &values))
+    }
+}
+
+/// The stack as snake_case JSON, the shape criterion `field` paths refer to
+pub fn stack_fields(stack: &TechnologyStack) -> serde_json::Value {
+    fn snake_keys(value: serde_json::Value) -> serde_json::Value {
+        match value {
+            serde_json::Value::Object(map) => map
+                .into_iter()
+                .map(|(key, value)| {
+                    let mut snake = String::with_capacity(key.len() + 4);
+                    for c in key.chars() {
+                        if c.is_ascii_uppercase() {
+                            snake.push('_');
+                            snake.push(c.to_ascii_lowercase());
+                        } else {
+                            snake.push(c);
+                        }
+                    }
+                    (snake, snake_keys(value))
+                })
+                .collect(),
+            serde_json::Value::Array(items) => items.into_iter().map(snake_keys).collect(),
+            other => other,
+        }
+    }
+    snake_keys(serde_json::to_value(stack).unwrap_or_default())
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundled_catalog_and_user_override() {
+        let mut catalog = PatternCatalog::bundled();
+        assert_eq!(catalog.patterns.len(), 10);
+        assert!(catalog.patterns.iter().all(|p| !p.components.is_empty() && !p.criteria.is_empty()));
+
+        let user: PatternCatalog = toml::from_str(
+            r#"
+[[patterns]]
+id = "worker"
+name = "Background Worker"
+
+[[patterns.components]]
+id = "worker"
+role = "backend"
+name = "Worker"
+language = "python"
+framework = "celery"
+location = "."
+
+[[patterns.criteria]]
+description = "Broker: {value}"
+weight = 50
+field = "databases"
+any_of = ["redis"]
+required = true
+"#,
+        )
+        .unwrap();
+        catalog.merge(user);
+        let worker = catalog.get("worker").unwrap();
+        assert_eq!(worker.components[0].framework, "celery");
+
+        let stack = serde_json::json!({ "databases": ["postgresql", "redis"], "config_files": { "vite_config": true } });
+        let values = worker.criteria[0].condition.evaluate(&stack).unwrap();
+        assert_eq!(worker.criteria[0].reason(&values), "Broker: redis");
+        assert_eq!(field_values(&stack, "config_files.vite_config"), vec!["true".to_string()]);
+    }
+}
@@ -1,3 +1,6 @@
+use crate::ci_scaffold::{app_jobs, check, ci_file, ci_provider, ci_toolchain, CiApp, CiProvider, CI_PROVIDERS};
+use crate::pattern_catalog::PatternCatalog;
+use crate::yaml_writer::{list, map, str, Yaml};
 use handlebars::Handlebars;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -11,7 +14,7 @@ use tauri::{Emitter, Manager};
 // TYPE DEFINITIONS (matching frontend TypeScript types)
 // ============================================================================
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ArchitecturePatternConfig {
     pub pattern_id: String,
     pub pattern_name: String,
@@ -22,7 +25,7 @@ pub struct ArchitecturePatternConfig {
     pub features: FeatureFlags,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ComponentGenerationConfig {
     pub id: String,
     pub role: String,  // "backend", "frontend", "database"
@@ -34,13 +37,13 @@ pub struct ComponentGenerationConfig {
     pub custom_config: Option<HashMap<String, serde_json::Value>>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ScaffoldingConfig {
     pub directories: Vec<DirectoryDef>,
     pub files: Vec<FileDef>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DirectoryDef {
     pub path: String,
     pub description: Option<String>,
@@ -48,7 +51,7 @@ pub struct DirectoryDef {
     pub files: Option<Vec<FileDef>>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileDef {
     pub path: String,
     pub content: String,
@@ -56,7 +59,7 @@ pub struct FileDef {
     pub overwritable: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FeatureFlags {
     pub testing: bool,
     pub linting: bool,
@@ -248,6 +251,9 @@ pub async fn generate_pattern_project_with_progress(
 
     // Stage 1: Preparing (0-5%)
     emit_progress("preparing", 0, "Validating configuration...", None);
+    let mut config = config;
+    let catalog = PatternCatalog::with_user_catalogs(window.app_handle().path().app_config_dir().ok().as_deref())?;
+    catalog.prepare_generation(&mut config)?;
     println!("Generating architecture pattern project: {}", config.project_name);
     println!("Pattern: {} ({})", config.pattern_name, config.pattern_id);
     println!("Components: {}", config.components.len());
@@ -379,7 +385,8 @@ pub async fn generate_pattern_project_with_progress(
 }
 
 /// Original synchronous generation function (kept for backward compatibility)
-pub fn generate_pattern_project(config: ArchitecturePatternConfig) -> Result<PatternGenerationResult, String> {
+pub fn generate_pattern_project(mut config: ArchitecturePatternConfig) -> Result<PatternGenerationResult, String> {
+    PatternCatalog::bundled().prepare_generation(&mut config)?;
     println!("Generating architecture pattern project: {}", config.project_name);
     println!("Pattern: {} ({})", config.pattern_name, config.pattern_id);
     println!("Components: {}", config.components.len());
@@ -445,6 +452,327 @@ pub fn generate_pattern_project(config: ArchitecturePatternConfig) -> Result<Pat
     })
 }
 
+// ============================================================================
+// ADD COMPONENT
+// ============================================================================
+
+/// Compose files looked up at the project root, in order
+pub(crate) const COMPOSE_FILES: &[&str] = &["docker-compose.yml", "docker-compose.yaml", "compose.yml", "compose.yaml"];
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AddComponentRequest {
+    pub project_path: String, // existing project root
+    pub component: ComponentGenerationConfig,
+    #[serde(default = "default_true")]
+    pub install_dependencies: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AddComponentResult {
+    pub success: bool,
+    pub project_path: String,
+    pub component_id: String,
+    pub message: String,
+    pub files_created: usize,
+    pub updated_files: Vec<String>, // root files whose section for the component was written
+    pub warnings: Vec<String>,
+}
+
+/// Render one component into an existing project and wire it into the root
+/// README, .gitignore, compose file and CI workflow. Each root file gets a
+/// section between `vibeforge:<id>` markers, so adding again replaces it.
+pub fn add_component(request: AddComponentRequest, catalog: &PatternCatalog) -> Result<AddComponentResult, String> {
+    let project_path = PathBuf::from(&request.project_path);
+    if !project_path.is_dir() {
+        return Err(format!("Project directory does not exist: {}", request.project_path));
+    }
+
+    let mut component = request.component;
+    let location = Path::new(&component.location);
+    if component.location.is_empty()
+        || component.location == "."
+        || location.is_absolute()
+        || location.components().any(|c| matches!(c, std::path::Component::ParentDir))
+    {
+        return Err(format!("Component location '{}' must be a subdirectory of the project", component.location));
+    }
+    let component_path = project_path.join(&component.location);
+    let occupied = component_path.is_file()
+        || fs::read_dir(&component_path).map(|mut entries| entries.next().is_some()).unwrap_or(false);
+    if occupied {
+        return Err(format!("Location '{}' already exists and is not empty", component.location));
+    }
+
+    if component.scaffolding.directories.is_empty() && component.scaffolding.files.is_empty() {
+        if let Some(default) = catalog.default_scaffolding(&component) {
+            component.scaffolding = default;
+        }
+    }
+
+    let compose_file = COMPOSE_FILES.iter().map(|f| project_path.join(f)).find(|p| p.is_file());
+    let ci_file = ci_workflow(&project_path);
+    let config = ArchitecturePatternConfig {
+        pattern_id: String::new(),
+        pattern_name: String::new(),
+        project_name: project_path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+        project_description: String::new(),
+        project_path: request.project_path.clone(),
+        components: vec![component.clone()],
+        features: FeatureFlags {
+            testing: true,
+            linting: true,
+            git: project_path.join(".git").exists(),
+            docker: compose_file.is_some(),
+            ci: ci_file.is_some(),
+        },
+    };
+
+    let mut handlebars = Handlebars::new();
+    handlebars.set_strict_mode(false);
+    register_handlebars_helpers(&mut handlebars);
+    let template_context = create_template_context(&config);
+
+    let files_created = generate_component(&component_path, &component, &handlebars, &template_context)?;
+
+    let mut updated_files = Vec::new();
+    let mut warnings = Vec::new();
+    let id = component.id.as_str();
+
+    let readme_path = project_path.join("README.md");
+    let readme = fs::read_to_string(&readme_path).unwrap_or_else(|_| format!("# {}\n", config.project_name));
+    let section = format!(
+        "<!-- vibeforge:{id} -->\n## {}\n\n{}/{} component in `{}`.\n\n{}\n<!-- /vibeforge:{id} -->",
+        component.name,
+        component.language,
+        component.framework,
+        component.location,
+        render_installation_steps(&config).trim_end(),
+    );
+    let markers = (format!("<!-- vibeforge:{} -->", id), format!("<!-- /vibeforge:{} -->", id));
+    write_root_file(&readme_path, &upsert_section(&readme, &markers, &section, None), &mut updated_files)?;
+
+    let entries = gitignore_entries(&component);
+    if !entries.is_empty() {
+        let gitignore_path = project_path.join(".gitignore");
+        let gitignore = fs::read_to_string(&gitignore_path).unwrap_or_default();
+        let markers = (format!("# vibeforge:{}", id), format!("# /vibeforge:{}", id));
+        let section = format!("{}\n{}\n{}", markers.0, entries.join("\n"), markers.1);
+        write_root_file(&gitignore_path, &upsert_section(&gitignore, &markers, &section, None), &mut updated_files)?;
+    }
+
+    if let Some(compose_path) = compose_file {
+        let compose = fs::read_to_string(&compose_path).map_err(|e| format!("Failed to read compose file: {}", e))?;
+        let markers = (format!("# vibeforge:{}", id), format!("# /vibeforge:{}", id));
+        if !component_path.join("Dockerfile").exists() {
+            warnings.push(format!("{} has no Dockerfile; the compose file was not updated", component.location));
+        } else if !compose.contains(&markers.0) && compose.lines().any(|l| l == format!("  {}:", id)) {
+            warnings.push(format!("A '{}' service already exists in the compose file; it was left unchanged", id));
+        } else {
+            let section = format!("  {}\n  {}:\n    build: ./{}\n  {}", markers.0, id, component.location, markers.1);
+            let insert_at = yaml_section_end(&compose, "services");
+            write_root_file(&compose_path, &upsert_section(&compose, &markers, &section, insert_at), &mut updated_files)?;
+        }
+    }
+
+    match (&ci_file, component_ci_app(&project_path, &component)) {
+        (Some((ci_path, provider)), Some(app)) => {
+            let mut ci = fs::read_to_string(ci_path).map_err(|e| format!("Failed to read CI workflow: {}", e))?;
+            let gitlab = provider.is_gitlab();
+            let jobs = app_jobs(provider, &app);
+
+            // The existing jobs stay unparsed; check the new ones against the
+            // keys they depend on
+            let mut document = if gitlab {
+                let stages = add_gitlab_stages(&mut ci, &jobs);
+                map([("stages", list(stages.into_iter().map(str)))])
+            } else {
+                map(["name", "on"].into_iter().filter_map(|key| yaml_scalar(&ci, key).map(|value| (key, str(value)))))
+            };
+            if gitlab {
+                jobs.iter().for_each(|(key, job)| document.insert(key, job.clone()));
+            } else {
+                document.insert("jobs", map(jobs.clone()));
+            }
+
+            if let Err(e) = check(provider, &document) {
+                warnings.push(format!("The CI workflow was not updated: {}", e));
+            } else {
+                // GitLab jobs are top-level keys, Actions jobs sit under `jobs:`
+                let indent = if gitlab { "" } else { "  " };
+                let markers = (format!("# vibeforge:{}", id), format!("# /vibeforge:{}", id));
+                let jobs = map(jobs).to_yaml();
+                let mut section = vec![format!("{}{}", indent, markers.0)];
+                section.extend(jobs.lines().map(|l| if l.is_empty() { String::new() } else { format!("{}{}", indent, l) }));
+                section.push(format!("{}{}", indent, markers.1));
+                let insert_at = if gitlab { None } else { yaml_section_end(&ci, "jobs") };
+                write_root_file(ci_path, &upsert_section(&ci, &markers, &section.join("\n"), insert_at), &mut updated_files)?;
+            }
+        }
+        (Some(_), None) => warnings.push(format!("No CI job template for {}; the CI workflow was not updated", component.language)),
+        (None, _) => {}
+    }
+
+    if request.install_dependencies {
+        if let Err(e) = install_dependencies(&project_path, std::slice::from_ref(&component)) {
+            warnings.push(format!("Dependency installation failed: {}", e));
+        }
+    }
+
+    let updated_files = updated_files
+        .iter()
+        .map(|p: &PathBuf| p.strip_prefix(&project_path).unwrap_or(p).to_string_lossy().to_string())
+        .collect();
+    Ok(AddComponentResult {
+        success: true,
+        project_path: request.project_path,
+        component_id: component.id.clone(),
+        message: format!("Component '{}' added at {}", component.name, component.location),
+        files_created,
+        updated_files,
+        warnings,
+    })
+}
+
+/// A component as CI sees it: its toolchain's install and test commands, then
+/// its image when the component has a Dockerfile
+fn component_ci_app(project_path: &Path, component: &ComponentGenerationConfig) -> Option<CiApp<'static>> {
+    let toolchain = ci_toolchain(&component.language)?;
+    Some(CiApp {
+        docker: project_path.join(&component.location).join("Dockerfile").exists(),
+        ..CiApp::new(&component.id, Some(&component.location), toolchain)
+    })
+}
+
+/// The workflow to extend and its provider, providers in `CI_PROVIDERS`
+/// order. In an Actions directory `ci.yml` comes first, then any workflow
+/// with jobs.
+fn ci_workflow(project_path: &Path) -> Option<(PathBuf, &'static CiProvider)> {
+    CI_PROVIDERS.iter().find_map(|provider| {
+        let path = project_path.join(provider.path);
+        if provider.is_gitlab() {
+            return path.is_file().then_some((path, provider));
+        }
+        let workflows = path.parent()?;
+        let named = ["ci.yml", "ci.yaml"].iter().map(|name| workflows.join(name)).find(|p| p.is_file());
+        let workflow = named.or_else(|| {
+            let mut candidates: Vec<PathBuf> = fs::read_dir(workflows)
+                .map(|entries| entries.flatten().map(|e| e.path()).collect())
+                .unwrap_or_default();
+            candidates.sort();
+            candidates.into_iter().find(|p| {
+                p.extension().is_some_and(|e| e == "yml" || e == "yaml")
+                    && fs::read_to_string(p).is_ok_and(|c| c.lines().any(|l| l.trim_end() == "jobs:"))
+            })
+        })?;
+        Some((workflow, provider))
+    })
+}
+
+/// GitLab's stages when a pipeline declares none
+const GITLAB_DEFAULT_STAGES: &[&str] = &["build", "test", "deploy"];
+
+/// Append the stages `jobs` run in to the pipeline's `stages:` list, writing
+/// the list out (block style, GitLab's defaults first when it was absent)
+/// only when one is missing; returns the resulting stages
+fn add_gitlab_stages(ci: &mut String, jobs: &[(String, Yaml)]) -> Vec<String> {
+    let (range, mut stages) = yaml_list(ci, "stages")
+        .unwrap_or_else(|| (0..0, GITLAB_DEFAULT_STAGES.iter().map(|s| s.to_string()).collect()));
+    let before = stages.len();
+    for stage in jobs.iter().filter_map(|(_, job)| job.get("stage").and_then(Yaml::as_str)) {
+        if !stages.iter().any(|s| s == stage) {
+            stages.push(stage.to_string());
+        }
+    }
+    if stages.len() > before {
+        let block = map([("stages", list(stages.iter().cloned().map(str)))]).to_yaml();
+        let mut lines: Vec<&str> = ci.lines().collect();
+        lines.splice(range, block.lines());
+        *ci = lines.join("\n") + "\n";
+    }
+    stages
+}
+
+/// Line range and items of a top-level YAML list, block (`- item`) or flow
+/// (`[a, b]`) style
+fn yaml_list(content: &str, key: &str) -> Option<(std::ops::Range<usize>, Vec<String>)> {
+    let lines: Vec<&str> = content.lines().collect();
+    let (start, inline) = lines.iter().enumerate().find_map(|(i, l)| Some((i, top_level_value(l, key)?.trim())))?;
+    let unquote = |item: &str| item.trim().trim_matches(|c| c == '"' || c == '\'').to_string();
+    if let Some(flow) = inline.strip_prefix('[').and_then(|f| f.strip_suffix(']')) {
+        let items = flow.split(',').map(unquote).filter(|s| !s.is_empty()).collect();
+        return Some((start..start + 1, items));
+    }
+    let end = yaml_section_end(content, key)?;
+    let items = lines[start + 1..end]
+        .iter()
+        .filter_map(|l| l.trim().strip_prefix('-'))
+        .map(|item| unquote(item.split(" #").next().unwrap_or_default()))
+        .collect();
+    Some((start..end, items))
+}
+
+/// Whatever follows a top-level key on its own line, quoted key or not
+/// (`"on": [push]`); empty when the value is a nested block
+fn yaml_scalar<'a>(content: &'a str, key: &str) -> Option<&'a str> {
+    content.lines().find_map(|line| top_level_value(line, key)).map(|value| value.trim())
+}
+
+fn top_level_value<'a>(line: &'a str, key: &str) -> Option<&'a str> {
+    [key.to_string(), format!("\"{}\"", key), format!("'{}'", key)]
+        .iter()
+        .find_map(|k| line.strip_prefix(k.as_str())?.strip_prefix(':'))
+}
+
+/// Line index just past the entries of a top-level YAML mapping key
+fn yaml_section_end(content: &str, key: &str) -> Option<usize> {
+    let lines: Vec<&str> = content.lines().collect();
+    let header = format!("{}:", key);
+    let start = lines.iter().position(|l| l.trim_end() == header)?;
+    let mut end = lines[start + 1..]
+        .iter()
+        .position(|l| !l.trim().is_empty() && !l.starts_with(char::is_whitespace) && !l.starts_with('#'))
+        .map(|i| start + 1 + i)
+        .unwrap_or(lines.len());
+    while end > start + 1 && lines[end - 1].trim().is_empty() {
+        end -= 1;
+    }
+    Some(end)
+}
+
+/// Replace the lines between `markers` with `section`, or insert it at line
+/// `insert_at` (the end of the file when `None`)
+fn upsert_section(content: &str, markers: &(String, String), section: &str, insert_at: Option<usize>) -> String {
+    let mut lines: Vec<&str> = content.lines().collect();
+    let begin = lines.iter().position(|l| l.trim() == markers.0);
+    let end = begin.and_then(|b| lines[b..].iter().position(|l| l.trim() == markers.1).map(|e| b + e));
+    match (begin, end) {
+        (Some(begin), Some(end)) => {
+            lines.splice(begin..=end, section.lines());
+        }
+        _ => {
+            let at = insert_at.unwrap_or(lines.len()).min(lines.len());
+            let mut inserted: Vec<&str> = section.lines().collect();
+            if at == lines.len() && lines.last().is_some_and(|l| !l.trim().is_empty()) && insert_at.is_none() {
+                inserted.insert(0, "");
+            }
+            lines.splice(at..at, inserted);
+        }
+    }
+    let mut updated = lines.join("\n");
+    updated.push('\n');
+    updated
+}
+
+fn write_root_file(path: &Path, content: &str, updated: &mut Vec<PathBuf>) -> Result<(), String> {
+    fs::write(path, content).map_err(|e| format!("Failed to update {}: {}", path.display(), e))?;
+    updated.push(path.to_path_buf());
+    Ok(())
+}
+
 // ============================================================================
 // COMPONENT GENERATION
 // ============================================================================
@@ -573,6 +901,11 @@ fn generate_root_files(
         .map_err(|e| format!("Failed to create .gitignore: {}", e))?;
     files_created += 1;
 
+    // Generate a CI workflow unless a component template already wrote one
+    if config.features.ci && ci_workflow(project_path).is_none() {
+        files_created += generate_ci_workflow(project_path, config)?;
+    }
+
     // Generate LICENSE if needed
     let license_content = generate_license();
     let license_path = project_path.join("LICENSE");
@@ -583,6 +916,21 @@ fn generate_root_files(
     Ok(files_created)
 }
 
+/// GitHub Actions workflow with a job per component CI has a toolchain for
+fn generate_ci_workflow(project_path: &Path, config: &ArchitecturePatternConfig) -> Result<usize, String> {
+    let apps: Vec<CiApp> = config.components.iter().filter_map(|c| component_ci_app(project_path, c)).collect();
+    let (Some(provider), false) = (ci_provider("github-actions"), apps.is_empty()) else {
+        return Ok(0);
+    };
+    let (relative, content) = ci_file(provider, &apps)?;
+    let path = project_path.join(relative);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    fs::write(&path, content).map_err(|e| format!("Failed to create CI workflow: {}", e))?;
+    Ok(1)
+}
+
 fn generate_readme(
     config: &ArchitecturePatternConfig,
     handlebars: &Handlebars,
@@ -736,42 +1084,28 @@ fn generate_gitignore(config: &ArchitecturePatternConfig) -> String {
     ];
 
     for component in &config.components {
-        match component.language.as_str() {
-            "typescript" | "javascript" => {
-                lines.extend(vec![
-                    format!("# {}", component.name),
-                    format!("{}/node_modules/", component.location),
-                    format!("{}/.svelte-kit/", component.location),
-                    format!("{}/build/", component.location),
-                    format!("{}/.env", component.location),
-                    "".to_string(),
-                ]);
-            }
-            "python" => {
-                lines.extend(vec![
-                    format!("# {}", component.name),
-                    format!("{}/venv/", component.location),
-                    format!("{}/__pycache__/", component.location),
-                    format!("{}/*.pyc", component.location),
-                    format!("{}/.env", component.location),
-                    "".to_string(),
-                ]);
-            }
-            "rust" => {
-                lines.extend(vec![
-                    format!("# {}", component.name),
-                    format!("{}/target/", component.location),
-                    format!("{}/Cargo.lock", component.location),
-                    "".to_string(),
-                ]);
-            }
-            _ => {}
+        let entries = gitignore_entries(component);
+        if !entries.is_empty() {
+            lines.push(format!("# {}", component.name));
+            lines.extend(entries);
+            lines.push("".to_string());
         }
     }
 
     lines.join("\n")
 }
 
+/// Ignored build output and local files for one component
+fn gitignore_entries(component: &ComponentGenerationConfig) -> Vec<String> {
+    let patterns: &[&str] = match component.language.as_str() {
+        "typescript" | "javascript" => &["node_modules/", ".svelte-kit/", "build/", ".env"],
+        "python" => &["venv/", "__pycache__/", "*.pyc", ".env"],
+        "rust" => &["target/", "Cargo.lock"],
+        _ => &[],
+    };
+    patterns.iter().map(|p| format!("{}/{}", component.location, p)).collect()
+}
+
 fn generate_license() -> String {
     r#"MIT License
 
@@ -1039,3 +1373,108 @@ fn install_go_dependencies(component_path: &Path) -> Result<(), String> {
 
     Ok(())
 }
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_component_updates_root_files_in_place() {
+        // The project name comes from the directory name
+        let dir = tempfile::Builder::new().prefix("shop-").tempdir().unwrap();
+        let project = dir.path();
+        fs::create_dir_all(project.join(".github/workflows")).unwrap();
+        fs::write(project.join("README.md"), "# shop\n").unwrap();
+        fs::write(project.join("docker-compose.yml"), "services:\n  web:\n    build: ./web\n\nvolumes:\n  data:\n").unwrap();
+        fs::write(project.join(".github/workflows/ci.yml"), "name: CI\non: [push]\njobs:\n  web:\n    runs-on: ubuntu-latest\n").unwrap();
+
+        let file = |path: &str, content: &str| FileDef {
+            path: path.to_string(),
+            content: content.to_string(),
+            template_engine: "handlebars".to_string(),
+            overwritable: false,
+        };
+        let request = AddComponentRequest {
+            project_path: project.to_string_lossy().to_string(),
+            component: ComponentGenerationConfig {
+                id: "worker".to_string(),
+                role: "backend".to_string(),
+                name: "Worker".to_string(),
+                language: "python".to_string(),
+                framework: "celery".to_string(),
+                location: "services/worker".to_string(),
+                scaffolding: ScaffoldingConfig {
+                    directories: vec![],
+                    files: vec![file("main.py", "# {{projectName}} worker\n"), file("Dockerfile", "FROM python:3.11\n")],
+                },
+                custom_config: None,
+            },
+            install_dependencies: false,
+        };
+        let result = add_component(request.clone(), &PatternCatalog::bundled()).unwrap();
+        let read = |path: &str| fs::read_to_string(project.join(path)).unwrap();
+
+        assert_eq!(result.files_created, 3);
+        assert!(read("services/worker/main.py").starts_with("# shop-"));
+        assert_eq!(result.updated_files.len(), 4);
+        assert!(read(".gitignore").contains("services/worker/__pycache__/"));
+        assert_eq!(
+            read("docker-compose.yml"),
+            "services:\n  web:\n    build: ./web\n  # vibeforge:worker\n  worker:\n    build: ./services/worker\n  # /vibeforge:worker\n\nvolumes:\n  data:\n"
+        );
+        let ci = read(".github/workflows/ci.yml");
+        assert!(ci.contains("  worker:\n    runs-on: ubuntu-latest"));
+        assert!(ci.contains("working-directory: services/worker"));
+        assert!(ci.contains("  worker-docker:\n    runs-on: ubuntu-latest\n    needs:\n      - worker\n"));
+        assert!(ci.ends_with("        run: docker build -t worker:ci services/worker\n  # /vibeforge:worker\n"));
+
+        // The location is taken now; the root sections are replaced, not duplicated
+        assert!(add_component(request.clone(), &PatternCatalog::bundled()).is_err());
+        let readme = read("README.md");
+        let markers = ("<!-- vibeforge:worker -->".to_string(), "<!-- /vibeforge:worker -->".to_string());
+        let again = upsert_section(&readme, &markers, "<!-- vibeforge:worker -->\n## Worker\n<!-- /vibeforge:worker -->", None);
+        assert_eq!(again, "# shop\n\n<!-- vibeforge:worker -->\n## Worker\n<!-- /vibeforge:worker -->\n");
+
+        // GitLab pipelines gain the stages the new jobs run in
+        fs::remove_dir_all(project.join(".github")).unwrap();
+        fs::write(project.join(".gitlab-ci.yml"), "stages:\n  - lint\n\nlint:\n  stage: lint\n  image: node:20\n  script:\n    - npm run lint\n").unwrap();
+        let mut mailer = request.clone();
+        mailer.component.id = "mailer".to_string();
+        mailer.component.location = "services/mailer".to_string();
+        let result = add_component(mailer, &PatternCatalog::bundled()).unwrap();
+        let ci = read(".gitlab-ci.yml");
+        assert!(result.warnings.is_empty());
+        assert!(ci.starts_with("stages:\n  - lint\n  - test\n  - docker\n\nlint:\n  stage: lint\n"));
+        assert!(ci.contains("# vibeforge:mailer\nmailer:\n  stage: test\n"));
+        assert!(ci.contains("mailer-docker:\n  stage: docker\n"));
+        assert_eq!(yaml_list("stages: [build, 'test']\n", "stages"), Some((0..1, vec!["build".to_string(), "test".to_string()])));
+
+        // A workflow the merged jobs can't be checked against is left alone
+        fs::remove_file(project.join(".gitlab-ci.yml")).unwrap();
+        fs::create_dir_all(project.join(".github/workflows")).unwrap();
+        fs::write(project.join(".github/workflows/ci.yml"), "name: CI\njobs:\n  web:\n    runs-on: ubuntu-latest\n").unwrap();
+        let mut notifier = request.clone();
+        notifier.component.id = "notifier".to_string();
+        notifier.component.location = "services/notifier".to_string();
+        let result = add_component(notifier, &PatternCatalog::bundled()).unwrap();
+        assert_eq!(result.warnings, vec!["The CI workflow was not updated: .github/workflows/ci.yml: missing required key 'on'"]);
+        assert!(!read(".github/workflows/ci.yml").contains("notifier"));
+
+        // Forgejo workflows get jobs with fully qualified action references
+        fs::remove_dir_all(project.join(".github")).unwrap();
+        fs::create_dir_all(project.join(".forgejo/workflows")).unwrap();
+        fs::write(project.join(".forgejo/workflows/build.yml"), "name: CI\non: [push]\njobs:\n  web:\n    runs-on: docker\n").unwrap();
+        let mut indexer = request;
+        indexer.component.id = "indexer".to_string();
+        indexer.component.location = "services/indexer".to_string();
+        let result = add_component(indexer, &PatternCatalog::bundled()).unwrap();
+        assert!(result.warnings.is_empty());
+        let ci = read(".forgejo/workflows/build.yml");
+        assert!(ci.contains("  indexer:\n"));
+        assert!(ci.contains("uses: https://github.com/actions/checkout@"));
+    }
+}
@@ -0,0 +1,606 @@
+//! Profile Mapper - Turn an analyzed codebase into generator input
+//!
+//! Maps a `ProjectProfile` from the code analyzer onto a `ProjectConfig` for
+//! the stack generator and an `ArchitecturePatternConfig` for the pattern
+//! generator, recording where every value came from so the UI can show which
+//! fields were detected and which are defaults the user should review.
+
+use crate::code_analyzer::{DetectedFramework, ProjectProfile};
+use crate::pattern_catalog::{PatternCatalog, PatternDefinition};
+use crate::pattern_generator::{ArchitecturePatternConfig, ComponentGenerationConfig, FeatureFlags, ScaffoldingConfig};
+use crate::ci_scaffold::ci_provider;
+use crate::project_generator::{stack_definition, ProjectConfig};
+use crate::runtime_detector::{PatternRecommendation, PatternRecommender};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+
+// ============================================================================
+// TYPES
+// ============================================================================
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileConfigMapping {
+    pub project_config: ProjectConfig,
+    pub pattern_config: ArchitecturePatternConfig,
+    pub provenance: Vec<FieldProvenance>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldProvenance {
+    pub field: String,  // e.g. "database", "features.docker", "components.backend"
+    pub source: String, // "detected", "inferred", "default"
+    pub confidence: Option<f64>,
+    pub evidence: Vec<String>,
+}
+
+impl FieldProvenance {
+    fn new(field: &str, source: &str, confidence: Option<f64>, evidence: Vec<String>) -> Self {
+        FieldProvenance {
+            field: field.to_string(),
+            source: source.to_string(),
+            confidence,
+            evidence,
+        }
+    }
+}
+
+// ============================================================================
+// MAPPING
+// ============================================================================
+
+/// Map a profile onto generator input; the pattern is the one `catalog`
+/// recommends for the profile
+pub fn profile_to_config(profile: &ProjectProfile, catalog: &PatternCatalog) -> ProfileConfigMapping {
+    let mut provenance = Vec::new();
+    let mut warnings = Vec::new();
+
+    provenance.push(FieldProvenance::new(
+        "name",
+        "detected",
+        None,
+        vec![format!("directory name of {}", profile.project_path)],
+    ));
+
+    let description = describe(profile);
+    provenance.push(FieldProvenance::new(
+        "description",
+        "inferred",
+        None,
+        vec!["summary of detected frameworks, languages and database".to_string()],
+    ));
+
+    let project_type = project_type(profile);
+    provenance.push(FieldProvenance::new(
+        "project_type",
+        "inferred",
+        None,
+        vec![format!(
+            "frontend: {}, backend: {}, mobile: {}",
+            profile.has_frontend, profile.has_backend, profile.has_mobile
+        )],
+    ));
+
+    // Top three languages by confidence, matching the wizard's language ids
+    let mut languages: Vec<_> = profile.languages.iter().filter(|l| l.id != "unknown").collect();
+    languages.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+    let languages: Vec<_> = languages.into_iter().take(3).collect();
+    provenance.push(FieldProvenance::new(
+        "languages",
+        "detected",
+        languages.first().map(|l| l.confidence),
+        languages
+            .iter()
+            .map(|l| format!("{} ({} files, {} lines)", l.name, l.files.len(), l.line_count))
+            .collect(),
+    ));
+    let languages: Vec<String> = languages.into_iter().map(|l| l.id.clone()).collect();
+
+    let stack_id = match &profile.suggested_stack_id {
+        Some(stack_id) => {
+            provenance.push(FieldProvenance::new(
+                "stack_id",
+                "detected",
+                profile.stack_match_confidence,
+                profile
+                    .stack_matches
+                    .iter()
+                    .find(|m| m.stack_id == *stack_id)
+                    .map(|m| m.matched.clone())
+                    .unwrap_or_default(),
+            ));
+            stack_id.clone()
+        }
+        None => {
+            warnings.push("No known stack matched this project; choose a stack before generating".to_string());
+            provenance.push(FieldProvenance::new("stack_id", "default", None, Vec::new()));
+            String::new()
+        }
+    };
+
+    let detected_database = profile.data_stores.iter().find(|s| s.kind == "database");
+    if profile.data_stores.iter().filter(|s| s.kind == "database").count() > 1 {
+        warnings.push(format!(
+            "Multiple databases detected; using {} for generation",
+            detected_database.map(|d| d.store_type.as_str()).unwrap_or_default()
+        ));
+    }
+    // The generator rejects databases the stack has no data layer for
+    let database = match (detected_database, stack_definition(&stack_id)) {
+        (Some(store), Some(stack)) if !stack.databases.contains(&store.store_type.as_str()) => {
+            let fallback = stack.databases.first().map(|db| db.to_string());
+            warnings.push(format!(
+                "{} does not support the detected {} database; using {} instead",
+                stack.name,
+                store.store_type,
+                fallback.as_deref().unwrap_or("no database")
+            ));
+            provenance.push(FieldProvenance::new(
+                "database",
+                "default",
+                None,
+                vec![format!("{} detected, not supported by {}", store.store_type, stack.name)],
+            ));
+            fallback
+        }
+        (Some(store), _) => {
+            provenance.push(FieldProvenance::new(
+                "database",
+                "detected",
+                Some(store.confidence),
+                store.indicators.clone(),
+            ));
+            Some(store.store_type.clone())
+        }
+        (None, _) => {
+            provenance.push(FieldProvenance::new("database", "default", None, Vec::new()));
+            None
+        }
+    };
+
+    match &profile.authentication {
+        Some(auth) => provenance.push(FieldProvenance::new(
+            "authentication",
+            "detected",
+            Some(auth.confidence),
+            auth.indicators.clone(),
+        )),
+        None => provenance.push(FieldProvenance::new("authentication", "default", None, Vec::new())),
+    }
+
+    match profile.infrastructure.deployment_platforms.first() {
+        Some(platform) => provenance.push(FieldProvenance::new(
+            "deployment_platform",
+            "detected",
+            Some(platform.confidence),
+            platform.indicators.clone(),
+        )),
+        None => provenance.push(FieldProvenance::new("deployment_platform", "default", None, Vec::new())),
+    }
+
+    provenance.push(FieldProvenance::new(
+        "environment_variables",
+        "default",
+        None,
+        vec!["values are never copied from the analyzed project".to_string()],
+    ));
+
+    let features = feature_flags(profile, &mut provenance);
+    let mut feature_names = Vec::new();
+    for (name, enabled) in [
+        ("testing", features.testing),
+        ("linting", features.linting),
+        ("git", features.git),
+        ("docker", features.docker),
+        ("ci", features.ci),
+    ] {
+        if enabled {
+            feature_names.push(name.to_string());
+        }
+    }
+
+    let project_config = ProjectConfig {
+        name: profile.project_name.clone(),
+        description: description.clone(),
+        project_type,
+        languages,
+        stack_id,
+        database,
+        authentication: profile.authentication.as_ref().map(|a| a.method.clone()),
+        deployment_platform: profile.suggested_deployment_platform.clone(),
+        environment_variables: HashMap::new(),
+        features: feature_names,
+        modules: Vec::new(),
+        // The first detected provider the generator can write workflows for
+        ci_provider: profile.ci.providers.iter().find(|p| ci_provider(p).is_some()).cloned(),
+    };
+
+    let components = components(profile, &mut provenance);
+    if components.is_empty() {
+        warnings.push("No frontend or backend frameworks detected; the pattern has no components".to_string());
+    }
+
+    let (pattern_id, pattern_name) = match pattern_for(profile, catalog) {
+        Some((pattern, recommendation)) => {
+            if !recommendation.recommended {
+                warnings.push(format!(
+                    "No catalog pattern fits this project; {} scored highest, review the pattern before generating",
+                    pattern.name
+                ));
+            }
+            provenance.push(FieldProvenance::new(
+                "pattern_id",
+                "inferred",
+                Some(f64::from(recommendation.score) / 100.0),
+                recommendation.reasons,
+            ));
+            (pattern.id.clone(), pattern.name.clone())
+        }
+        None => {
+            warnings.push("The pattern catalog is empty; choose a pattern before generating".to_string());
+            provenance.push(FieldProvenance::new("pattern_id", "default", None, Vec::new()));
+            (String::new(), String::new())
+        }
+    };
+
+    let pattern_config = ArchitecturePatternConfig {
+        pattern_id,
+        pattern_name,
+        project_name: profile.project_name.clone(),
+        project_description: description,
+        // The output location is chosen by the user, never the analyzed project itself
+        project_path: String::new(),
+        components,
+        features,
+    };
+
+    ProfileConfigMapping {
+        project_config,
+        pattern_config,
+        provenance,
+        warnings,
+    }
+}
+
+fn describe(profile: &ProjectProfile) -> String {
+    let mut parts = Vec::new();
+
+    if profile.has_frontend && profile.has_backend {
+        parts.push("Full-stack application".to_string());
+    } else if profile.has_frontend {
+        parts.push("Frontend application".to_string());
+    } else if profile.has_backend {
+        parts.push("Backend service".to_string());
+    } else if profile.has_mobile {
+        parts.push("Mobile application".to_string());
+    }
+
+    if let Some(framework) = profile.frameworks.first() {
+        parts.push(format!("built with {}", framework.name));
+    }
+
+    if let Some(language) = profile.languages.iter().find(|l| l.id == profile.primary_language) {
+        parts.push(format!("using {}", language.name));
+    }
+
+    if let Some(database) = profile.data_stores.iter().find(|s| s.kind == "database") {
+        parts.push(format!("with {} database", database.store_type));
+    }
+
+    if parts.is_empty() {
+        "Existing codebase".to_string()
+    } else {
+        parts.join(" ")
+    }
+}
+
+fn project_type(profile: &ProjectProfile) -> String {
+    const AI_DEPENDENCIES: &[&str] = &["openai", "langchain", "tensorflow", "torch"];
+
+    if profile.has_mobile {
+        "mobile"
+    } else if profile.has_frontend {
+        "web"
+    } else if profile.has_backend {
+        let has_ai = profile
+            .dependencies
+            .iter()
+            .any(|d| AI_DEPENDENCIES.iter().any(|ai| d.name.contains(ai)));
+        if has_ai {
+            "ai"
+        } else {
+            "api"
+        }
+    } else {
+        "cli"
+    }
+    .to_string()
+}
+
+fn feature_flags(profile: &ProjectProfile, provenance: &mut Vec<FieldProvenance>) -> FeatureFlags {
+    let lint_jobs: Vec<String> = profile
+        .ci
+        .workflows
+        .iter()
+        .flat_map(|w| w.jobs.iter().filter(|j| j.stages.iter().any(|s| s == "lint")).map(move |j| format!("{} job in {}", j.name, w.file)))
+        .collect();
+    let lint_dependencies: Vec<String> = profile
+        .dependencies
+        .iter()
+        .filter(|d| matches!(d.name.as_str(), "eslint" | "prettier" | "ruff" | "flake8" | "black" | "@biomejs/biome"))
+        .map(|d| format!("{} in {}", d.name, d.source))
+        .collect();
+    let linting_evidence: Vec<String> = lint_jobs.into_iter().chain(lint_dependencies).collect();
+
+    let workflow_files: Vec<String> = profile.ci.workflows.iter().map(|w| w.file.clone()).collect();
+    let docker_files: Vec<String> = profile
+        .infrastructure
+        .docker_images
+        .iter()
+        .map(|i| i.file.clone())
+        .collect();
+
+    let flags = FeatureFlags {
+        testing: profile.has_tests,
+        linting: !linting_evidence.is_empty(),
+        git: true,
+        docker: profile.has_docker,
+        ci: profile.has_ci,
+    };
+
+    let source = |enabled: bool| if enabled { "detected" } else { "default" };
+    provenance.push(FieldProvenance::new("features.testing", source(flags.testing), None, Vec::new()));
+    provenance.push(FieldProvenance::new("features.linting", source(flags.linting), None, linting_evidence));
+    provenance.push(FieldProvenance::new("features.git", "default", None, Vec::new()));
+    provenance.push(FieldProvenance::new("features.docker", source(flags.docker), None, docker_files));
+    provenance.push(FieldProvenance::new("features.ci", source(flags.ci), None, workflow_files));
+
+    flags
+}
+
+/// Directory holding the manifest a framework was found in, relative to the project root
+fn framework_location(framework: &DetectedFramework) -> Option<String> {
+    framework.sources.iter().find(|s| s.as_str() != "imports").map(|source| {
+        match source.rsplit_once('/') {
+            Some((dir, _)) => dir.to_string(),
+            None => ".".to_string(),
+        }
+    })
+}
+
+/// Component language id used by the pattern generator
+fn component_language(framework: &DetectedFramework, profile: &ProjectProfile) -> String {
+    let manifest = framework.sources.iter().find_map(|s| s.rsplit('/').next());
+    match manifest {
+        Some("package.json") => "typescript",
+        Some("requirements.txt" | "pyproject.toml") => "python",
+        Some("Cargo.toml") => "rust",
+        Some("go.mod") => "go",
+        Some("composer.json") => "php",
+        _ => match profile.primary_language.as_str() {
+            "javascript-typescript" | "svelte" => "typescript",
+            other => other,
+        },
+    }
+    .to_string()
+}
+
+/// Lowercase slug used for component frameworks, e.g. "Next.js" -> "nextjs"
+fn framework_slug(name: &str) -> String {
+    name.chars().filter(|c| c.is_ascii_alphanumeric()).collect::<String>().to_lowercase()
+}
+
+fn components(profile: &ProjectProfile, provenance: &mut Vec<FieldProvenance>) -> Vec<ComponentGenerationConfig> {
+    let mut components: Vec<ComponentGenerationConfig> = Vec::new();
+
+    for framework in &profile.frameworks {
+        let role = match framework.category.as_str() {
+            "frontend" | "fullstack" => "frontend",
+            "backend" => "backend",
+            _ => continue,
+        };
+        // One component per role and location; the strongest framework wins
+        let location = framework_location(framework).unwrap_or_else(|| ".".to_string());
+        if components.iter().any(|c| c.role == role && c.location == location) {
+            continue;
+        }
+
+        let id = if components.iter().any(|c| c.id == role) {
+            format!("{}-{}", role, components.len() + 1)
+        } else {
+            role.to_string()
+        };
+        provenance.push(FieldProvenance::new(
+            &format!("components.{}", id),
+            "detected",
+            Some(framework.confidence),
+            framework.indicators.clone(),
+        ));
+        components.push(ComponentGenerationConfig {
+            id,
+            role: role.to_string(),
+            name: framework.name.clone(),
+            language: component_language(framework, profile),
+            framework: framework_slug(&framework.name),
+            location,
+            scaffolding: ScaffoldingConfig {
+                directories: Vec::new(),
+                files: Vec::new(),
+            },
+            custom_config: None,
+        });
+    }
+
+    if let Some(database) = profile.data_stores.iter().find(|s| s.kind == "database") {
+        provenance.push(FieldProvenance::new(
+            "components.database",
+            "detected",
+            Some(database.confidence),
+            database.indicators.clone(),
+        ));
+        let migrations = profile.migrations.first();
+        components.push(ComponentGenerationConfig {
+            id: "database".to_string(),
+            role: "database".to_string(),
+            name: "Database".to_string(),
+            language: "sql".to_string(),
+            framework: database.store_type.clone(),
+            location: migrations
+                .and_then(|m| m.path.rsplit_once('/').map(|(dir, _)| dir.to_string()))
+                .unwrap_or_else(|| "database".to_string()),
+            scaffolding: ScaffoldingConfig {
+                directories: Vec::new(),
+                files: Vec::new(),
+            },
+            custom_config: None,
+        });
+    }
+
+    components
+}
+
+/// The catalog pattern ranked first for the profile, with its recommendation
+fn pattern_for<'a>(
+    profile: &ProjectProfile,
+    catalog: &'a PatternCatalog,
+) -> Option<(&'a PatternDefinition, PatternRecommendation)> {
+    let recommender = PatternRecommender::with_catalog(catalog.clone());
+    let recommendations = recommender.recommend_fields(&profile_fields(profile));
+    let best = recommender.adjust_for_architecture(recommendations, &profile.architecture).into_iter().next()?;
+    Some((catalog.get(&best.pattern_id)?, best))
+}
+
+/// The profile in the shape of `stack_fields`, the fields catalog criteria refer to
+fn profile_fields(profile: &ProjectProfile) -> serde_json::Value {
+    let framework = |categories: &[&str]| {
+        profile
+            .frameworks
+            .iter()
+            .find(|f| categories.contains(&f.category.as_str()))
+            .map(|f| framework_slug(&f.name))
+    };
+    let desktop_framework = profile.dependencies.iter().find_map(|d| match d.name.as_str() {
+        "tauri" | "@tauri-apps/api" => Some("tauri"),
+        "electron" => Some("electron"),
+        _ => None,
+    });
+    let mut databases: Vec<&str> =
+        profile.data_stores.iter().filter(|s| s.kind == "database").map(|s| s.store_type.as_str()).collect();
+    if databases.is_empty() {
+        databases.push("none");
+    }
+    let has_root_file = |prefix: &str| profile.root_files.iter().any(|f| f.starts_with(prefix));
+
+    json!({
+        "primary_language": match profile.primary_language.as_str() {
+            "javascript-typescript" => "typescript",
+            other => other,
+        },
+        "frontend_framework": framework(&["frontend", "fullstack"]),
+        "backend_framework": framework(&["backend"]),
+        "desktop_framework": desktop_framework,
+        "databases": databases,
+        "build_tools": if has_root_file("package.json") { vec!["npm"] } else { Vec::new() },
+        "config_files": {
+            "vite_config": has_root_file("vite.config."),
+            "docker_compose": has_root_file("docker-compose.") || has_root_file("compose."),
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile() -> ProjectProfile {
+        serde_json::from_value(serde_json::json!({
+            "projectName": "shop",
+            "projectPath": "/work/shop",
+            "rootFiles": [],
+            "languages": [
+                { "id": "python", "name": "Python", "confidence": 0.6, "files": ["api/main.py"], "line_count": 300 },
+                { "id": "javascript-typescript", "name": "JavaScript/TypeScript", "confidence": 0.9, "files": ["web/src/app.ts"], "line_count": 900 }
+            ],
+            "primaryLanguage": "javascript-typescript",
+            "frameworks": [
+                { "name": "SvelteKit", "version": "2.0.0", "category": "fullstack", "confidence": 0.9, "indicators": ["@sveltejs/kit dependency"], "sources": ["web/package.json"] },
+                { "name": "FastAPI", "version": null, "category": "backend", "confidence": 0.9, "indicators": ["fastapi dependency"], "sources": ["api/requirements.txt"] },
+                { "name": "TailwindCSS", "version": null, "category": "styling", "confidence": 0.9, "indicators": [], "sources": ["web/package.json"] }
+            ],
+            "dependencies": [],
+            "hasBackend": true,
+            "hasFrontend": true,
+            "hasMobile": false,
+            "hasTests": true,
+            "testing": { "frameworks": [], "testDirectories": [], "fileCounts": [], "coverageReports": [] },
+            "dataStores": [
+                { "kind": "database", "type": "postgresql", "category": "sql", "confidence": 0.7, "indicators": ["image: postgres: in docker-compose.yml"], "sources": ["docker-compose.yml"] }
+            ],
+            "orms": [],
+            "migrations": [],
+            "authenticationMethods": [],
+            "hasDocker": true,
+            "hasCI": false,
+            "ci": { "providers": [], "workflows": [], "stages": [] },
+            "infrastructure": { "iac": [], "dockerImages": [], "platformFiles": [], "deploymentPlatforms": [] },
+            "architecture": { "entryPoints": [], "routes": [], "components": [], "connections": [], "diagram": "" },
+            "importGraphs": [],
+            "findings": [],
+            "suggestedStackId": null,
+            "stackMatches": []
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_project_config_from_profile() {
+        let mapping = profile_to_config(&profile(), &PatternCatalog::bundled());
+        let config = &mapping.project_config;
+
+        assert_eq!(config.project_type, "web");
+        assert_eq!(config.languages, vec!["javascript-typescript", "python"]);
+        assert_eq!(config.database.as_deref(), Some("postgresql"));
+        assert_eq!(config.features, vec!["testing", "git", "docker"]);
+        assert!(config.stack_id.is_empty());
+        assert_eq!(mapping.warnings.len(), 1);
+
+        let database = mapping.provenance.iter().find(|p| p.field == "database").unwrap();
+        assert_eq!(database.source, "detected");
+        assert_eq!(database.confidence, Some(0.7));
+
+        // Databases the suggested stack can't generate fall back to one it can
+        let mut mobile = profile();
+        mobile.suggested_stack_id = Some("react-native-expo".to_string());
+        let mapping = profile_to_config(&mobile, &PatternCatalog::bundled());
+        assert_eq!(mapping.project_config.database.as_deref(), Some("sqlite"));
+        assert!(mapping.warnings.iter().any(|w| w.contains("does not support the detected postgresql database")));
+        let database = mapping.provenance.iter().find(|p| p.field == "database").unwrap();
+        assert_eq!(database.source, "default");
+    }
+
+    #[test]
+    fn test_components_follow_framework_manifests() {
+        let mapping = profile_to_config(&profile(), &PatternCatalog::bundled());
+        let pattern = &mapping.pattern_config;
+
+        assert_eq!(pattern.pattern_id, "fullstack-web");
+        assert_eq!(pattern.pattern_name, "Full-Stack Web Application");
+        let provenance = mapping.provenance.iter().find(|p| p.field == "pattern_id").unwrap();
+        assert_eq!(provenance.confidence, Some(0.8));
+        let components: Vec<(&str, &str, &str, &str)> = pattern
+            .components
+            .iter()
+            .map(|c| (c.role.as_str(), c.framework.as_str(), c.language.as_str(), c.location.as_str()))
+            .collect();
+        assert_eq!(
+            components,
+            vec![
+                ("frontend", "sveltekit", "typescript", "web"),
+                ("backend", "fastapi", "python", "api"),
+                ("database", "postgresql", "sql", "database"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,46 @@
+//! Relative Path - Helpers for project-relative file paths
+//!
+//! Scans record every file relative to the project root with `/` separators on
+//! all platforms, so detectors compare and combine them as plain strings rather
+//! than going through `Path`.
+
+/// Last segment of a relative path
+pub fn file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+/// Parent directory of a relative path, `""` for the project root
+pub fn parent_dir(path: &str) -> &str {
+    path.rsplit_once('/').map_or("", |(dir, _)| dir)
+}
+
+/// Join `/`-separated segments onto a directory, resolving `.` and `..` in either
+pub fn join_path(dir: &str, relative: &str) -> String {
+    let mut parts: Vec<&str> = Vec::new();
+    for part in dir.split('/').chain(relative.split('/')) {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            part => parts.push(part),
+        }
+    }
+    parts.join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relative_path_helpers() {
+        assert_eq!(file_name("src/lib/db.ts"), "db.ts");
+        assert_eq!(file_name("README.md"), "README.md");
+        assert_eq!(parent_dir("src/lib/db.ts"), "src/lib");
+        assert_eq!(parent_dir("README.md"), "");
+        assert_eq!(join_path("", "package.json"), "package.json");
+        assert_eq!(join_path(".", "web/"), "web");
+        assert_eq!(join_path("apps/web", "../api/./main.py"), "apps/api/main.py");
+    }
+}
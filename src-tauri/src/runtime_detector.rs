@@ -5,8 +5,14 @@
  * and recommend architecture patterns.
  */
 
+use crate::analysis_progress::{AnalysisProgress, CANCELLED_ERROR};
+use crate::architecture::{self, ArchitectureProfile};
+use crate::detection_rules::{glob_to_regex, read_small_file};
+use crate::pattern_catalog::{stack_fields, PatternCatalog, PatternDefinition};
+use crate::relative_path::{file_name, join_path, parent_dir};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::time::Instant;
@@ -28,9 +34,23 @@ pub struct TechnologyStack {
     pub structure: String,
     pub package_files: PackageFiles,
     pub config_files: ConfigFiles,
+    pub directories: Vec<DirectoryStack>,
+    pub technology_confidence: Vec<TechnologyConfidence>, // highest first
     pub confidence: u8,
 }
 
+/// Technologies attributed to one directory that holds a manifest or framework config
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DirectoryStack {
+    pub path: String, // relative to the project root, "." for the root itself
+    pub manifests: Vec<String>,
+    pub languages: Vec<String>, // most files first
+    pub frontend_framework: Option<String>,
+    pub backend_framework: Option<String>,
+    pub desktop_framework: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PackageFiles {
@@ -56,9 +76,24 @@ pub struct ConfigFiles {
 pub struct PatternRecommendation {
     pub pattern_id: String,
     pub score: u8,
+    /// Required criteria hold and the score reaches the minimum
+    pub recommended: bool,
     pub reasons: Vec<String>,
     pub warnings: Vec<String>,
     pub confidence: String,
+    pub breakdown: Vec<CriterionScore>,
+}
+
+/// How one catalog criterion contributed to a pattern's score
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CriterionScore {
+    pub description: String, // the reason when matched, otherwise what was expected
+    pub field: String,
+    pub weight: u8,
+    pub required: bool,
+    pub matched: bool,
+    pub evidence: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -66,6 +101,7 @@ pub struct PatternRecommendation {
 pub struct RecommendationResult {
     pub tech_stack: TechnologyStack,
     pub recommendations: Vec<PatternRecommendation>,
+    pub architecture: ArchitectureProfile,
     pub metadata: AnalysisMetadata,
 }
 
@@ -89,6 +125,9 @@ pub struct RuntimeAnalysisOptions {
     pub analyze_dependencies: bool,
     #[serde(default = "default_true")]
     pub read_package_files: bool,
+    /// Patterns scoring below this are reported but not recommended
+    #[serde(default)]
+    pub min_recommendation_score: u8,
 }
 
 fn default_max_depth() -> usize {
@@ -103,102 +142,266 @@ fn default_true() -> bool {
 // FILE INDICATORS
 // ============================================================================
 
-struct FileIndicator {
-    pattern: &'static str,
-    technology: &'static str,
-    weight: u8,
+const BUNDLED_INDICATORS: &str = include_str!("../rules/file-indicators.json");
+
+/// File name looked up in `<project>/.vibeforge/` and the app config dir
+pub const USER_INDICATORS_FILE: &str = "file-indicators.json";
+
+/// Upper bound on files read per content check
+const MAX_CONTENT_FILES: usize = 20;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileIndicatorSet {
+    #[serde(default)]
+    pub indicators: Vec<FileIndicator>,
+}
+
+/// A file whose presence (and optionally content) speaks for or against a technology
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileIndicator {
+    pub pattern: String, // glob over project-relative paths
+    pub technology: String,
+    pub weight: u8, // 1-10
+    /// Regex the matched file must contain
+    #[serde(default)]
+    pub contains: Option<String>,
+    /// A match lowers the technology's confidence instead of raising it
+    #[serde(default)]
+    pub negative: bool,
+}
+
+impl FileIndicatorSet {
+    pub fn bundled() -> Self {
+        serde_json::from_str(BUNDLED_INDICATORS).expect("bundled file indicators are valid JSON")
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read indicators {}: {}", path.display(), e))?;
+        serde_json::from_str(&content)
+            .map_err(|e| format!("Invalid file indicators in {}: {}", path.display(), e))
+    }
+
+    /// Merge another set; an indicator with the same pattern, technology and
+    /// polarity replaces the original, so a weight of 0 disables it.
+    pub fn merge(&mut self, other: FileIndicatorSet) {
+        for indicator in other.indicators {
+            match self.indicators.iter_mut().find(|i| {
+                i.pattern == indicator.pattern && i.technology == indicator.technology && i.negative == indicator.negative
+            }) {
+                Some(existing) => *existing = indicator,
+                None => self.indicators.push(indicator),
+            }
+        }
+    }
+
+    /// Merge an indicator file if it exists. Returns whether the file was found.
+    pub fn merge_file(&mut self, path: &Path) -> Result<bool, String> {
+        if !path.is_file() {
+            return Ok(false);
+        }
+        self.merge(Self::load(path)?);
+        Ok(true)
+    }
+}
+
+/// Confidence in one technology from the indicators that matched
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TechnologyConfidence {
+    pub technology: String,
+    pub confidence: u8, // 0-100
+    pub indicators: Vec<String>,
 }
 
-const FILE_INDICATORS: &[FileIndicator] = &[
+// ============================================================================
+// FRAMEWORK INDICATORS
+// ============================================================================
+
+/// A dependency declared in a manifest that identifies a framework
+struct FrameworkIndicator {
+    manifest: &'static str,
+    dependency: &'static str,
+    framework: &'static str,
+    slot: &'static str, // "frontend", "backend", "desktop" (desktop or mobile)
+}
+
+const fn framework(
+    manifest: &'static str,
+    dependency: &'static str,
+    framework: &'static str,
+    slot: &'static str,
+) -> FrameworkIndicator {
+    FrameworkIndicator {
+        manifest,
+        dependency,
+        framework,
+        slot,
+    }
+}
+
+/// First match wins per slot, so meta-frameworks come before the libraries they build on
+const FRAMEWORK_INDICATORS: &[FrameworkIndicator] = &[
     // JavaScript/TypeScript
-    FileIndicator {
-        pattern: "package.json",
-        technology: "javascript",
-        weight: 10,
-    },
-    FileIndicator {
-        pattern: "tsconfig.json",
-        technology: "typescript",
-        weight: 9,
-    },
-    // Python
-    FileIndicator {
-        pattern: "requirements.txt",
-        technology: "python",
-        weight: 9,
-    },
-    FileIndicator {
-        pattern: "pyproject.toml",
-        technology: "python",
-        weight: 9,
-    },
-    FileIndicator {
-        pattern: "poetry.lock",
-        technology: "python",
-        weight: 8,
-    },
+    framework("package.json", "next", "nextjs", "frontend"),
+    framework("package.json", "nuxt", "nuxt", "frontend"),
+    framework("package.json", "@angular/core", "angular", "frontend"),
+    framework("package.json", "solid-js", "solid", "frontend"),
+    framework("package.json", "vue", "vue", "frontend"),
+    framework("package.json", "react", "react", "frontend"),
+    framework("package.json", "express", "express", "backend"),
+    framework("package.json", "react-native", "react-native", "desktop"),
+    framework("package.json", "electron", "electron", "desktop"),
     // Rust
-    FileIndicator {
-        pattern: "Cargo.toml",
-        technology: "rust",
-        weight: 10,
-    },
-    FileIndicator {
-        pattern: "Cargo.lock",
-        technology: "rust",
-        weight: 8,
-    },
+    framework("Cargo.toml", "axum", "axum", "backend"),
+    framework("Cargo.toml", "actix-web", "actix", "backend"),
+    framework("Cargo.toml", "rocket", "rocket", "backend"),
     // Go
-    FileIndicator {
-        pattern: "go.mod",
-        technology: "go",
-        weight: 10,
-    },
-    // Frameworks
-    FileIndicator {
-        pattern: "svelte.config.js",
-        technology: "svelte",
-        weight: 10,
-    },
-    FileIndicator {
-        pattern: "vite.config.ts",
-        technology: "vite",
-        weight: 8,
-    },
-    FileIndicator {
-        pattern: "src-tauri",
-        technology: "tauri",
-        weight: 10,
-    },
-    FileIndicator {
-        pattern: "tauri.conf.json",
-        technology: "tauri",
-        weight: 10,
-    },
-    // Databases
-    FileIndicator {
-        pattern: "docker-compose.yml",
-        technology: "docker",
-        weight: 7,
-    },
-    FileIndicator {
-        pattern: "Dockerfile",
-        technology: "docker",
-        weight: 6,
-    },
+    framework("go.mod", "github.com/gin-gonic/gin", "gin", "backend"),
+    framework("go.mod", "github.com/labstack/echo", "echo", "backend"),
+    framework("go.mod", "github.com/gofiber/fiber", "fiber", "backend"),
+    // Java
+    framework("pom.xml", "org.springframework.boot", "spring-boot", "backend"),
+    framework("build.gradle", "spring-boot", "spring-boot", "backend"),
+    framework("build.gradle.kts", "spring-boot", "spring-boot", "backend"),
+    // PHP
+    framework("composer.json", "laravel/framework", "laravel", "backend"),
+    framework("composer.json", "symfony/framework-bundle", "symfony", "backend"),
+    // Python
+    framework("requirements.txt", "fastapi", "fastapi", "backend"),
+    framework("requirements.txt", "flask", "flask", "backend"),
+    framework("requirements.txt", "django", "django", "backend"),
+    framework("pyproject.toml", "fastapi", "fastapi", "backend"),
+    framework("pyproject.toml", "flask", "flask", "backend"),
+    framework("pyproject.toml", "django", "django", "backend"),
+    // Dart
+    framework("pubspec.yaml", "sdk: flutter", "flutter", "desktop"),
 ];
 
+/// Whether a manifest declares `dependency`, using the manifest's own syntax
+fn declares_dependency(manifest: &str, content: &str, dependency: &str) -> bool {
+    match manifest {
+        "package.json" | "composer.json" => content.contains(&format!("\"{}\"", dependency)),
+        "Cargo.toml" => content.lines().map(str::trim).any(|line| {
+            line.strip_prefix(dependency)
+                .is_some_and(|rest| rest.starts_with([' ', '=', '.']))
+                || line == format!("[dependencies.{}]", dependency)
+        }),
+        // Module paths, which gain a `/vN` suffix from major version 2 on
+        "go.mod" => content.split_whitespace().any(|token| {
+            token.strip_prefix(dependency).is_some_and(|rest| {
+                rest.is_empty()
+                    || rest.strip_prefix("/v").is_some_and(|v| !v.is_empty() && v.bytes().all(|b| b.is_ascii_digit()))
+            })
+        }),
+        "pom.xml" => ["groupId", "artifactId"]
+            .iter()
+            .any(|tag| content.contains(&format!("<{0}>{1}</{0}>", tag, dependency))),
+        "requirements.txt" => content.lines().any(|line| {
+            let name = line.trim().split(['=', '<', '>', '!', '~', ';', '[', '@', '#', ' ']).next().unwrap_or("");
+            python_package_name(name) == python_package_name(dependency)
+        }),
+        _ => content.contains(dependency),
+    }
+}
+
+/// PEP 503 normalized name, so `Flask_Login` and `flask-login` compare equal
+fn python_package_name(name: &str) -> String {
+    name.to_ascii_lowercase().replace(['_', '.'], "-")
+}
+
+/// Language implied by a file name, from its manifest or its extension
+fn file_language(file_name: &str) -> Option<&'static str> {
+    let language = match file_name {
+        "Cargo.toml" => "rust",
+        "go.mod" => "go",
+        "pom.xml" | "build.gradle" | "build.gradle.kts" => "java",
+        "composer.json" => "php",
+        "pubspec.yaml" => "dart",
+        "requirements.txt" | "pyproject.toml" => "python",
+        _ => {
+            // Tool configs (vite.config.js, svelte.config.js) say nothing about the codebase
+            if file_name.contains(".config.") {
+                return None;
+            }
+            match file_name.rsplit_once('.')?.1 {
+                "ts" | "tsx" => "typescript",
+                "js" | "jsx" | "mjs" | "cjs" => "javascript",
+                "py" => "python",
+                "rs" => "rust",
+                "go" => "go",
+                "java" => "java",
+                "kt" => "kotlin",
+                "php" => "php",
+                "dart" => "dart",
+                "cs" => "csharp",
+                "swift" => "swift",
+                "rb" => "ruby",
+                _ => return None,
+            }
+        }
+    };
+    Some(language)
+}
+
+/// Files that make the directory holding them a stack of its own
+const STACK_FILES: &[&str] = &[
+    "package.json",
+    "Cargo.toml",
+    "go.mod",
+    "pom.xml",
+    "build.gradle",
+    "build.gradle.kts",
+    "composer.json",
+    "pubspec.yaml",
+    "requirements.txt",
+    "pyproject.toml",
+    "svelte.config.js",
+    "tauri.conf.json",
+];
+
+/// Whether a file with this name exists anywhere in the scan
+fn has_file(detected: &BTreeSet<String>, name: &str) -> bool {
+    detected.iter().any(|path| file_name(path) == name)
+}
+
+/// Languages ranked by file count, most files first
+fn rank_languages(counts: HashMap<&str, usize>) -> Vec<String> {
+    let mut languages: Vec<(&str, usize)> = counts.into_iter().collect();
+    languages.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    languages.into_iter().map(|(language, _)| language.to_string()).collect()
+}
+
 // ============================================================================
 // TECHNOLOGY DETECTOR
 // ============================================================================
 
 pub struct TechStackDetector {
     files_scanned: usize,
+    files: Vec<PathBuf>,
+    indicators: FileIndicatorSet,
+    progress: AnalysisProgress,
 }
 
 impl TechStackDetector {
     pub fn new() -> Self {
-        Self { files_scanned: 0 }
+        Self {
+            files_scanned: 0,
+            files: Vec::new(),
+            indicators: FileIndicatorSet::bundled(),
+            progress: AnalysisProgress::silent(),
+        }
+    }
+
+    pub fn with_progress(progress: AnalysisProgress) -> Self {
+        Self { progress, ..Self::new() }
+    }
+
+    /// Extend the bundled file indicators with a user indicator file, if present
+    pub fn load_user_indicators(&mut self, indicators_path: &Path) -> Result<bool, String> {
+        self.indicators.merge_file(indicators_path)
     }
 
     pub fn detect(&mut self, options: &RuntimeAnalysisOptions) -> Result<TechnologyStack, String> {
@@ -208,19 +411,33 @@ impl TechStackDetector {
             return Err(format!("Project path does not exist: {}", options.project_path));
         }
 
-        let mut detected_files: HashMap<String, bool> = HashMap::new();
-        self.scan_directory(project_path, &mut detected_files, 0, options.max_depth, &options.exclude)?;
+        // Project-local indicators take precedence over bundled and user ones
+        self.indicators
+            .merge_file(&project_path.join(".vibeforge").join(USER_INDICATORS_FILE))?;
+
+        self.progress.phase("scan", "Scanning files...");
+        self.scan_directory(project_path, 0, options.max_depth, &options.exclude)?;
+        let detected_files: BTreeSet<String> = self.relative_files(project_path).into_iter().collect();
 
         // Analyze detected files
+        self.progress.phase("dependencies", "Reading dependency manifests...");
         let stack = self.analyze_files(&detected_files, options)?;
 
         Ok(stack)
     }
 
+    /// Scanned files relative to the project root, `/`-separated
+    fn relative_files(&self, project_path: &Path) -> Vec<String> {
+        self.files
+            .iter()
+            .filter_map(|f| f.strip_prefix(project_path).ok())
+            .map(|f| f.to_string_lossy().replace('\\', "/"))
+            .collect()
+    }
+
     fn scan_directory(
         &mut self,
         dir: &Path,
-        detected: &mut HashMap<String, bool>,
         current_depth: usize,
         max_depth: usize,
         exclude: &[String],
@@ -229,7 +446,12 @@ impl TechStackDetector {
             return Ok(());
         }
 
+        if self.progress.is_cancelled() {
+            return Err(CANCELLED_ERROR.to_string());
+        }
+
         let entries = fs::read_dir(dir).map_err(|e| format!("Failed to read directory: {}", e))?;
+        self.progress.directory_visited();
 
         for entry in entries {
             let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
@@ -257,10 +479,11 @@ impl TechStackDetector {
 
             if path.is_dir() {
                 // Recursively scan subdirectories
-                self.scan_directory(&path, detected, current_depth + 1, max_depth, exclude)?;
+                self.scan_directory(&path, current_depth + 1, max_depth, exclude)?;
             } else {
                 self.files_scanned += 1;
-                detected.insert(file_name.to_string(), true);
+                self.progress.file_scanned();
+                self.files.push(path);
             }
         }
 
@@ -269,34 +492,41 @@ impl TechStackDetector {
 
     fn analyze_files(
         &self,
-        detected: &HashMap<String, bool>,
+        detected: &BTreeSet<String>,
         options: &RuntimeAnalysisOptions,
     ) -> Result<TechnologyStack, String> {
         let project_path = Path::new(&options.project_path);
 
         // Detect package files
         let package_files = PackageFiles {
-            package_json: detected.contains_key("package.json"),
-            cargo_toml: detected.contains_key("Cargo.toml"),
-            requirements_txt: detected.contains_key("requirements.txt"),
-            poetry_lock: detected.contains_key("poetry.lock"),
-            go_mod: detected.contains_key("go.mod"),
+            package_json: has_file(detected, "package.json"),
+            cargo_toml: has_file(detected, "Cargo.toml"),
+            requirements_txt: has_file(detected, "requirements.txt"),
+            poetry_lock: has_file(detected, "poetry.lock"),
+            go_mod: has_file(detected, "go.mod"),
         };
 
         // Detect config files
         let config_files = ConfigFiles {
-            vite_config: detected.contains_key("vite.config.ts") || detected.contains_key("vite.config.js"),
-            svelte_config: detected.contains_key("svelte.config.js"),
-            tauri_config: detected.contains_key("tauri.conf.json"),
-            docker_compose: detected.contains_key("docker-compose.yml") || detected.contains_key("docker-compose.yaml"),
-            dockerfile: detected.contains_key("Dockerfile"),
+            vite_config: has_file(detected, "vite.config.ts") || has_file(detected, "vite.config.js"),
+            svelte_config: has_file(detected, "svelte.config.js"),
+            tauri_config: has_file(detected, "tauri.conf.json"),
+            docker_compose: has_file(detected, "docker-compose.yml") || has_file(detected, "docker-compose.yaml"),
+            dockerfile: has_file(detected, "Dockerfile"),
         };
 
-        // Determine primary language
+        // Determine primary language; PHP, Java and Dart projects often carry a
+        // package.json for asset tooling, so their manifests are checked first
         let primary_language = if package_files.cargo_toml {
             "rust"
+        } else if has_file(detected, "composer.json") {
+            "php"
+        } else if ["pom.xml", "build.gradle", "build.gradle.kts"].iter().any(|f| has_file(detected, f)) {
+            "java"
+        } else if has_file(detected, "pubspec.yaml") {
+            "dart"
         } else if package_files.package_json {
-            if detected.contains_key("tsconfig.json") {
+            if has_file(detected, "tsconfig.json") {
                 "typescript"
             } else {
                 "javascript"
@@ -310,25 +540,41 @@ impl TechStackDetector {
         }
         .to_string();
 
-        // Detect frameworks
-        let (frontend_framework, backend_framework, desktop_framework) =
-            self.detect_frameworks(&package_files, &config_files, project_path, options)?;
+        let additional_languages = self.detect_additional_languages(detected, &primary_language);
+
+        // Detect databases from compose files and dependency manifests
+        let databases = self.detect_databases(detected, project_path, options)?;
+
+        // Detect frameworks per directory; the root wins, then the shallowest directory
+        self.progress.phase("frameworks", "Detecting technology stack...");
+        let mut directories = self.detect_directories(detected, project_path, options);
+        directories.sort_by_cached_key(|d| (if d.path == "." { 0 } else { d.path.matches('/').count() + 1 }, d.path.clone()));
+        let pick = |slot: fn(&DirectoryStack) -> &Option<String>| directories.iter().find_map(|d| slot(d).clone());
+        let frontend_framework = pick(|d| &d.frontend_framework);
+        let backend_framework = pick(|d| &d.backend_framework);
+        let desktop_framework = pick(|d| &d.desktop_framework);
 
         // Detect build tools
         let build_tools = self.detect_build_tools(&package_files, &config_files);
 
-        // Detect databases
-        let databases = self.detect_databases(project_path, &config_files, options)?;
-
         // Determine structure
         let structure = self.detect_structure(&frontend_framework, &backend_framework, &config_files);
 
         // Calculate confidence
-        let confidence = self.calculate_confidence(&package_files, &config_files);
+        let technology_confidence = self.technology_confidence(detected, project_path, options)?;
+        let confidence = self.calculate_confidence(
+            &technology_confidence,
+            &[
+                Some(primary_language.as_str()),
+                frontend_framework.as_deref(),
+                backend_framework.as_deref(),
+                desktop_framework.as_deref(),
+            ],
+        );
 
         Ok(TechnologyStack {
             primary_language,
-            additional_languages: vec![],
+            additional_languages,
             frontend_framework,
             backend_framework,
             desktop_framework,
@@ -337,58 +583,125 @@ impl TechStackDetector {
             structure,
             package_files,
             config_files,
+            directories,
+            technology_confidence,
             confidence,
         })
     }
 
+    /// Languages other than the primary one, most files first
+    fn detect_additional_languages(&self, detected: &BTreeSet<String>, primary_language: &str) -> Vec<String> {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for language in detected.iter().filter_map(|f| file_language(file_name(f))) {
+            *counts.entry(language).or_default() += 1;
+        }
+        counts.remove(primary_language);
+        rank_languages(counts)
+    }
+
+    /// One entry per directory holding a stack file, root first. Source files are
+    /// attributed to the nearest such directory above them.
+    fn detect_directories(
+        &self,
+        detected: &BTreeSet<String>,
+        project_path: &Path,
+        options: &RuntimeAnalysisOptions,
+    ) -> Vec<DirectoryStack> {
+        let mut manifests: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+        for path in detected {
+            if STACK_FILES.contains(&file_name(path)) {
+                manifests.entry(parent_dir(path)).or_default().push(file_name(path).to_string());
+            }
+        }
+
+        let mut languages: HashMap<&str, HashMap<&str, usize>> = HashMap::new();
+        for path in detected {
+            let Some(language) = file_language(file_name(path)) else {
+                continue;
+            };
+            let owner = manifests
+                .keys()
+                .filter(|dir| dir.is_empty() || path.starts_with(&format!("{}/", dir)))
+                .max_by_key(|dir| dir.len());
+            if let Some(owner) = owner {
+                *languages.entry(owner).or_default().entry(language).or_default() += 1;
+            }
+        }
+
+        manifests
+            .into_iter()
+            .map(|(dir, manifests)| {
+                let (frontend_framework, backend_framework, desktop_framework) =
+                    self.detect_frameworks(dir, detected, project_path, options);
+                DirectoryStack {
+                    path: if dir.is_empty() { ".".to_string() } else { dir.to_string() },
+                    manifests,
+                    languages: rank_languages(languages.remove(dir).unwrap_or_default()),
+                    frontend_framework,
+                    backend_framework,
+                    desktop_framework,
+                }
+            })
+            .collect()
+    }
+
+    /// Frameworks configured or declared by the stack files directly inside `dir`
     fn detect_frameworks(
         &self,
-        package_files: &PackageFiles,
-        config_files: &ConfigFiles,
+        dir: &str,
+        detected: &BTreeSet<String>,
         project_path: &Path,
         options: &RuntimeAnalysisOptions,
-    ) -> Result<(Option<String>, Option<String>, Option<String>), String> {
+    ) -> (Option<String>, Option<String>, Option<String>) {
         let mut frontend_framework = None;
         let mut backend_framework = None;
         let mut desktop_framework = None;
+        let in_dir = |name: &str| detected.contains(&join_path(dir, name));
 
         // Desktop frameworks
-        if config_files.tauri_config {
+        if in_dir("tauri.conf.json") {
             desktop_framework = Some("tauri".to_string());
         }
 
         // Frontend frameworks
-        if config_files.svelte_config {
-            frontend_framework = Some(if config_files.vite_config {
+        if in_dir("svelte.config.js") {
+            frontend_framework = Some(if in_dir("vite.config.ts") || in_dir("vite.config.js") {
                 "sveltekit".to_string()
             } else {
                 "svelte".to_string()
             });
         }
 
-        // Backend frameworks (from package.json if available)
-        if package_files.package_json && options.read_package_files {
-            if let Ok(content) = fs::read_to_string(project_path.join("package.json")) {
-                if content.contains("\"express\"") {
-                    backend_framework = Some("express".to_string());
-                }
-            }
+        if !options.read_package_files {
+            return (frontend_framework, backend_framework, desktop_framework);
         }
 
-        // Backend frameworks (from Python)
-        if package_files.requirements_txt && options.read_package_files {
-            if let Ok(content) = fs::read_to_string(project_path.join("requirements.txt")) {
-                if content.contains("fastapi") {
-                    backend_framework = Some("fastapi".to_string());
-                } else if content.contains("flask") {
-                    backend_framework = Some("flask".to_string());
-                } else if content.contains("django") {
-                    backend_framework = Some("django".to_string());
-                }
+        // Frameworks declared as manifest dependencies
+        let mut manifests: HashMap<&str, Option<String>> = HashMap::new();
+        for indicator in FRAMEWORK_INDICATORS {
+            if !in_dir(indicator.manifest) {
+                continue;
+            }
+            let slot = match indicator.slot {
+                "frontend" => &mut frontend_framework,
+                "backend" => &mut backend_framework,
+                _ => &mut desktop_framework,
+            };
+            if slot.is_some() {
+                continue;
+            }
+            let content = manifests
+                .entry(indicator.manifest)
+                .or_insert_with(|| fs::read_to_string(project_path.join(join_path(dir, indicator.manifest))).ok());
+            if content
+                .as_deref()
+                .is_some_and(|c| declares_dependency(indicator.manifest, c, indicator.dependency))
+            {
+                *slot = Some(indicator.framework.to_string());
             }
         }
 
-        Ok((frontend_framework, backend_framework, desktop_framework))
+        (frontend_framework, backend_framework, desktop_framework)
     }
 
     fn detect_build_tools(&self, package_files: &PackageFiles, config_files: &ConfigFiles) -> Vec<String> {
@@ -414,17 +727,19 @@ impl TechStackDetector {
 
     fn detect_databases(
         &self,
+        detected: &BTreeSet<String>,
         project_path: &Path,
-        config_files: &ConfigFiles,
         options: &RuntimeAnalysisOptions,
     ) -> Result<Vec<String>, String> {
         let mut databases = Vec::new();
 
-        // Check docker-compose for databases
-        if config_files.docker_compose && options.read_package_files {
-            if let Ok(content) = fs::read_to_string(project_path.join("docker-compose.yml"))
-                .or_else(|_| fs::read_to_string(project_path.join("docker-compose.yaml")))
-            {
+        // Check the shallowest docker-compose file for databases
+        let compose_file = detected
+            .iter()
+            .filter(|path| matches!(file_name(path), "docker-compose.yml" | "docker-compose.yaml"))
+            .min_by_key(|path| path.matches('/').count());
+        if let (Some(compose_file), true) = (compose_file, options.read_package_files) {
+            if let Ok(content) = fs::read_to_string(project_path.join(compose_file)) {
                 if content.contains("postgres") || content.contains("postgresql") {
                     databases.push("postgresql".to_string());
                 }
@@ -462,27 +777,80 @@ impl TechStackDetector {
         }
     }
 
-    fn calculate_confidence(&self, package_files: &PackageFiles, config_files: &ConfigFiles) -> u8 {
-        let mut confidence = 50;
+    /// Score every technology named by a matched indicator. Positive matches
+    /// combine as `1 - Π(1 - w/10)`; each negative match then scales the
+    /// result by `1 - w/10`. Content checks only run when package files may be read.
+    fn technology_confidence(
+        &self,
+        detected: &BTreeSet<String>,
+        project_path: &Path,
+        options: &RuntimeAnalysisOptions,
+    ) -> Result<Vec<TechnologyConfidence>, String> {
+        // technology -> (probability no positive indicator is right, negative factor, evidence)
+        let mut scores: BTreeMap<&str, (f64, f64, Vec<String>)> = BTreeMap::new();
 
-        // Boost confidence for clear indicators
-        if package_files.package_json {
-            confidence += 10;
-        }
-        if package_files.cargo_toml {
-            confidence += 10;
-        }
-        if config_files.svelte_config {
-            confidence += 10;
-        }
-        if config_files.tauri_config {
-            confidence += 10;
-        }
-        if config_files.vite_config {
-            confidence += 5;
+        for indicator in &self.indicators.indicators {
+            if indicator.weight == 0 || (indicator.contains.is_some() && !options.read_package_files) {
+                continue;
+            }
+            let glob = glob_to_regex(&indicator.pattern)?;
+            let content = indicator
+                .contains
+                .as_deref()
+                .map(Regex::new)
+                .transpose()
+                .map_err(|e| format!("invalid content pattern for '{}': {}", indicator.pattern, e))?;
+
+            let candidates = if content.is_some() { MAX_CONTENT_FILES } else { 1 };
+            let Some(file) = detected
+                .iter()
+                .filter(|f| glob.is_match(f))
+                .take(candidates)
+                .find(|f| {
+                    content.as_ref().is_none_or(|re| {
+                        read_small_file(&project_path.join(f)).is_some_and(|c| re.is_match(&c))
+                    })
+                })
+            else {
+                continue;
+            };
+
+            let weight = f64::from(indicator.weight.min(10)) / 10.0;
+            let (miss, penalty, evidence) = scores.entry(&indicator.technology).or_insert((1.0, 1.0, Vec::new()));
+            if indicator.negative {
+                *penalty *= 1.0 - weight;
+                evidence.push(format!("{} (against)", file));
+            } else {
+                *miss *= 1.0 - weight;
+                evidence.push(file.clone());
+            }
         }
 
-        confidence.min(100)
+        let mut confidences: Vec<TechnologyConfidence> = scores
+            .into_iter()
+            .filter(|(_, (miss, _, _))| *miss < 1.0)
+            .map(|(technology, (miss, penalty, indicators))| TechnologyConfidence {
+                technology: technology.to_string(),
+                confidence: ((1.0 - miss) * penalty * 100.0).round() as u8,
+                indicators,
+            })
+            .collect();
+        confidences.sort_by(|a, b| b.confidence.cmp(&a.confidence).then(a.technology.cmp(&b.technology)));
+        Ok(confidences)
+    }
+
+    /// Mean confidence of the reported language and frameworks that have indicators
+    fn calculate_confidence(&self, technologies: &[TechnologyConfidence], reported: &[Option<&str>]) -> u8 {
+        let scores: Vec<u32> = reported
+            .iter()
+            .flatten()
+            .filter_map(|name| technologies.iter().find(|t| t.technology == *name))
+            .map(|t| u32::from(t.confidence))
+            .collect();
+        if scores.is_empty() {
+            return 0;
+        }
+        (scores.iter().sum::<u32>() / scores.len() as u32) as u8
     }
 }
 
@@ -490,284 +858,174 @@ impl TechStackDetector {
 // PATTERN RECOMMENDER
 // ============================================================================
 
-pub struct PatternRecommender;
+pub struct PatternRecommender {
+    catalog: PatternCatalog,
+    min_score: u8,
+}
 
 impl PatternRecommender {
-    pub fn new() -> Self {
-        Self
+    pub fn with_catalog(catalog: PatternCatalog) -> Self {
+        Self { catalog, min_score: 0 }
     }
 
-    pub fn recommend(&self, stack: &TechnologyStack) -> Vec<PatternRecommendation> {
-        let mut recommendations = Vec::new();
-
-        // Desktop app pattern
-        if stack.desktop_framework == Some("tauri".to_string()) {
-            recommendations.push(self.recommend_desktop(stack));
-        }
-
-        // Full-stack web pattern
-        if stack.frontend_framework.is_some() && stack.backend_framework.is_some() {
-            recommendations.push(self.recommend_fullstack(stack));
-        }
-
-        // Microservices pattern
-        if stack.structure == "multi-service" {
-            recommendations.push(self.recommend_microservices(stack));
-        }
-
-        // REST API backend pattern
-        if stack.backend_framework.is_some() && stack.frontend_framework.is_none() {
-            recommendations.push(self.recommend_rest_api(stack));
-        }
-
-        // Static site pattern
-        if stack.frontend_framework == Some("sveltekit".to_string())
-            && stack.backend_framework.is_none()
-        {
-            recommendations.push(self.recommend_static_site(stack));
-        }
+    pub fn with_min_score(self, min_score: u8) -> Self {
+        Self { min_score, ..self }
+    }
 
-        // SPA pattern
-        if stack.frontend_framework == Some("svelte".to_string())
-            && stack.config_files.vite_config
-            && stack.backend_framework.is_none()
-        {
-            recommendations.push(self.recommend_spa(stack));
-        }
+    /// Every catalog pattern, recommended ones first, then by score
+    pub fn recommend(&self, stack: &TechnologyStack) -> Vec<PatternRecommendation> {
+        self.recommend_fields(&stack_fields(stack))
+    }
 
-        // Sort by score
-        recommendations.sort_by(|a, b| b.score.cmp(&a.score));
+    /// `recommend` for fields gathered elsewhere, in the shape of `stack_fields`
+    pub fn recommend_fields(&self, fields: &serde_json::Value) -> Vec<PatternRecommendation> {
+        let mut recommendations: Vec<PatternRecommendation> =
+            self.catalog.patterns.iter().map(|pattern| self.evaluate(pattern, fields)).collect();
 
+        Self::rank(&mut recommendations);
         recommendations
     }
 
-    fn recommend_desktop(&self, stack: &TechnologyStack) -> PatternRecommendation {
-        let mut score = 0;
-        let mut reasons = Vec::new();
-        let mut warnings = Vec::new();
-
-        if stack.desktop_framework == Some("tauri".to_string()) {
-            score += 40;
-            reasons.push("Tauri framework detected".to_string());
-        }
-        if stack.primary_language == "rust" || stack.primary_language == "typescript" {
-            score += 20;
-            reasons.push("Compatible languages detected".to_string());
-        }
-        if stack.frontend_framework.is_some() {
-            score += 20;
-            reasons.push("Frontend framework for UI".to_string());
-        }
-
-        let confidence = if score >= 80 {
-            "high"
-        } else if score >= 50 {
-            "medium"
-        } else {
-            "low"
-        }
-        .to_string();
-
-        PatternRecommendation {
-            pattern_id: "desktop-app".to_string(),
-            score,
-            reasons,
-            warnings,
-            confidence,
-        }
+    /// Recommend from the stack, then adjust scores with the extracted component graph
+    pub fn recommend_with_architecture(
+        &self,
+        stack: &TechnologyStack,
+        architecture: &ArchitectureProfile,
+    ) -> Vec<PatternRecommendation> {
+        self.adjust_for_architecture(self.recommend(stack), architecture)
     }
 
-    fn recommend_fullstack(&self, stack: &TechnologyStack) -> PatternRecommendation {
-        let mut score = 0;
-        let mut reasons = Vec::new();
-        let warnings = Vec::new();
-
-        if stack.frontend_framework.is_some() {
-            score += 30;
-            reasons.push(format!(
-                "Frontend: {}",
-                stack.frontend_framework.as_ref().unwrap()
-            ));
-        }
-        if stack.backend_framework.is_some() {
-            score += 30;
-            reasons.push(format!(
-                "Backend: {}",
-                stack.backend_framework.as_ref().unwrap()
-            ));
-        }
-        if !stack.databases.contains(&"none".to_string()) {
-            score += 20;
-            reasons.push("Database detected".to_string());
+    /// Adjust scores with evidence from the extracted component graph, then rank again
+    pub fn adjust_for_architecture(
+        &self,
+        mut recommendations: Vec<PatternRecommendation>,
+        architecture: &ArchitectureProfile,
+    ) -> Vec<PatternRecommendation> {
+
+        let services: Vec<&str> = architecture.application_components().map(|c| c.id.as_str()).collect();
+        let service_links = architecture
+            .connections
+            .iter()
+            .filter(|c| services.contains(&c.from.as_str()) && services.contains(&c.to.as_str()))
+            .count();
+        let endpoints = architecture.routes.iter().filter(|r| r.kind == "endpoint").count();
+        let pages = architecture.routes.iter().filter(|r| r.kind == "page").count();
+
+        // Services calling each other stand in for a multi-service layout
+        if services.len() >= 2 && service_links > 0 {
+            self.boost(
+                &mut recommendations,
+                "microservices",
+                20,
+                format!("{} application services calling each other", services.len()),
+                true,
+            );
         }
-        if stack.structure == "monorepo" {
-            score += 10;
-            reasons.push("Monorepo structure".to_string());
+        if endpoints > 0 && pages == 0 {
+            self.boost(
+                &mut recommendations,
+                "rest-api-backend",
+                10,
+                format!("{} HTTP endpoints defined", endpoints),
+                false,
+            );
         }
-
-        let confidence = if score >= 80 {
-            "high"
-        } else if score >= 50 {
-            "medium"
-        } else {
-            "low"
+        if endpoints > 0 && pages > 0 {
+            self.boost(
+                &mut recommendations,
+                "fullstack-web",
+                10,
+                format!("{} pages and {} API endpoints", pages, endpoints),
+                false,
+            );
         }
-        .to_string();
 
-        PatternRecommendation {
-            pattern_id: "fullstack-web".to_string(),
-            score,
-            reasons,
-            warnings,
-            confidence,
-        }
+        Self::rank(&mut recommendations);
+        recommendations
     }
 
-    fn recommend_microservices(&self, stack: &TechnologyStack) -> PatternRecommendation {
-        let mut score = 0;
-        let mut reasons = Vec::new();
-        let warnings = Vec::new();
-
-        if stack.structure == "multi-service" {
-            score += 40;
-            reasons.push("Multi-service structure detected".to_string());
-        }
-        if stack.config_files.docker_compose {
-            score += 30;
-            reasons.push("Docker Compose orchestration".to_string());
-        }
-        if stack.backend_framework.is_some() {
-            score += 20;
-            reasons.push("Backend services detected".to_string());
-        }
-
-        let confidence = if score >= 80 {
-            "high"
-        } else if score >= 50 {
-            "medium"
-        } else {
-            "low"
-        }
-        .to_string();
-
-        PatternRecommendation {
-            pattern_id: "microservices".to_string(),
-            score,
-            reasons,
-            warnings,
-            confidence,
-        }
+    fn rank(recommendations: &mut [PatternRecommendation]) {
+        recommendations.sort_by(|a, b| b.recommended.cmp(&a.recommended).then(b.score.cmp(&a.score)));
     }
 
-    fn recommend_rest_api(&self, stack: &TechnologyStack) -> PatternRecommendation {
-        let mut score = 0;
-        let mut reasons = Vec::new();
-        let warnings = Vec::new();
-
-        if stack.backend_framework.is_some() {
-            score += 40;
-            reasons.push(format!(
-                "Backend framework: {}",
-                stack.backend_framework.as_ref().unwrap()
-            ));
-        }
-        if stack.frontend_framework.is_none() {
-            score += 20;
-            reasons.push("Backend-only project".to_string());
-        }
-        if !stack.databases.contains(&"none".to_string()) {
-            score += 20;
-            reasons.push("Database integration".to_string());
-        }
-
-        let confidence = if score >= 80 {
-            "high"
-        } else if score >= 50 {
-            "medium"
-        } else {
-            "low"
+    /// Add architecture evidence to a pattern; `satisfies_required` lets it
+    /// stand in for the pattern's required criteria
+    fn boost(
+        &self,
+        recommendations: &mut [PatternRecommendation],
+        pattern_id: &str,
+        points: u8,
+        reason: String,
+        satisfies_required: bool,
+    ) {
+        if let Some(recommendation) = recommendations.iter_mut().find(|r| r.pattern_id == pattern_id) {
+            recommendation.score = recommendation.score.saturating_add(points).min(100);
+            recommendation.reasons.push(reason.clone());
+            recommendation.breakdown.push(CriterionScore {
+                description: reason,
+                field: "architecture".to_string(),
+                weight: points,
+                required: false,
+                matched: true,
+                evidence: None,
+            });
+            let required_hold = satisfies_required || Self::required_hold(&recommendation.breakdown);
+            recommendation.recommended = required_hold && recommendation.score >= self.min_score;
+            recommendation.confidence = Self::confidence(recommendation.score);
         }
-        .to_string();
+    }
 
-        PatternRecommendation {
-            pattern_id: "rest-api-backend".to_string(),
-            score,
-            reasons,
-            warnings,
-            confidence,
-        }
+    fn required_hold(breakdown: &[CriterionScore]) -> bool {
+        breakdown.iter().filter(|c| c.required).all(|c| c.matched)
     }
 
-    fn recommend_static_site(&self, stack: &TechnologyStack) -> PatternRecommendation {
-        let mut score = 0;
+    /// Score a pattern as the sum of the weights of its criteria that hold,
+    /// keeping the outcome of every criterion and the conflicts that apply
+    fn evaluate(&self, pattern: &PatternDefinition, fields: &serde_json::Value) -> PatternRecommendation {
+        let mut score: u8 = 0;
         let mut reasons = Vec::new();
-        let warnings = Vec::new();
-
-        if stack.frontend_framework == Some("sveltekit".to_string()) {
-            score += 40;
-            reasons.push("SvelteKit detected".to_string());
-        }
-        if stack.backend_framework.is_none() {
-            score += 30;
-            reasons.push("No backend - ideal for SSG".to_string());
-        }
-        if stack.databases.contains(&"none".to_string()) {
-            score += 20;
-            reasons.push("No database - static content".to_string());
-        }
-
-        let confidence = if score >= 80 {
-            "high"
-        } else if score >= 50 {
-            "medium"
-        } else {
-            "low"
+        let mut breakdown = Vec::new();
+        for criterion in &pattern.criteria {
+            let outcome = criterion.condition.evaluate(fields);
+            if let Some(values) = &outcome {
+                score = score.saturating_add(criterion.weight);
+                reasons.push(criterion.reason(values));
+            }
+            breakdown.push(CriterionScore {
+                description: match &outcome {
+                    Some(values) => criterion.reason(values),
+                    None => criterion.condition.expected(),
+                },
+                field: criterion.condition.field.clone(),
+                weight: criterion.weight,
+                required: criterion.required,
+                matched: outcome.is_some(),
+                evidence: outcome.filter(|v| !v.is_empty()).map(|v| v.join(", ")),
+            });
         }
-        .to_string();
+        let score = score.min(100);
+        let warnings = pattern.warnings.iter().filter_map(|w| w.evaluate(fields)).collect();
 
         PatternRecommendation {
-            pattern_id: "static-site".to_string(),
+            pattern_id: pattern.id.clone(),
             score,
+            recommended: Self::required_hold(&breakdown) && score >= self.min_score,
             reasons,
             warnings,
-            confidence,
+            confidence: Self::confidence(score),
+            breakdown,
         }
     }
 
-    fn recommend_spa(&self, stack: &TechnologyStack) -> PatternRecommendation {
-        let mut score = 0;
-        let mut reasons = Vec::new();
-        let warnings = Vec::new();
-
-        if stack.frontend_framework == Some("svelte".to_string()) {
-            score += 40;
-            reasons.push("Svelte framework detected".to_string());
-        }
-        if stack.config_files.vite_config {
-            score += 30;
-            reasons.push("Vite build tool".to_string());
-        }
-        if stack.backend_framework.is_none() {
-            score += 20;
-            reasons.push("Client-side only app".to_string());
-        }
-
-        let confidence = if score >= 80 {
+    fn confidence(score: u8) -> String {
+        if score >= 80 {
             "high"
         } else if score >= 50 {
             "medium"
         } else {
             "low"
         }
-        .to_string();
-
-        PatternRecommendation {
-            pattern_id: "spa".to_string(),
-            score,
-            reasons,
-            warnings,
-            confidence,
-        }
+        .to_string()
     }
 }
 
@@ -775,22 +1033,38 @@ impl PatternRecommender {
 // PUBLIC API
 // ============================================================================
 
-pub fn analyze_project(options: RuntimeAnalysisOptions) -> Result<RecommendationResult, String> {
+/// Analyze while reporting progress; returns `CANCELLED_ERROR` once `progress` is cancelled.
+///
+/// `config_dir` is where user file indicators and pattern catalogs are looked up.
+pub fn analyze_project(
+    options: RuntimeAnalysisOptions,
+    progress: AnalysisProgress,
+    config_dir: Option<&Path>,
+) -> Result<RecommendationResult, String> {
     let start = Instant::now();
 
     // Detect technology stack
-    let mut detector = TechStackDetector::new();
+    let mut detector = TechStackDetector::with_progress(progress.clone());
+    if let Some(dir) = config_dir {
+        detector.load_user_indicators(&dir.join(USER_INDICATORS_FILE))?;
+    }
     let tech_stack = detector.detect(&options)?;
 
     // Recommend patterns
-    let recommender = PatternRecommender::new();
-    let recommendations = recommender.recommend(&tech_stack);
+    progress.phase("recommendations", "Recommending architecture patterns...");
+    let project_path = Path::new(&options.project_path);
+    let architecture = architecture::extract_architecture(project_path, &detector.relative_files(project_path));
+    let recommender = PatternRecommender::with_catalog(PatternCatalog::with_user_catalogs(config_dir)?)
+        .with_min_score(options.min_recommendation_score);
+    let recommendations = recommender.recommend_with_architecture(&tech_stack, &architecture);
 
     let duration = start.elapsed().as_millis() as u64;
+    progress.phase("complete", "Analysis complete");
 
     Ok(RecommendationResult {
         tech_stack,
         recommendations,
+        architecture,
         metadata: AnalysisMetadata {
             files_scanned: detector.files_scanned,
             duration,
@@ -809,9 +1083,9 @@ mod tests {
 
     #[test]
     fn test_detect_typescript_from_package_json() {
-        let mut detected_files = HashMap::new();
-        detected_files.insert("package.json".to_string(), true);
-        detected_files.insert("tsconfig.json".to_string(), true);
+        let mut detected_files = BTreeSet::new();
+        detected_files.insert("package.json".to_string());
+        detected_files.insert("tsconfig.json".to_string());
 
         let detector = TechStackDetector::new();
         let options = RuntimeAnalysisOptions {
@@ -820,6 +1094,7 @@ mod tests {
             exclude: vec![],
             analyze_dependencies: false,
             read_package_files: false,
+            min_recommendation_score: 0,
         };
 
         let result = detector.analyze_files(&detected_files, &options);
@@ -832,8 +1107,8 @@ mod tests {
 
     #[test]
     fn test_detect_python_from_requirements() {
-        let mut detected_files = HashMap::new();
-        detected_files.insert("requirements.txt".to_string(), true);
+        let mut detected_files = BTreeSet::new();
+        detected_files.insert("requirements.txt".to_string());
 
         let detector = TechStackDetector::new();
         let options = RuntimeAnalysisOptions {
@@ -842,6 +1117,7 @@ mod tests {
             exclude: vec![],
             analyze_dependencies: false,
             read_package_files: false,
+            min_recommendation_score: 0,
         };
 
         let result = detector.analyze_files(&detected_files, &options);
@@ -854,8 +1130,8 @@ mod tests {
 
     #[test]
     fn test_detect_rust_from_cargo_toml() {
-        let mut detected_files = HashMap::new();
-        detected_files.insert("Cargo.toml".to_string(), true);
+        let mut detected_files = BTreeSet::new();
+        detected_files.insert("Cargo.toml".to_string());
 
         let detector = TechStackDetector::new();
         let options = RuntimeAnalysisOptions {
@@ -864,6 +1140,7 @@ mod tests {
             exclude: vec![],
             analyze_dependencies: false,
             read_package_files: false,
+            min_recommendation_score: 0,
         };
 
         let result = detector.analyze_files(&detected_files, &options);
@@ -876,9 +1153,9 @@ mod tests {
 
     #[test]
     fn test_detect_sveltekit_framework() {
-        let mut detected_files = HashMap::new();
-        detected_files.insert("package.json".to_string(), true);
-        detected_files.insert("svelte.config.js".to_string(), true);
+        let mut detected_files = BTreeSet::new();
+        detected_files.insert("package.json".to_string());
+        detected_files.insert("svelte.config.js".to_string());
 
         let detector = TechStackDetector::new();
         let options = RuntimeAnalysisOptions {
@@ -887,6 +1164,7 @@ mod tests {
             exclude: vec![],
             analyze_dependencies: false,
             read_package_files: false,
+            min_recommendation_score: 0,
         };
 
         let result = detector.analyze_files(&detected_files, &options);
@@ -898,9 +1176,9 @@ mod tests {
 
     #[test]
     fn test_detect_tauri_desktop_app() {
-        let mut detected_files = HashMap::new();
-        detected_files.insert("Cargo.toml".to_string(), true);
-        detected_files.insert("tauri.conf.json".to_string(), true);
+        let mut detected_files = BTreeSet::new();
+        detected_files.insert("Cargo.toml".to_string());
+        detected_files.insert("tauri.conf.json".to_string());
 
         let detector = TechStackDetector::new();
         let options = RuntimeAnalysisOptions {
@@ -909,6 +1187,7 @@ mod tests {
             exclude: vec![],
             analyze_dependencies: false,
             read_package_files: false,
+            min_recommendation_score: 0,
         };
 
         let result = detector.analyze_files(&detected_files, &options);
@@ -918,6 +1197,112 @@ mod tests {
         assert!(stack.config_files.tauri_config);
     }
 
+    #[test]
+    fn test_detect_frameworks_from_manifests() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path();
+        fs::write(project.join("composer.json"), r#"{"require": {"laravel/framework": "^11.0"}}"#).unwrap();
+        fs::write(project.join("package.json"), r#"{"devDependencies": {"vue": "^3.4", "vite": "^5.0"}}"#).unwrap();
+        fs::write(project.join("pubspec.yaml"), "dependencies:\n  flutter:\n    sdk: flutter\n").unwrap();
+
+        let mut detected_files = BTreeSet::new();
+        for file in ["composer.json", "package.json", "pubspec.yaml", "UserController.php", "Kernel.php", "app.js", "vite.config.js"] {
+            detected_files.insert(file.to_string());
+        }
+
+        let options = RuntimeAnalysisOptions {
+            project_path: project.to_string_lossy().to_string(),
+            max_depth: 3,
+            exclude: vec![],
+            analyze_dependencies: false,
+            read_package_files: true,
+            min_recommendation_score: 0,
+        };
+        let stack = TechStackDetector::new().analyze_files(&detected_files, &options).unwrap();
+
+        assert_eq!(stack.primary_language, "php");
+        assert_eq!(stack.backend_framework.as_deref(), Some("laravel"));
+        assert_eq!(stack.frontend_framework.as_deref(), Some("vue"));
+        assert_eq!(stack.desktop_framework.as_deref(), Some("flutter"));
+        assert_eq!(stack.additional_languages, vec!["dart".to_string(), "javascript".to_string()]);
+
+        assert!(declares_dependency("Cargo.toml", "[dependencies]\naxum = \"0.7\"\n", "axum"));
+        assert!(!declares_dependency("Cargo.toml", "[dependencies]\naxum-extra = \"0.9\"\n", "axum"));
+        assert!(declares_dependency("requirements.txt", "Flask[async]>=3.0 ; python_version > '3.8'\n", "flask"));
+        assert!(!declares_dependency("requirements.txt", "flask-cors==4.0.0\n", "flask"));
+        let gin = "github.com/gin-gonic/gin";
+        assert!(declares_dependency("go.mod", "require (\n\tgithub.com/gin-gonic/gin v1.10.0\n)\n", gin));
+        assert!(!declares_dependency("go.mod", "require github.com/gin-gonic/gin-contrib v0.1.0\n", gin));
+        assert!(declares_dependency("go.mod", "require github.com/labstack/echo/v4 v4.12.0\n", "github.com/labstack/echo"));
+        let starter = "<parent>\n  <groupId>org.springframework.boot</groupId>\n</parent>\n";
+        assert!(declares_dependency("pom.xml", starter, "org.springframework.boot"));
+        assert!(!declares_dependency("pom.xml", "<groupId>org.springframework.boot.experimental</groupId>", "org.springframework.boot"));
+    }
+
+    #[test]
+    fn test_nested_manifests_get_their_own_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path();
+        fs::create_dir_all(project.join("frontend")).unwrap();
+        fs::create_dir_all(project.join("api")).unwrap();
+        fs::write(project.join("frontend/package.json"), r#"{"dependencies": {"react": "^18.3"}}"#).unwrap();
+        fs::write(project.join("api/go.mod"), "module example.com/api\n\nrequire github.com/gin-gonic/gin v1.10.0\n").unwrap();
+
+        let mut detected_files = BTreeSet::new();
+        for file in ["README.md", "frontend/package.json", "frontend/src/App.tsx", "frontend/src/main.tsx", "api/go.mod", "api/main.go"] {
+            detected_files.insert(file.to_string());
+        }
+
+        let options = RuntimeAnalysisOptions {
+            project_path: project.to_string_lossy().to_string(),
+            max_depth: 3,
+            exclude: vec![],
+            analyze_dependencies: false,
+            read_package_files: true,
+            min_recommendation_score: 0,
+        };
+        let stack = TechStackDetector::new().analyze_files(&detected_files, &options).unwrap();
+
+        assert_eq!(stack.frontend_framework.as_deref(), Some("react"));
+        assert_eq!(stack.backend_framework.as_deref(), Some("gin"));
+
+        let paths: Vec<&str> = stack.directories.iter().map(|d| d.path.as_str()).collect();
+        assert_eq!(paths, vec!["api", "frontend"]);
+        assert_eq!(stack.directories[0].languages, vec!["go".to_string()]);
+        assert_eq!(stack.directories[1].languages, vec!["typescript".to_string()]);
+        assert_eq!(stack.directories[1].frontend_framework.as_deref(), Some("react"));
+    }
+
+    #[test]
+    fn test_shallowest_directory_wins() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path();
+        fs::create_dir_all(project.join("apps/admin/nested")).unwrap();
+        fs::create_dir_all(project.join("server")).unwrap();
+        fs::write(project.join("apps/admin/nested/package.json"), r#"{"dependencies": {"express": "^4.19"}}"#).unwrap();
+        fs::write(project.join("server/go.mod"), "module example.com/server\n\nrequire github.com/gin-gonic/gin v1.10.0\n").unwrap();
+
+        let mut detected_files = BTreeSet::new();
+        for file in ["apps/admin/nested/package.json", "apps/admin/nested/index.js", "server/go.mod", "server/main.go"] {
+            detected_files.insert(file.to_string());
+        }
+
+        let options = RuntimeAnalysisOptions {
+            project_path: project.to_string_lossy().to_string(),
+            max_depth: 4,
+            exclude: vec![],
+            analyze_dependencies: false,
+            read_package_files: true,
+            min_recommendation_score: 0,
+        };
+        let stack = TechStackDetector::new().analyze_files(&detected_files, &options).unwrap();
+
+        // `apps/admin/nested` sorts first by name, but `server` is shallower
+        assert_eq!(stack.backend_framework.as_deref(), Some("gin"));
+        let paths: Vec<&str> = stack.directories.iter().map(|d| d.path.as_str()).collect();
+        assert_eq!(paths, vec!["server", "apps/admin/nested"]);
+    }
+
     #[test]
     fn test_pattern_recommendation_desktop_app() {
         let tech_stack = TechnologyStack {
@@ -943,10 +1328,12 @@ mod tests {
                 docker_compose: false,
                 dockerfile: false,
             },
+            directories: vec![],
+            technology_confidence: vec![],
             confidence: 95,
         };
 
-        let recommender = PatternRecommender::new();
+        let recommender = PatternRecommender::with_catalog(PatternCatalog::bundled());
         let recommendations = recommender.recommend(&tech_stack);
 
         assert!(!recommendations.is_empty());
@@ -987,10 +1374,12 @@ mod tests {
                 docker_compose: true,
                 dockerfile: true,
             },
+            directories: vec![],
+            technology_confidence: vec![],
             confidence: 90,
         };
 
-        let recommender = PatternRecommender::new();
+        let recommender = PatternRecommender::with_catalog(PatternCatalog::bundled());
         let recommendations = recommender.recommend(&tech_stack);
 
         assert!(!recommendations.is_empty());
@@ -1002,6 +1391,18 @@ mod tests {
 
         let rec = fullstack_rec.unwrap();
         assert!(rec.score >= 80);
+        assert!(rec.recommended);
+
+        // Static site is still scored, with the failed criterion and conflicts explained
+        let static_site = recommendations.iter().find(|r| r.pattern_id == "static-site").unwrap();
+        assert!(!static_site.recommended);
+        let backend = static_site.breakdown.iter().find(|c| c.field == "backend_framework").unwrap();
+        assert!(backend.required && !backend.matched);
+        assert!(static_site.warnings.iter().any(|w| w.contains("fastapi")));
+        assert!(static_site.warnings.iter().any(|w| w.contains("postgresql")));
+
+        let strict = PatternRecommender::with_catalog(PatternCatalog::bundled()).with_min_score(101);
+        assert!(strict.recommend(&tech_stack).iter().all(|r| !r.recommended));
     }
 
     #[test]
@@ -1029,27 +1430,158 @@ mod tests {
                 docker_compose: true,
                 dockerfile: true,
             },
+            directories: vec![],
+            technology_confidence: vec![],
             confidence: 85,
         };
 
-        let recommender = PatternRecommender::new();
+        let recommender = PatternRecommender::with_catalog(PatternCatalog::bundled());
         let recommendations = recommender.recommend(&tech_stack);
 
         assert!(!recommendations.is_empty());
 
-        // Microservices should be recommended
+        // Microservices should be recommended, on every criterion
         let micro_rec = recommendations.iter()
-            .find(|r| r.pattern_id == "microservices");
-        assert!(micro_rec.is_some());
+            .find(|r| r.pattern_id == "microservices")
+            .unwrap();
+        assert!(micro_rec.recommended);
+        assert_eq!(micro_rec.score, 90);
+        let breakdown: Vec<(&str, bool, Option<&str>)> = micro_rec
+            .breakdown
+            .iter()
+            .map(|c| (c.field.as_str(), c.matched, c.evidence.as_deref()))
+            .collect();
+        assert_eq!(
+            breakdown,
+            vec![
+                ("structure", true, Some("multi-service")),
+                ("config_files.docker_compose", true, Some("true")),
+                ("backend_framework", true, Some("fastapi")),
+            ]
+        );
+        assert!(micro_rec.warnings.is_empty());
+
+        // Without a compose file the criterion misses and the catalog warns
+        let mut no_compose = tech_stack.clone();
+        no_compose.config_files.docker_compose = false;
+        let recommendations = recommender.recommend(&no_compose);
+        let micro_rec = recommendations.iter().find(|r| r.pattern_id == "microservices").unwrap();
+        assert!(!micro_rec.breakdown[1].matched);
+        assert_eq!(
+            micro_rec.warnings,
+            vec!["Microservices without container orchestration: no docker-compose file found".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_architecture_adds_microservices() {
+        let tech_stack = TechnologyStack {
+            primary_language: "go".to_string(),
+            additional_languages: vec![],
+            frontend_framework: None,
+            backend_framework: Some("gin".to_string()),
+            desktop_framework: None,
+            build_tools: vec![],
+            databases: vec!["none".to_string()],
+            structure: "single-app".to_string(),
+            package_files: PackageFiles {
+                package_json: false,
+                cargo_toml: false,
+                requirements_txt: false,
+                poetry_lock: false,
+                go_mod: true,
+            },
+            config_files: ConfigFiles {
+                vite_config: false,
+                svelte_config: false,
+                tauri_config: false,
+                docker_compose: false,
+                dockerfile: false,
+            },
+            directories: vec![],
+            technology_confidence: vec![],
+            confidence: 70,
+        };
+        let component = |id: &str| crate::architecture::ArchitectureComponent {
+            id: id.to_string(),
+            kind: "app".to_string(),
+            source: "entry-point".to_string(),
+            path: None,
+            image: None,
+        };
+        let architecture = ArchitectureProfile {
+            components: vec![component("orders"), component("billing")],
+            connections: vec![crate::architecture::ComponentConnection {
+                from: "orders".to_string(),
+                to: "billing".to_string(),
+                kind: "network".to_string(),
+                evidence: "docker-compose.yml".to_string(),
+            }],
+            ..Default::default()
+        };
+
+        let recommender = PatternRecommender::with_catalog(PatternCatalog::bundled());
+        assert!(!recommender
+            .recommend(&tech_stack)
+            .iter()
+            .any(|r| r.pattern_id == "microservices" && r.recommended));
+
+        let recommendations = recommender.recommend_with_architecture(&tech_stack, &architecture);
+        let micro = recommendations.iter().find(|r| r.pattern_id == "microservices").unwrap();
+        assert!(micro.reasons.iter().any(|r| r.contains("2 application services")));
+    }
+
+    #[test]
+    fn test_indicator_weights_drive_technology_confidence() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path();
+        fs::write(project.join("package.json"), r#"{"dependencies": {"react": "^18.3"}}"#).unwrap();
+
+        let mut detected_files = BTreeSet::new();
+        for file in ["package.json", "tsconfig.json", "yarn.lock", "src/App.tsx"] {
+            detected_files.insert(file.to_string());
+        }
+        let options = RuntimeAnalysisOptions {
+            project_path: project.to_string_lossy().to_string(),
+            max_depth: 3,
+            exclude: vec![],
+            analyze_dependencies: false,
+            read_package_files: true,
+            min_recommendation_score: 0,
+        };
+
+        let mut detector = TechStackDetector::new();
+        detector.indicators.merge(FileIndicatorSet {
+            indicators: vec![FileIndicator {
+                pattern: "*.{ts,tsx}".to_string(),
+                technology: "typescript".to_string(),
+                weight: 0,
+                contains: None,
+                negative: false,
+            }],
+        });
+        let stack = detector.analyze_files(&detected_files, &options).unwrap();
+
+        let confidence = |name: &str| stack.technology_confidence.iter().find(|t| t.technology == name).map(|t| t.confidence);
+        // tsconfig 9 alone: the disabled glob adds nothing and package.json has no typescript dependency
+        assert_eq!(confidence("typescript"), Some(90));
+        // package.json 10 alone (the JS glob does not match App.tsx), halved by tsconfig.json
+        assert_eq!(confidence("javascript"), Some(50));
+        // package.json 6, scaled down by yarn.lock
+        assert_eq!(confidence("npm"), Some(18));
+        // the package.json content check 9 and App.tsx 6
+        assert_eq!(confidence("react"), Some(96));
+        // mean of the primary language and the frontend framework
+        assert_eq!(stack.confidence, 93);
     }
 
     #[test]
     fn test_confidence_calculation() {
-        let mut detected_files = HashMap::new();
-        detected_files.insert("package.json".to_string(), true);
-        detected_files.insert("tsconfig.json".to_string(), true);
-        detected_files.insert("svelte.config.js".to_string(), true);
-        detected_files.insert("vite.config.ts".to_string(), true);
+        let mut detected_files = BTreeSet::new();
+        detected_files.insert("package.json".to_string());
+        detected_files.insert("tsconfig.json".to_string());
+        detected_files.insert("svelte.config.js".to_string());
+        detected_files.insert("vite.config.ts".to_string());
 
         let detector = TechStackDetector::new();
         let options = RuntimeAnalysisOptions {
@@ -1058,6 +1590,7 @@ mod tests {
             exclude: vec![],
             analyze_dependencies: false,
             read_package_files: false,
+            min_recommendation_score: 0,
         };
 
         let result = detector.analyze_files(&detected_files, &options);
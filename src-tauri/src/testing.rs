@@ -0,0 +1,299 @@
+//! Testing - Test frameworks, test locations and coverage reports
+//!
+//! Test frameworks come from `testing` detection rules. Test files are
+//! recognised by naming convention and directory, counted per language
+//! against source files, and lcov/Cobertura reports are parsed for totals.
+
+use crate::detection_rules::RuleMatch;
+use crate::relative_path::file_name;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Directory names that hold tests
+const TEST_DIRECTORIES: &[&str] = &["test", "tests", "__tests__", "spec", "specs", "e2e", "cypress", "playwright"];
+
+/// Cobertura totals live on the root element near the top of the file
+const COBERTURA_HEADER_BYTES: u64 = 8 * 1024;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestingProfile {
+    pub frameworks: Vec<DetectedTestFramework>,
+    pub test_directories: Vec<String>,
+    pub file_counts: Vec<TestFileCount>,
+    pub coverage_reports: Vec<CoverageReport>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DetectedTestFramework {
+    pub name: String,
+    pub category: String, // "unit", "e2e"
+    pub confidence: f64,
+    pub indicators: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestFileCount {
+    pub language: String,
+    pub test_files: usize,
+    pub source_files: usize,
+    pub test_ratio: f64, // test files per source file
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoverageReport {
+    pub file: String,
+    pub format: String, // "lcov", "cobertura"
+    pub lines_covered: u64,
+    pub lines_total: u64,
+    pub line_percent: f64,
+    pub branches_covered: Option<u64>,
+    pub branches_total: Option<u64>,
+}
+
+impl TestingProfile {
+    pub fn has_tests(&self) -> bool {
+        !self.frameworks.is_empty() || self.file_counts.iter().any(|c| c.test_files > 0)
+    }
+}
+
+fn percent(covered: u64, total: u64) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+    (covered as f64 / total as f64 * 1000.0).round() / 10.0
+}
+
+/// Test file by naming convention or by living in a test directory
+pub fn is_test_file(file: &str) -> bool {
+    let name = file_name(file);
+    let stem = name.split('.').next().unwrap_or(name);
+    let in_test_dir = file
+        .split('/')
+        .rev()
+        .skip(1)
+        .any(|segment| TEST_DIRECTORIES.contains(&segment));
+
+    in_test_dir
+        || name.contains(".test.")
+        || name.contains(".spec.")
+        || name.contains(".cy.")
+        || name.ends_with("_test.go")
+        || (name.ends_with(".py") && (stem.starts_with("test_") || stem.ends_with("_test")))
+        || (name.ends_with(".php") && stem.ends_with("Test"))
+        || (name.ends_with(".java") && stem.ends_with("Test"))
+        || (name.ends_with(".kt") && stem.ends_with("Test"))
+        || name.ends_with("_spec.rb")
+}
+
+/// Test directories closest to the project root; nested test dirs are not repeated
+fn test_directories(project_files: &[String]) -> Vec<String> {
+    let mut directories = BTreeSet::new();
+    for file in project_files {
+        let segments: Vec<&str> = file.split('/').collect();
+        if let Some(position) = segments[..segments.len() - 1]
+            .iter()
+            .position(|s| TEST_DIRECTORIES.contains(s))
+        {
+            directories.insert(segments[..=position].join("/"));
+        }
+    }
+    directories.into_iter().collect()
+}
+
+fn frameworks_from_matches(matches: &[RuleMatch]) -> Vec<DetectedTestFramework> {
+    let mut frameworks: Vec<DetectedTestFramework> = matches
+        .iter()
+        .filter(|m| m.kind == "testing")
+        .map(|m| DetectedTestFramework {
+            name: m.name.clone(),
+            category: m.category.clone().unwrap_or_else(|| "unit".to_string()),
+            confidence: m.confidence,
+            indicators: m.indicators.clone(),
+        })
+        .collect();
+    frameworks.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+    frameworks
+}
+
+// ============================================================================
+// COVERAGE REPORTS
+// ============================================================================
+
+fn coverage_format(file: &str) -> Option<&'static str> {
+    let name = file_name(file);
+    if name == "lcov.info" || name.ends_with(".lcov") {
+        Some("lcov")
+    } else if name == "cobertura.xml" || name == "cobertura-coverage.xml" || name == "coverage.xml" {
+        Some("cobertura")
+    } else {
+        None
+    }
+}
+
+/// Sum `LF`/`LH` and `BRF`/`BRH` records over every source file in an lcov report
+fn parse_lcov(file: &str, reader: impl BufRead) -> Option<CoverageReport> {
+    let (mut lines_total, mut lines_covered, mut branches_total, mut branches_covered) = (0, 0, 0, 0);
+    let mut has_branches = false;
+    for line in reader.lines().map_while(Result::ok) {
+        let Some((record, value)) = line.split_once(':') else {
+            continue;
+        };
+        let Ok(value) = value.trim().parse::<u64>() else {
+            continue;
+        };
+        match record {
+            "LF" => lines_total += value,
+            "LH" => lines_covered += value,
+            "BRF" => {
+                branches_total += value;
+                has_branches = true;
+            }
+            "BRH" => branches_covered += value,
+            _ => {}
+        }
+    }
+    (lines_total > 0).then(|| CoverageReport {
+        file: file.to_string(),
+        format: "lcov".to_string(),
+        lines_covered,
+        lines_total,
+        line_percent: percent(lines_covered, lines_total),
+        branches_covered: has_branches.then_some(branches_covered),
+        branches_total: has_branches.then_some(branches_total),
+    })
+}
+
+/// Totals from the attributes of the root `<coverage>` element
+fn parse_cobertura(file: &str, header: &str) -> Option<CoverageReport> {
+    static ATTRIBUTE: OnceLock<Regex> = OnceLock::new();
+    let attribute = ATTRIBUTE.get_or_init(|| Regex::new(r#"([\w-]+)="([^"]*)""#).unwrap());
+
+    let start = header.find("<coverage")?;
+    let tag = &header[start..start + header[start..].find('>')?];
+    let attributes: BTreeMap<&str, &str> = attribute
+        .captures_iter(tag)
+        .map(|c| (c.get(1).unwrap().as_str(), c.get(2).unwrap().as_str()))
+        .collect();
+    let number = |key: &str| attributes.get(key).and_then(|v| v.parse::<u64>().ok());
+    let rate = |key: &str| attributes.get(key).and_then(|v| v.parse::<f64>().ok());
+
+    let lines_total = number("lines-valid").unwrap_or(0);
+    let lines_covered = number("lines-covered").unwrap_or(0);
+    // Older reports only carry rates
+    let line_percent = if lines_total > 0 {
+        percent(lines_covered, lines_total)
+    } else {
+        (rate("line-rate")? * 1000.0).round() / 10.0
+    };
+    let branches_total = number("branches-valid");
+
+    Some(CoverageReport {
+        file: file.to_string(),
+        format: "cobertura".to_string(),
+        lines_covered,
+        lines_total,
+        line_percent,
+        branches_covered: branches_total.and(number("branches-covered")),
+        branches_total,
+    })
+}
+
+fn read_coverage(project_path: &Path, file: &str) -> Option<CoverageReport> {
+    let handle = File::open(project_path.join(file)).ok()?;
+    match coverage_format(file)? {
+        "lcov" => parse_lcov(file, BufReader::new(handle)),
+        _ => {
+            let mut header = String::new();
+            handle.take(COBERTURA_HEADER_BYTES).read_to_string(&mut header).ok()?;
+            parse_cobertura(file, &header)
+        }
+    }
+}
+
+/// Build the testing section from rule matches and the scanned files.
+///
+/// `source_files` pairs each project-relative source file with its language.
+pub fn detect_testing(
+    project_path: &Path,
+    project_files: &[String],
+    source_files: &[(String, String)],
+    matches: &[RuleMatch],
+) -> TestingProfile {
+    let mut counts: BTreeMap<&str, (usize, usize)> = BTreeMap::new();
+    for (file, language) in source_files {
+        let (tests, sources) = counts.entry(language.as_str()).or_default();
+        if is_test_file(file) {
+            *tests += 1;
+        } else {
+            *sources += 1;
+        }
+    }
+    let file_counts = counts
+        .into_iter()
+        .map(|(language, (test_files, source_files))| TestFileCount {
+            language: language.to_string(),
+            test_files,
+            source_files,
+            test_ratio: if source_files == 0 {
+                0.0
+            } else {
+                (test_files as f64 / source_files as f64 * 100.0).round() / 100.0
+            },
+        })
+        .collect();
+
+    let coverage_reports = project_files
+        .iter()
+        .filter(|f| coverage_format(f).is_some())
+        .filter_map(|f| read_coverage(project_path, f))
+        .collect();
+
+    TestingProfile {
+        frameworks: frameworks_from_matches(matches),
+        test_directories: test_directories(project_files),
+        file_counts,
+        coverage_reports,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_test_file_conventions() {
+        for file in ["src/lib/a.test.ts", "tests/integration.rs", "pkg/db/db_test.go", "app/test_models.py", "tests/Unit/UserTest.php", "e2e/login.ts"] {
+            assert!(is_test_file(file), "{}", file);
+        }
+        for file in ["src/lib/testing.ts", "src/main.rs", "pkg/db/db.go", "app/contest.py"] {
+            assert!(!is_test_file(file), "{}", file);
+        }
+
+        let files: Vec<String> = ["tests/a.rs", "tests/fixtures/b.json", "web/e2e/login.spec.ts", "src/main.rs"]
+            .iter()
+            .map(|f| f.to_string())
+            .collect();
+        assert_eq!(test_directories(&files), vec!["tests".to_string(), "web/e2e".to_string()]);
+    }
+
+    #[test]
+    fn test_coverage_report_totals() {
+        let lcov = "TN:\nSF:src/a.ts\nLF:10\nLH:8\nBRF:4\nBRH:2\nend_of_record\nSF:src/b.ts\nLF:10\nLH:4\nend_of_record\n";
+        let report = parse_lcov("coverage/lcov.info", lcov.as_bytes()).unwrap();
+        assert_eq!((report.lines_covered, report.lines_total, report.line_percent), (12, 20, 60.0));
+        assert_eq!((report.branches_covered, report.branches_total), (Some(2), Some(4)));
+
+        let cobertura = r#"<?xml version="1.0" ?>
+<coverage version="7.4" timestamp="1" lines-valid="200" lines-covered="173" line-rate="0.865" branches-covered="0" branches-valid="0" branch-rate="0">"#;
+        let report = parse_cobertura("coverage.xml", cobertura).unwrap();
+        assert_eq!((report.lines_covered, report.lines_total, report.line_percent), (173, 200, 86.5));
+    }
+}
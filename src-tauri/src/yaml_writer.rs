@@ -0,0 +1,232 @@
+//! YAML Writer - Ordered YAML documents for generated configuration
+//!
+//! Deployment manifests, compose files and CI workflows are built as a
+//! [`Yaml`] tree and serialized here, so keys keep the order a reader expects
+//! and every scalar is quoted where YAML would otherwise reinterpret it. The
+//! same tree answers the lookups generators use to check a document before
+//! writing it.
+
+// ============================================================================
+// DOCUMENT
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Yaml {
+    Str(String),
+    Int(i64),
+    Bool(bool),
+    List(Vec<Yaml>),
+    Map(Vec<(String, Yaml)>),
+}
+
+pub fn str(value: impl Into<String>) -> Yaml {
+    Yaml::Str(value.into())
+}
+
+pub fn int(value: i64) -> Yaml {
+    Yaml::Int(value)
+}
+
+pub fn list(items: impl IntoIterator<Item = Yaml>) -> Yaml {
+    Yaml::List(items.into_iter().collect())
+}
+
+pub fn map<K: Into<String>>(entries: impl IntoIterator<Item = (K, Yaml)>) -> Yaml {
+    Yaml::Map(entries.into_iter().map(|(key, value)| (key.into(), value)).collect())
+}
+
+impl Yaml {
+    /// Add or replace a key; no-op on anything but a map
+    pub fn insert(&mut self, key: &str, value: Yaml) {
+        if let Yaml::Map(entries) = self {
+            match entries.iter_mut().find(|(k, _)| k == key) {
+                Some(entry) => entry.1 = value,
+                None => entries.push((key.to_string(), value)),
+            }
+        }
+    }
+
+    /// Value at a dotted path; numeric segments index lists (`services.0.name`)
+    pub fn get(&self, path: &str) -> Option<&Yaml> {
+        path.split('.').try_fold(self, |node, segment| match node {
+            Yaml::Map(entries) => entries.iter().find(|(k, _)| k == segment).map(|(_, v)| v),
+            Yaml::List(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+            _ => None,
+        })
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Yaml::Str(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Error naming the first of `paths` that is missing
+    pub fn require(&self, document: &str, paths: &[&str]) -> Result<(), String> {
+        match paths.iter().find(|path| self.get(path).is_none()) {
+            Some(path) => Err(format!("{}: missing required key '{}'", document, path)),
+            None => Ok(()),
+        }
+    }
+
+    pub fn to_yaml(&self) -> String {
+        let mut out = String::new();
+        match self {
+            Yaml::Map(entries) if !entries.is_empty() => write_map(&mut out, entries, 0),
+            Yaml::List(items) if !items.is_empty() => write_list(&mut out, items, 0),
+            scalar => {
+                out.push_str(&inline(scalar));
+                out.push('\n');
+            }
+        }
+        out
+    }
+}
+
+// ============================================================================
+// SERIALIZATION
+// ============================================================================
+
+fn write_map(out: &mut String, entries: &[(String, Yaml)], indent: usize) {
+    for (i, (key, value)) in entries.iter().enumerate() {
+        // A map inside a list item continues the `- ` line
+        if i > 0 || indent == 0 || !out.ends_with("- ") {
+            out.push_str(&" ".repeat(indent));
+        }
+        out.push_str(&scalar(key));
+        out.push(':');
+        write_value(out, value, indent);
+    }
+}
+
+fn write_list(out: &mut String, items: &[Yaml], indent: usize) {
+    for item in items {
+        out.push_str(&" ".repeat(indent));
+        out.push_str("- ");
+        match item {
+            Yaml::Map(entries) if !entries.is_empty() => write_map(out, entries, indent + 2),
+            Yaml::List(nested) if !nested.is_empty() => {
+                out.push('\n');
+                write_list(out, nested, indent + 2);
+            }
+            Yaml::Str(text) if text.contains('\n') => write_block(out, text, indent + 2),
+            value => {
+                out.push_str(&inline(value));
+                out.push('\n');
+            }
+        }
+    }
+}
+
+/// Value after `key:` at `indent`
+fn write_value(out: &mut String, value: &Yaml, indent: usize) {
+    match value {
+        Yaml::Map(entries) if !entries.is_empty() => {
+            out.push('\n');
+            write_map(out, entries, indent + 2);
+        }
+        Yaml::List(items) if !items.is_empty() => {
+            out.push('\n');
+            write_list(out, items, indent + 2);
+        }
+        Yaml::Str(text) if text.contains('\n') => {
+            out.push(' ');
+            write_block(out, text, indent + 2);
+        }
+        value => {
+            out.push(' ');
+            out.push_str(&inline(value));
+            out.push('\n');
+        }
+    }
+}
+
+/// Literal block scalar, keeping line breaks as written
+fn write_block(out: &mut String, text: &str, indent: usize) {
+    out.push_str(if text.ends_with('\n') { "|\n" } else { "|-\n" });
+    for line in text.trim_end_matches('\n').lines() {
+        if !line.is_empty() {
+            out.push_str(&" ".repeat(indent));
+            out.push_str(line);
+        }
+        out.push('\n');
+    }
+}
+
+fn inline(value: &Yaml) -> String {
+    match value {
+        Yaml::Str(text) => scalar(text),
+        Yaml::Int(number) => number.to_string(),
+        Yaml::Bool(flag) => flag.to_string(),
+        Yaml::List(_) => "[]".to_string(),
+        Yaml::Map(_) => "{}".to_string(),
+    }
+}
+
+/// Plain scalar when YAML reads it back as the same string, double-quoted otherwise
+fn scalar(text: &str) -> String {
+    const RESERVED: &[&str] = &["true", "false", "yes", "no", "on", "off", "null", "~", "y", "n"];
+    let needs_quotes = text.is_empty()
+        || text.trim() != text
+        || RESERVED.contains(&text.to_lowercase().as_str())
+        || text.parse::<f64>().is_ok()
+        // YAML 1.1 reads 8080:80 as a base-60 number
+        || text.chars().all(|c| c.is_ascii_digit() || ":._+-".contains(c))
+        || text.starts_with(|c: char| "-?:,[]{}#&*!|>'\"%@`".contains(c))
+        || text.contains(": ")
+        || text.contains(" #")
+        || text.ends_with(':')
+        || text.chars().any(|c| c.is_control());
+    if !needs_quotes {
+        return text.to_string();
+    }
+    let mut quoted = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize_nested_documents() {
+        let mut doc = map([
+            ("name", str("ci")),
+            ("on", map([("push", map([("branches", list([str("main")]))]))])),
+            (
+                "steps",
+                list([
+                    map([("uses", str("actions/checkout@v4"))]),
+                    map([("name", str("Test")), ("run", str("npm ci\nnpm test\n"))]),
+                ]),
+            ),
+            ("ports", list([str("8080:80")])),
+            ("replicas", int(2)),
+            ("empty", map::<&str>([])),
+        ]);
+        doc.insert("version", str("3.10"));
+
+        assert_eq!(
+            doc.to_yaml(),
+            "name: ci\n\"on\":\n  push:\n    branches:\n      - main\nsteps:\n  - uses: actions/checkout@v4\n  - name: Test\n    run: |\n      npm ci\n      npm test\nports:\n  - \"8080:80\"\nreplicas: 2\nempty: {}\nversion: \"3.10\"\n"
+        );
+        assert_eq!(doc.get("steps.1.name").and_then(Yaml::as_str), Some("Test"));
+        assert!(doc.require("ci.yml", &["on.push.branches", "steps.0.uses"]).is_ok());
+        assert_eq!(doc.require("ci.yml", &["jobs"]), Err("ci.yml: missing required key 'jobs'".to_string()));
+    }
+}
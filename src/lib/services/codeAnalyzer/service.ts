@@ -4,7 +4,10 @@
  */
 
 import type {
+  AnalysisProgressEvent,
   AnalysisResult,
+  AnalyzerOptions,
+  ProfileConfigMapping,
   ProjectProfile,
   DetectedLanguage,
   DetectedFramework,
@@ -15,14 +18,17 @@ import { ALL_STACKS } from "$lib/data/stack-profiles";
 // Conditionally import Tauri APIs (only available in Tauri environment)
 type TauriInvoke = <T>(cmd: string, args?: Record<string, unknown>) => Promise<T>;
 type TauriOpen = (path: string) => Promise<void>;
+type TauriListen = <T>(event: string, handler: (event: { payload: T }) => void) => Promise<() => void>;
 let invoke: TauriInvoke = () => Promise.reject(new Error("Tauri not available"));
 let open: TauriOpen = () => Promise.reject(new Error("Tauri not available"));
+let listen: TauriListen = () => Promise.reject(new Error("Tauri not available"));
 
 if (typeof window !== "undefined" && "__TAURI__" in window) {
   try {
     // Dynamic imports with variables to bypass Vite static analysis
     const tauriModule = "@tauri-apps/api/tauri";
     const dialogModule = "@tauri-apps/api/dialog";
+    const eventModule = "@tauri-apps/api/event";
     import(/* @vite-ignore */ tauriModule)
       .then((module) => {
         invoke = module.invoke;
@@ -33,6 +39,11 @@ if (typeof window !== "undefined" && "__TAURI__" in window) {
         open = module.open;
       })
       .catch(() => {});
+    import(/* @vite-ignore */ eventModule)
+      .then((module) => {
+        listen = module.listen;
+      })
+      .catch(() => {});
   } catch (e) {
     // Tauri not available in browser
   }
@@ -80,10 +91,16 @@ export class CodeAnalyzerService {
   /**
    * Analyze specific directory path (for testing or programmatic use)
    */
-  async analyzeDirectory(path: string): Promise<AnalysisResult> {
+  async analyzeDirectory(
+    path: string,
+    options?: AnalyzerOptions,
+    analysisId?: string
+  ): Promise<AnalysisResult> {
     try {
       const result = await invoke<AnalysisResult>("analyze_codebase", {
         projectPath: path,
+        options,
+        analysisId,
       });
       return result;
     } catch (error) {
@@ -97,6 +114,35 @@ export class CodeAnalyzerService {
     }
   }
 
+  /**
+   * Listen to analysis-progress events, optionally for a single analysis id
+   */
+  async listenToProgress(
+    callback: (event: AnalysisProgressEvent) => void,
+    analysisId?: string
+  ): Promise<() => void> {
+    return listen<AnalysisProgressEvent>("analysis-progress", (event) => {
+      if (!analysisId || event.payload.analysisId === analysisId) {
+        callback(event.payload);
+      }
+    });
+  }
+
+  /**
+   * Cancel an in-flight analysis; resolves false if no analysis has that id
+   */
+  async cancelAnalysis(analysisId: string): Promise<boolean> {
+    return invoke<boolean>("cancel_analysis", { analysisId });
+  }
+
+  /**
+   * Map a ProjectProfile to generator input (ProjectConfig and pattern
+   * components) with per-field provenance
+   */
+  async profileToConfig(profile: ProjectProfile): Promise<ProfileConfigMapping> {
+    return invoke<ProfileConfigMapping>("profile_to_config_command", { profile });
+  }
+
   /**
    * Convert ProjectProfile to WizardData
    * Maps detected project to wizard configuration
@@ -169,8 +215,9 @@ export class CodeAnalyzerService {
     }
 
     // Database
-    if (profile.database) {
-      parts.push(`with ${profile.database.type} database`);
+    const database = profile.dataStores.find((s) => s.kind === "database");
+    if (database) {
+      parts.push(`with ${database.type} database`);
     }
 
     return parts.join(" ") || "Existing codebase";
@@ -220,12 +267,12 @@ export class CodeAnalyzerService {
     if (profile.hasCI) features.push("ci-cd");
     if (profile.hasTests) features.push("testing");
     if (profile.authentication) features.push("authentication");
-    if (profile.database) features.push("database");
+    if (profile.dataStores.some((s) => s.kind === "database")) features.push("database");
+    if (profile.orms.length > 0) features.push("orm");
 
     // Check for specific features in frameworks
     for (const framework of profile.frameworks) {
       if (framework.name === "TailwindCSS") features.push("tailwind");
-      if (framework.name === "Prisma") features.push("orm");
       if (framework.name === "tRPC") features.push("type-safety");
       if (framework.name === "GraphQL") features.push("graphql");
     }
@@ -237,9 +284,10 @@ export class CodeAnalyzerService {
    * Get confidence score for stack match
    */
   getStackMatchConfidence(profile: ProjectProfile, stackId: string): number {
-    // If analyzer already matched, use that confidence
-    if (profile.suggestedStackId === stackId && profile.stackMatchConfidence) {
-      return profile.stackMatchConfidence;
+    // The analyzer scores every stack it can generate
+    const match = profile.stackMatches.find((m) => m.stackId === stackId);
+    if (match) {
+      return match.score;
     }
 
     // Calculate manual confidence
@@ -300,14 +348,35 @@ export class CodeAnalyzerService {
       lines.push(...profile.frameworks.slice(0, 5).map((f) => `  • ${f.name}`));
     }
 
-    if (profile.database) {
-      lines.push("", `**Database:** ${profile.database.type}`);
+    const databases = profile.dataStores.filter((s) => s.kind === "database");
+    if (databases.length > 0) {
+      lines.push("", `**Database:** ${databases.map((d) => d.type).join(", ")}`);
+    }
+
+    if (profile.orms.length > 0) {
+      lines.push("", `**ORM:** ${profile.orms.map((o) => o.name).join(", ")}`);
     }
 
     if (profile.authentication) {
       lines.push("", `**Authentication:** ${profile.authentication.method}`);
     }
 
+    if (profile.ci.providers.length > 0) {
+      lines.push("", `**CI/CD:** ${profile.ci.providers.join(", ")}`);
+    }
+
+    if (profile.suggestedDeploymentPlatform) {
+      lines.push("", `**Deployment:** ${profile.suggestedDeploymentPlatform}`);
+    }
+
+    const serious = profile.findings.filter(
+      (f) => f.severity === "critical" || f.severity === "high",
+    );
+    if (serious.length > 0) {
+      lines.push("", "**Findings:**");
+      lines.push(...serious.slice(0, 5).map((f) => `  • ${f.title}`));
+    }
+
     const projectType = [];
     if (profile.hasFrontend) projectType.push("Frontend");
     if (profile.hasBackend) projectType.push("Backend");
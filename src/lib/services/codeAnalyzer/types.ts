@@ -3,6 +3,8 @@
  * For analyzing existing codebases and detecting project structure
  */
 
+import type { FeatureFlags } from "$lib/workbench/types/scaffolding";
+
 export interface DetectedLanguage {
   id: string;
   name: string;
@@ -23,6 +25,7 @@ export interface DetectedFramework {
     | "build";
   confidence: number;
   indicators: string[]; // Files/patterns that indicated this framework
+  sources: string[]; // Manifests or files the framework was found in
 }
 
 export interface DetectedDependency {
@@ -32,18 +35,189 @@ export interface DetectedDependency {
   source: string; // package.json, requirements.txt, etc.
 }
 
-export interface DetectedDatabase {
-  type: "postgresql" | "mongodb" | "mysql" | "sqlite" | "redis" | "unknown";
+export interface DetectedDataStore {
+  kind: "database" | "broker";
+  type: string; // postgresql, mongodb, redis, kafka, rabbitmq, ...
+  category?: "sql" | "document" | "key-value" | null;
+  confidence: number;
+  indicators: string[];
+  sources: string[]; // Manifests, compose files or env files the store was found in
+}
+
+export interface DetectedOrm {
+  name: string;
+  category: "orm" | "odm" | "query-builder";
+  version?: string;
   confidence: number;
   indicators: string[];
 }
 
+export interface DetectedMigrations {
+  path: string;
+  tool?: string; // prisma, alembic, diesel, sqlx, ...
+  fileCount: number;
+}
+
 export interface DetectedAuthentication {
   method: "jwt" | "session" | "oauth" | "none" | "unknown";
   confidence: number;
   indicators: string[];
 }
 
+export interface CiJob {
+  name: string;
+  stages: CiStage[];
+}
+
+export type CiStage = "lint" | "test" | "build" | "deploy";
+
+export interface CiWorkflow {
+  provider: string; // github-actions, gitlab-ci, jenkins, circleci, ...
+  file: string;
+  jobs: CiJob[];
+  stages: CiStage[];
+}
+
+export interface CiProfile {
+  providers: string[];
+  workflows: CiWorkflow[];
+  stages: CiStage[];
+}
+
+export interface DetectedIac {
+  tool: "terraform" | "pulumi" | "helm" | "kubernetes";
+  files: string[];
+  details: string[]; // Terraform providers, chart names, manifest kinds, Pulumi runtime
+}
+
+export interface DockerBaseImage {
+  file: string;
+  image: string;
+  tag?: string;
+  stage?: string;
+}
+
+export interface DeploymentPlatformSuggestion {
+  platform: string; // vercel, netlify, fly, docker, kubernetes, ...
+  confidence: number;
+  indicators: string[];
+}
+
+export interface InfrastructureProfile {
+  iac: DetectedIac[];
+  dockerImages: DockerBaseImage[];
+  platformFiles: string[]; // fly.toml, vercel.json, netlify.toml, ...
+  deploymentPlatforms: DeploymentPlatformSuggestion[];
+}
+
+export interface DetectedTestFramework {
+  name: string; // Vitest, Playwright, pytest, cargo test, go test, PHPUnit, ...
+  category: "unit" | "e2e";
+  confidence: number;
+  indicators: string[];
+}
+
+export interface TestFileCount {
+  language: string;
+  testFiles: number;
+  sourceFiles: number;
+  testRatio: number; // test files per source file
+}
+
+export interface CoverageReport {
+  file: string;
+  format: "lcov" | "cobertura";
+  linesCovered: number;
+  linesTotal: number;
+  linePercent: number;
+  branchesCovered?: number;
+  branchesTotal?: number;
+}
+
+export interface TestingProfile {
+  frameworks: DetectedTestFramework[];
+  testDirectories: string[];
+  fileCounts: TestFileCount[];
+  coverageReports: CoverageReport[];
+}
+
+export interface EntryPoint {
+  name: string;
+  file: string;
+  language: string;
+  kind: "binary" | "server" | "management";
+}
+
+export interface HttpRoute {
+  method: string; // GET, POST, ... or ANY
+  path: string; // parameters normalized to :name, catch-alls to *name
+  kind: "page" | "endpoint";
+  framework: string;
+  file: string;
+  line?: number;
+}
+
+export interface ArchitectureComponent {
+  id: string;
+  kind: "app" | "service" | "database" | "cache" | "broker" | "proxy";
+  source: "compose" | "entry-point";
+  path?: string;
+  image?: string;
+}
+
+export interface ComponentConnection {
+  from: string;
+  to: string;
+  kind: "depends_on" | "link" | "network";
+  evidence: string;
+}
+
+export interface ArchitectureProfile {
+  entryPoints: EntryPoint[];
+  routes: HttpRoute[];
+  components: ArchitectureComponent[];
+  connections: ComponentConnection[];
+  diagram: string; // Mermaid flowchart
+}
+
+export interface ModuleMetrics {
+  module: string; // file path; package directory for Go
+  fanIn: number;
+  fanOut: number;
+}
+
+export interface ImportEdge {
+  from: string;
+  to: string;
+}
+
+export interface ImportGraph {
+  language: "javascript-typescript" | "python" | "rust" | "go";
+  modules: ModuleMetrics[];
+  edges: ImportEdge[];
+  cycles: string[][];
+  orphans: string[];
+}
+
+export interface StackMatch {
+  stackId: string;
+  name: string;
+  score: number;
+  matched: string[]; // "framework: Next.js", "dependency: @trpc/server", ...
+  missing: string[];
+}
+
+export type FindingSeverity = "critical" | "high" | "medium" | "low";
+
+export interface Finding {
+  id: string; // committed-env-file, hardcoded-secret, private-key, missing-lockfile, ...
+  severity: FindingSeverity;
+  title: string;
+  file?: string;
+  line?: number;
+  remediation: string;
+}
+
 export interface ProjectProfile {
   // Basic metadata
   projectName: string;
@@ -65,16 +239,94 @@ export interface ProjectProfile {
   hasFrontend: boolean;
   hasMobile: boolean;
   hasTests: boolean;
+  testing: TestingProfile;
+
+  // Data layer (databases first, strongest match first)
+  dataStores: DetectedDataStore[];
+  orms: DetectedOrm[];
+  migrations: DetectedMigrations[];
 
-  // Infrastructure
-  database?: DetectedDatabase;
+  // Infrastructure (single field holds the strongest match)
   authentication?: DetectedAuthentication;
+  authenticationMethods: DetectedAuthentication[];
   hasDocker: boolean;
   hasCI: boolean;
+  ci: CiProfile;
+  infrastructure: InfrastructureProfile;
+  architecture: ArchitectureProfile;
+  importGraphs: ImportGraph[];
+  findings: Finding[];
+  suggestedDeploymentPlatform?: string;
 
   // Matched stack (if any)
   suggestedStackId?: string;
   stackMatchConfidence?: number;
+  stackMatches: StackMatch[]; // every generator stack, best first
+}
+
+// ============================================================================
+// PROFILE TO CONFIG MAPPING (snake_case, as serialized by the generators)
+// ============================================================================
+
+export interface GeneratorProjectConfig {
+  name: string;
+  description: string;
+  project_type: string;
+  languages: string[];
+  stack_id: string; // Empty when no stack matched
+  database: string | null;
+  authentication: string | null;
+  deployment_platform: string | null;
+  environment_variables: Record<string, string>;
+  features: string[]; // testing, linting, git, docker, ci
+  modules?: string[]; // Stack module ids composed into a monorepo instead of stack_id
+  ci_provider?: string | null; // github-actions, gitlab-ci, gitea-actions, forgejo-actions; GitHub Actions when unset
+}
+
+/** A composable stack module, as returned by `list_stack_modules` */
+export interface GeneratorStackModule {
+  id: string;
+  name: string;
+  kind: 'frontend' | 'backend' | 'mobile' | 'infra';
+  language: string;
+  port: number; // 0 when the module is not a service
+  env: [string, string][];
+  conflicts_with: string[];
+}
+
+export interface GeneratorComponentConfig {
+  id: string;
+  role: string;
+  name: string;
+  language: string;
+  framework: string;
+  location: string;
+  scaffolding: { directories: unknown[]; files: unknown[] };
+  custom_config: Record<string, unknown> | null;
+}
+
+export interface GeneratorPatternConfig {
+  pattern_id: string;
+  pattern_name: string;
+  project_name: string;
+  project_description: string;
+  project_path: string; // Always empty; the user picks the output directory
+  components: GeneratorComponentConfig[];
+  features: FeatureFlags;
+}
+
+export interface FieldProvenance {
+  field: string; // e.g. "database", "features.docker", "components.backend"
+  source: "detected" | "inferred" | "default";
+  confidence: number | null;
+  evidence: string[];
+}
+
+export interface ProfileConfigMapping {
+  projectConfig: GeneratorProjectConfig;
+  patternConfig: GeneratorPatternConfig;
+  provenance: FieldProvenance[];
+  warnings: string[];
 }
 
 export interface AnalysisResult {
@@ -83,6 +335,48 @@ export interface AnalysisResult {
   error?: string;
   analysisTimeMs: number;
   filesScanned: number;
+  cacheStats?: CacheStats; // Absent when the analysis never reached the backend
+  scanStats?: ScanStats;
+}
+
+export interface ScanStats {
+  truncated: boolean; // The maxFiles budget was reached and some files were not listed
+  maxFiles: number;
+  maxFileSizeBytes: number;
+  largeFilesSkipped: number; // Listed but not read for line counts and imports
+  binaryFilesSkipped: number;
+}
+
+export type AnalysisPhase =
+  | "scan"
+  | "dependencies"
+  | "frameworks"
+  | "recommendations"
+  | "complete"
+  | "cancelled";
+
+/** Payload of `analysis-progress`, emitted by analyze_codebase and analyze_project_runtime */
+export interface AnalysisProgressEvent {
+  analysisId: string;
+  phase: AnalysisPhase;
+  directoriesVisited: number;
+  filesScanned: number;
+  message: string;
+}
+
+/** Scan limits passed to `analyze_codebase`; omitted fields use backend defaults */
+export interface AnalyzerOptions {
+  maxDepth?: number;
+  maxFiles?: number;
+  maxFileSizeBytes?: number;
+}
+
+export interface CacheStats {
+  enabled: boolean;
+  hits: number; // Files whose line counts and imports came from the cache
+  misses: number; // Files read because they were new or changed
+  cacheFile?: string;
+  saveError?: string; // Set when the cache file could not be written
 }
 
 export interface AnalyzerConfig {
@@ -0,0 +1,58 @@
+/**
+ * Pattern Catalog Service Test Suite
+ *
+ * Checks how backend catalog patterns are turned into wizard patterns.
+ */
+
+import { describe, it, expect } from 'vitest';
+import { toArchitecturePattern } from '../patternCatalog';
+import type { CatalogPattern } from '../../types/architecture';
+
+const worker = {
+	id: 'worker',
+	role: 'backend',
+	name: 'Worker',
+	language: 'python',
+	framework: 'celery',
+	location: 'worker',
+	depends_on: ['api'],
+	scaffolding: {
+		directories: [{ path: 'tasks', description: null }],
+		files: [{ path: 'main.py', content: '', template_engine: 'none', overwritable: false }]
+	}
+};
+
+describe('toArchitecturePattern', () => {
+	it('keeps the registry presentation for known patterns', () => {
+		const entry: CatalogPattern = {
+			id: 'desktop-app',
+			name: 'Desktop (custom)',
+			description: '',
+			category: 'desktop',
+			components: [worker]
+		};
+		const pattern = toArchitecturePattern(entry);
+
+		expect(pattern.displayName).toBe('Desktop (custom)');
+		expect(pattern.description).not.toBe('');
+		expect(pattern.icon).not.toBe('🧩');
+		expect(pattern.components.map(c => c.id)).toEqual(['worker']);
+		expect(pattern.components[0].dependencies).toEqual([{ componentId: 'api', type: 'required' }]);
+		expect(pattern.components[0].scaffolding.files[0].templateEngine).toBe('none');
+	});
+
+	it('fills defaults for patterns only the catalog has', () => {
+		const pattern = toArchitecturePattern({
+			id: 'job-queue',
+			name: 'Job Queue',
+			description: 'Workers behind a queue',
+			category: 'unknown',
+			components: [worker]
+		});
+
+		expect(pattern.id).toBe('job-queue');
+		expect(pattern.category).toBe('web');
+		expect(pattern.tags).toContain('custom');
+		expect(pattern.components[0].scaffolding.directories).toEqual([{ path: 'tasks', description: undefined }]);
+	});
+});
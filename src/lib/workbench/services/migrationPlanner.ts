@@ -0,0 +1,44 @@
+/**
+ * Migration Planner Service
+ *
+ * Asks the backend for the ordered steps that move an analyzed project towards
+ * a catalog pattern. Planning only reads the project; nothing is moved.
+ *
+ * Browser Mode: Planning needs the project on disk, so it is only available in Tauri.
+ */
+
+import { browser } from '$app/environment';
+import type { MigrationPlan, MigrationRequest } from '../types/runtime-detection';
+
+// Conditionally import Tauri APIs (only available in Tauri context)
+let invoke: <T>(cmd: string, args?: Record<string, unknown>) => Promise<T>;
+
+if (browser && (window as any).__TAURI__) {
+	import('@tauri-apps/api/core').then(module => {
+		invoke = module.invoke;
+	});
+}
+
+const isTauriMode = browser && (window as any).__TAURI__;
+
+// ============================================================================
+// TAURI COMMANDS
+// ============================================================================
+
+/**
+ * Plan the migration of `request.projectPath` towards `request.patternId`
+ */
+export async function planMigration(request: MigrationRequest): Promise<MigrationPlan> {
+	if (!isTauriMode) {
+		throw new Error('Migration planning is only available in the desktop app');
+	}
+
+	try {
+		return await invoke<MigrationPlan>('plan_migration', { request });
+	} catch (error) {
+		console.error('Migration planning error:', error);
+		throw new Error(
+			`Failed to plan migration: ${error instanceof Error ? error.message : String(error)}`
+		);
+	}
+}
@@ -0,0 +1,130 @@
+/**
+ * Pattern Catalog Service
+ *
+ * Loads the architecture patterns the backend generates: the bundled catalog
+ * merged with the user's catalog files. The catalog decides which patterns
+ * exist, their names and their components; the registry in
+ * `$lib/data/architecture-patterns` only adds presentation (icon, trade-offs,
+ * documentation) for the patterns it knows.
+ *
+ * Browser Mode: When running in browser (not Tauri), the registry is used as is.
+ */
+
+import { browser } from '$app/environment';
+import { getAllPatterns, getPattern } from '$lib/data/architecture-patterns';
+import type {
+	ArchitectureCategory,
+	ArchitecturePattern,
+	CatalogComponent,
+	CatalogPattern,
+	ComponentRole,
+	PatternCatalog,
+	ProjectComponent,
+	TemplateEngine
+} from '../types/architecture';
+
+// Conditionally import Tauri APIs (only available in Tauri context)
+let invoke: <T>(cmd: string, args?: Record<string, unknown>) => Promise<T>;
+
+if (browser && (window as any).__TAURI__) {
+	import('@tauri-apps/api/core').then(module => {
+		invoke = module.invoke;
+	});
+}
+
+const isTauriMode = browser && (window as any).__TAURI__;
+
+const CATEGORIES: ArchitectureCategory[] = ['desktop', 'web', 'mobile', 'backend', 'cli', 'ai-ml', 'microservices'];
+
+// ============================================================================
+// TAURI COMMANDS
+// ============================================================================
+
+/**
+ * Patterns in the backend catalog
+ */
+export async function listArchitecturePatterns(): Promise<CatalogPattern[]> {
+	const catalog = await invoke<PatternCatalog>('list_architecture_patterns');
+	return catalog.patterns;
+}
+
+/**
+ * Patterns for the pattern picker, in catalog order. Falls back to the
+ * registry outside Tauri or when the catalog cannot be loaded.
+ */
+export async function loadPatterns(): Promise<ArchitecturePattern[]> {
+	if (!isTauriMode) {
+		return getAllPatterns();
+	}
+
+	try {
+		return (await listArchitecturePatterns()).map(toArchitecturePattern);
+	} catch (error) {
+		console.error('Failed to load the pattern catalog:', error);
+		return getAllPatterns();
+	}
+}
+
+// ============================================================================
+// CONVERSION
+// ============================================================================
+
+/**
+ * The catalog pattern with the registry's presentation when the registry has
+ * the same id, and neutral defaults otherwise
+ */
+export function toArchitecturePattern(entry: CatalogPattern): ArchitecturePattern {
+	const known = getPattern(entry.id);
+	const category = CATEGORIES.find(c => c === entry.category) ?? known?.category ?? 'web';
+	const base: ArchitecturePattern = known ?? {
+		id: entry.id,
+		name: entry.id,
+		displayName: entry.name,
+		description: entry.description,
+		category,
+		icon: '🧩',
+		components: [],
+		integration: { protocol: 'rest-api', sharedTypes: false, sharedConfig: false },
+		complexity: 'intermediate',
+		maturity: 'stable',
+		popularity: 0,
+		idealFor: [],
+		notIdealFor: [],
+		tags: ['custom'],
+		prerequisites: { tools: [] }
+	};
+
+	return {
+		...base,
+		displayName: entry.name,
+		description: entry.description || base.description,
+		category,
+		components: entry.components.map(toProjectComponent)
+	};
+}
+
+function toProjectComponent(component: CatalogComponent): ProjectComponent {
+	return {
+		id: component.id,
+		role: component.role as ComponentRole,
+		name: component.name,
+		language: component.language,
+		framework: component.framework,
+		location: component.location,
+		dependencies: component.depends_on.map(id => ({ componentId: id, type: 'required' })),
+		scaffolding: {
+			directories: component.scaffolding.directories.map(d => ({
+				path: d.path,
+				description: d.description ?? undefined
+			})),
+			files: component.scaffolding.files.map(f => ({
+				path: f.path,
+				content: f.content,
+				templateEngine: f.template_engine as TemplateEngine,
+				overwritable: f.overwritable
+			})),
+			packageFiles: {},
+			configFiles: {}
+		}
+	};
+}
@@ -9,6 +9,8 @@
 
 import { browser } from '$app/environment';
 import type {
+	AddComponentResult,
+	ComponentConfig,
 	ScaffoldConfig,
 	ScaffoldResult,
 	ScaffoldProgressEvent
@@ -97,6 +99,61 @@ export async function generateProject(config: ScaffoldConfig): Promise<ScaffoldR
 	}
 }
 
+/**
+ * Add one component to an existing project, updating its root README,
+ * .gitignore, compose file and CI workflow in place
+ */
+export async function addComponent(
+	projectPath: string,
+	component: ComponentConfig,
+	installDependencies = true
+): Promise<AddComponentResult> {
+	try {
+		const result = await invoke<{
+			success: boolean;
+			project_path: string;
+			component_id: string;
+			message: string;
+			files_created: number;
+			updated_files: string[];
+			warnings: string[];
+		}>('add_component', {
+			request: {
+				project_path: projectPath,
+				component: {
+					id: component.id,
+					role: component.role,
+					name: component.name,
+					language: component.language,
+					framework: component.framework,
+					location: component.location,
+					scaffolding: {
+						directories: component.scaffolding.directories.map(mapDirectory),
+						files: component.scaffolding.files.map(mapFile)
+					},
+					custom_config: component.customConfig
+				},
+				install_dependencies: installDependencies
+			}
+		});
+
+		return {
+			success: result.success,
+			projectPath: result.project_path,
+			componentId: result.component_id,
+			message: result.message,
+			filesCreated: result.files_created,
+			updatedFiles: result.updated_files,
+			warnings: result.warnings
+		};
+	} catch (error) {
+		console.error('Add component error:', error);
+		throw new Error(
+			`Failed to add component: ${error instanceof Error ? error.message : String(error)}`
+		);
+	}
+}
+
 /**
  * Install dependencies for a generated project
  */
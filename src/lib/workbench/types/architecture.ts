@@ -451,3 +451,45 @@ export interface ArchitecturePattern {
 	/** Files to generate in the project root */
 	rootFiles?: FileTemplate[];
 }
+
+// ============================================================================
+// PATTERN CATALOG
+// ============================================================================
+
+/**
+ * Scaffolding of a catalog component, as serialized by the backend
+ */
+export interface CatalogScaffolding {
+	directories: { path: string; description?: string | null }[];
+	files: { path: string; content: string; template_engine: string; overwritable: boolean }[];
+}
+
+/**
+ * Component of a catalog pattern
+ */
+export interface CatalogComponent {
+	id: string;
+	role: string;
+	name: string;
+	language: string;
+	framework: string;
+	location: string;
+	depends_on: string[];
+	scaffolding: CatalogScaffolding;
+}
+
+/**
+ * Pattern from the backend catalog (`list_architecture_patterns`): the
+ * bundled patterns merged with the user's catalog files
+ */
+export interface CatalogPattern {
+	id: string;
+	name: string;
+	description: string;
+	category: string;
+	components: CatalogComponent[];
+}
+
+export interface PatternCatalog {
+	patterns: CatalogPattern[];
+}
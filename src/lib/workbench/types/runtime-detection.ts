@@ -6,6 +6,7 @@
  */
 
 import type { ArchitecturePatternId } from '$lib/data/architecture-patterns';
+import type { ArchitectureProfile, ProjectProfile } from '$lib/services/codeAnalyzer/types';
 
 // ============================================================================
 // DETECTED TECHNOLOGIES
@@ -21,7 +22,12 @@ export type DetectedLanguage =
 	| 'rust'
 	| 'go'
 	| 'java'
+	| 'kotlin'
+	| 'php'
+	| 'dart'
 	| 'csharp'
+	| 'swift'
+	| 'ruby'
 	| 'unknown';
 
 /**
@@ -32,13 +38,27 @@ export type DetectedFramework =
 	| 'svelte'
 	| 'react'
 	| 'vue'
+	| 'angular'
+	| 'solid'
 	| 'fastapi'
 	| 'express'
 	| 'nextjs'
+	| 'nuxt'
 	| 'tauri'
 	| 'electron'
+	| 'flutter'
+	| 'react-native'
 	| 'flask'
 	| 'django'
+	| 'axum'
+	| 'actix'
+	| 'rocket'
+	| 'gin'
+	| 'echo'
+	| 'fiber'
+	| 'spring-boot'
+	| 'laravel'
+	| 'symfony'
 	| 'unknown';
 
 /**
@@ -88,7 +108,7 @@ export interface TechnologyStack {
 	/** Primary programming language */
 	primaryLanguage: DetectedLanguage;
 
-	/** Additional languages found */
+	/** Additional languages found, most files first */
 	additionalLanguages: DetectedLanguage[];
 
 	/** Frontend framework (if any) */
@@ -127,8 +147,45 @@ export interface TechnologyStack {
 		dockerfile?: boolean;
 	};
 
+	/** Per-directory breakdown, one entry per directory holding a manifest or framework config */
+	directories: DirectoryStack[];
+
+	/** Confidence per technology from matched file indicators, highest first */
+	technologyConfidence: TechnologyConfidence[];
+
+	/** Mean confidence of the primary language and detected frameworks (0-100) */
+	confidence: number;
+}
+
+/**
+ * Confidence in one technology from the file indicators that matched
+ */
+export interface TechnologyConfidence {
+	technology: string;
+
 	/** Confidence score (0-100) */
 	confidence: number;
+
+	/** Matched files; negative evidence is suffixed with "(against)" */
+	indicators: string[];
+}
+
+/**
+ * Technologies attributed to one directory of the project
+ */
+export interface DirectoryStack {
+	/** Path relative to the project root, "." for the root itself */
+	path: string;
+
+	/** Manifests and framework configs found in the directory */
+	manifests: string[];
+
+	/** Languages of the source files under the directory, most files first */
+	languages: DetectedLanguage[];
+
+	frontendFramework?: DetectedFramework;
+	backendFramework?: DetectedFramework;
+	desktopFramework?: DetectedFramework;
 }
 
 // ============================================================================
@@ -136,20 +193,24 @@ export interface TechnologyStack {
 // ============================================================================
 
 /**
- * File patterns that indicate specific technologies
+ * File patterns that indicate specific technologies, as stored in
+ * `file-indicators.json` (bundled, app config dir or `<project>/.vibeforge/`)
  */
 export interface FileIndicator {
-	/** File name or glob pattern */
+	/** Glob over project-relative paths; without `/` it matches at any depth */
 	pattern: string;
 
 	/** Technology indicated by this file */
-	technology: DetectedLanguage | DetectedFramework | DetectedBuildTool | DetectedDatabase;
+	technology: DetectedLanguage | DetectedFramework | DetectedBuildTool | DetectedDatabase | string;
 
-	/** Confidence weight (higher = stronger indicator) */
+	/** Confidence weight from 1 to 10 (higher = stronger indicator, 0 disables) */
 	weight: number;
 
-	/** Whether this file must exist (vs. optional) */
-	required?: boolean;
+	/** Regex the matched file must contain */
+	contains?: string;
+
+	/** Whether a match counts against the technology */
+	negative?: boolean;
 }
 
 // ============================================================================
@@ -166,6 +227,9 @@ export interface PatternRecommendation {
 	/** Recommendation score (0-100) */
 	score: number;
 
+	/** Required criteria hold and the score reaches the minimum */
+	recommended: boolean;
+
 	/** Why this pattern was recommended */
 	reasons: string[];
 
@@ -174,6 +238,27 @@ export interface PatternRecommendation {
 
 	/** Confidence level */
 	confidence: 'high' | 'medium' | 'low';
+
+	/** Outcome of every criterion the score is built from */
+	breakdown: CriterionScore[];
+}
+
+/**
+ * How one catalog criterion contributed to a pattern's score
+ */
+export interface CriterionScore {
+	/** The reason when matched, otherwise what was expected */
+	description: string;
+
+	/** Stack field the criterion reads */
+	field: string;
+
+	weight: number;
+	required: boolean;
+	matched: boolean;
+
+	/** Values that matched */
+	evidence?: string;
 }
 
 /**
@@ -183,9 +268,12 @@ export interface RecommendationResult {
 	/** Detected technology stack */
 	techStack: TechnologyStack;
 
-	/** Recommended patterns, sorted by score (highest first) */
+	/** Every catalog pattern, recommended ones first, then by score */
 	recommendations: PatternRecommendation[];
 
+	/** Entry points, routes and component graph found in the scanned files */
+	architecture: ArchitectureProfile;
+
 	/** Analysis metadata */
 	metadata: {
 		/** Number of files scanned */
@@ -199,6 +287,61 @@ export interface RecommendationResult {
 	};
 }
 
+// ============================================================================
+// MIGRATION PLAN
+// ============================================================================
+
+/**
+ * Input for the `plan_migration` command
+ */
+export interface MigrationRequest {
+	projectPath: string;
+
+	/** Catalog pattern to migrate towards */
+	patternId: ArchitecturePatternId;
+
+	/** Stack from `analyze_project_runtime` */
+	techStack: TechnologyStack;
+
+	/** Code analyzer profile, used for CI and dependency checks when given */
+	profile?: ProjectProfile;
+}
+
+/**
+ * One step of a migration plan
+ */
+export interface MigrationStep {
+	/** Position in the plan, starting at 1 */
+	order: number;
+
+	action: 'create-directory' | 'move' | 'add-config' | 'add-dependency';
+
+	/** Pattern component the step belongs to, or "project" */
+	component?: string;
+
+	/** Moved path, or the manifest a dependency goes into */
+	source?: string;
+
+	/** Relative path, or the package for dependencies */
+	target: string;
+
+	reason: string;
+}
+
+/**
+ * Ordered plan for moving a project towards a pattern
+ */
+export interface MigrationPlan {
+	patternId: ArchitecturePatternId;
+	patternName: string;
+	projectPath: string;
+	steps: MigrationStep[];
+	warnings: string[];
+
+	/** The plan rendered for export */
+	markdown: string;
+}
+
 // ============================================================================
 // ANALYSIS OPTIONS
 // ============================================================================
@@ -221,6 +364,9 @@ export interface RuntimeAnalysisOptions {
 
 	/** Whether to read package.json/Cargo.toml contents */
 	readPackageFiles?: boolean;
+
+	/** Patterns scoring below this are reported but not recommended */
+	minRecommendationScore?: number;
 }
 
 // ============================================================================
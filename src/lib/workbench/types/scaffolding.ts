@@ -160,6 +160,25 @@ export interface ScaffoldResult {
 	components_generated?: string[];
 }
 
+export interface AddComponentResult {
+	success: boolean;
+
+	/** Existing project the component was added to */
+	projectPath: string;
+
+	componentId: string;
+	message: string;
+
+	/** Number of files created for the component */
+	filesCreated: number;
+
+	/** Root files (README, .gitignore, compose, CI) whose component section was written */
+	updatedFiles: string[];
+
+	/** Non-fatal problems, e.g. failed dependency installation */
+	warnings: string[];
+}
+
 export interface ScaffoldError {
 	/** Error stage where failure occurred */
 	stage: ScaffoldStage;